@@ -33,8 +33,16 @@ interface IPersistentOperation {
     dur: string
 }
 
+interface IDeletedTopic {
+    topicId: string;
+    messageId: number;
+    gcAfter: string;
+    purgeIn: string;
+}
+
 interface IStatus {
     topics: ITopicInfo[];
+    deletedTopics: IDeletedTopic[];
     awaitingOperations: IPersistentOperation[];
     queuesSnapshotId: number;
     activeOperations: IPersistentOperation[];
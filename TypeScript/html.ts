@@ -113,13 +113,36 @@ class HtmlRenderer {
     }
 
 
+    private static renderDeletedTopics(deletedTopics: IDeletedTopic[]): string {
+
+        if (!deletedTopics || deletedTopics.length == 0) {
+            return '';
+        }
+
+        let result = '<h4>Pending purge</h4><table class="table table-striped"><tr><th>Topic</th><th>MessageId</th><th>Purge after</th><th>Purge in</th></tr>';
+
+        for (let topic of deletedTopics) {
+            result += '<tr style="font-size: 12px">' +
+                '<td>' + topic.topicId + '</td>' +
+                '<td>' + topic.messageId + '</td>' +
+                '<td>' + topic.gcAfter + '</td>' +
+                '<td>' + topic.purgeIn + '</td>' +
+                '</tr>';
+        }
+
+        return result + '</table>';
+    }
+
+
     public static renderMainContent(r: IStatus): string {
 
+        let deletedTopics = this.renderDeletedTopics(r.deletedTopics);
+
         if (r.initialing) {
-            return '<h1 style="color:red">Application is being initialized</h1><div style="color:gray">' + this.renderMainTable(r.topics) + '</div>';
+            return '<h1 style="color:red">Application is being initialized</h1><div style="color:gray">' + deletedTopics + this.renderMainTable(r.topics) + '</div>';
         }
 
-        return this.renderMainTable(r.topics);
+        return deletedTopics + this.renderMainTable(r.topics);
 
     }
 
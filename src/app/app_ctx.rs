@@ -1,21 +1,36 @@
 use std::{sync::Arc, time::Duration};
 
+use my_azure_page_blob_ext::MyAzurePageBlobStorageWithRetries;
 use my_azure_storage_sdk::{
     blob_container::BlobContainersApi, page_blob::AzurePageBlobStorage, AzureStorageConnection,
+    AzureStorageError,
 };
 
 use rust_extensions::AppStates;
 
 use crate::{
-    archive_storage::{ArchiveFileNo, ArchivePageBlobCreator, ArchiveStorageList},
+    archive_storage::{
+        ArchiveFileNo, ArchivePageBlobCreator, ArchiveStatsCache, ArchiveStorageList,
+    },
+    audit_log::AuditLog,
+    backup::BackupJobs,
+    encryption::PayloadEncryption,
+    file_storage::FileStorage,
+    header_index::HeaderIndexes,
     index_by_minute::{IndexByMinuteUtils, YearlyIndexByMinute},
-    settings::SettingsModel,
+    leader_lease::LeaderLease,
+    message_pages::SubPageCodecs,
+    parquet_export::ParquetExportJobs,
+    replication::{ReplicaState, ReplicationHub},
+    settings::{FileStorageSettingsModel, SettingsModel},
     topic_data::TopicsDataList,
-    topics_snapshot::current_snapshot::CurrentTopicsSnapshot,
+    topics_snapshot::{
+        current_snapshot::CurrentTopicsSnapshot, page_blob_storage::TopicsSnapshotPageBlobStorage,
+    },
     typing::Year,
 };
 
-use super::PrometheusMetrics;
+use super::{Logs, PrometheusMetrics, LOGS};
 
 pub const APP_VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
@@ -35,6 +50,20 @@ pub struct AppContext {
     topics_and_queue_conn_string: Arc<AzureStorageConnection>,
 
     pub archive_storage_list: ArchiveStorageList,
+    pub archive_stats: ArchiveStatsCache,
+    pub logs: Arc<Logs>,
+    pub audit_log: AuditLog,
+    pub codecs: SubPageCodecs,
+    pub encryption: PayloadEncryption,
+    pub header_indexes: HeaderIndexes,
+    pub parquet_export_jobs: ParquetExportJobs,
+    pub backup_jobs: BackupJobs,
+    // None if only one instance runs against the storage
+    pub leader_lease: Option<LeaderLease>,
+    // None if replication is not configured
+    pub replication_hub: Option<ReplicationHub>,
+    // Some if the instance is a secondary replica of another instance
+    pub replica: Option<ReplicaState>,
 }
 
 impl AppContext {
@@ -51,9 +80,18 @@ impl AppContext {
             AzureStorageConnection::from_conn_string(settings.archive_connection_string.as_str());
 
         let topics_repo = settings.get_topics_snapshot_repository().await;
+        let topics_history = settings.get_topics_snapshot_history().await;
+        let audit_log = settings.get_audit_log().await;
+        let codecs = settings.get_sub_page_codecs();
+        let encryption = settings.get_payload_encryption();
+        let header_indexes = settings.get_header_indexes();
+        let leader_lease = settings.get_leader_lease();
+        let replication_hub = settings.get_replication_hub();
+        let replica = settings.get_replica_state();
 
         AppContext {
-            topics_snapshot: CurrentTopicsSnapshot::read_or_create(topics_repo).await,
+            topics_snapshot: CurrentTopicsSnapshot::read_or_create(topics_repo, topics_history)
+                .await,
             topics_list: TopicsDataList::new(),
             settings,
 
@@ -62,11 +100,46 @@ impl AppContext {
             messages_conn_string,
             app_states: Arc::new(AppStates::create_un_initialized()),
             archive_storage_list: ArchiveStorageList::new(),
+            archive_stats: ArchiveStatsCache::new(),
+            logs: LOGS.clone(),
+            audit_log,
+            codecs,
+            encryption,
+            header_indexes,
+            parquet_export_jobs: ParquetExportJobs::new(),
+            backup_jobs: BackupJobs::new(),
+            leader_lease,
+            replication_hub,
+            replica,
             topics_and_queue_conn_string,
             archive_conn_string: Arc::new(archive_conn_string),
         }
     }
 
+    // Instance without leader lease is always active
+    pub fn is_passive(&self) -> bool {
+        match &self.leader_lease {
+            Some(leader_lease) => !leader_lease.is_leader(),
+            None => false,
+        }
+    }
+
+    // Secondary serves reads and applies events of the primary until it is promoted
+    pub fn is_secondary(&self) -> bool {
+        match &self.replica {
+            Some(replica) => !replica.is_promoted(),
+            None => false,
+        }
+    }
+
+    // Secondary which missed events of the primary serves nothing until it is resynced
+    pub fn is_replica_out_of_sync(&self) -> bool {
+        match &self.replica {
+            Some(replica) => !replica.is_promoted() && !replica.is_in_sync(),
+            None => false,
+        }
+    }
+
     pub fn get_env_info(&self) -> String {
         let env_info = std::env::var("ENV_INFO");
 
@@ -97,6 +170,39 @@ impl AppContext {
         }
     }
 
+    // Archive files, minute indexes and other blobs of the topic are deleted together with its
+    // containers. Accounts may be the same, so each of them is handled once
+    pub async fn delete_topic_containers(&self, topic_id: &str) -> Result<(), AzureStorageError> {
+        let connections = [
+            (
+                self.settings.archive_connection_string.as_str(),
+                &self.archive_conn_string,
+            ),
+            (
+                self.settings.messages_connection_string.as_str(),
+                &self.messages_conn_string,
+            ),
+            (
+                self.settings.topics_connection_string.as_str(),
+                &self.topics_and_queue_conn_string,
+            ),
+        ];
+
+        let mut handled: Vec<&str> = Vec::new();
+
+        for (conn_string, connection) in connections {
+            if handled.contains(&conn_string) {
+                continue;
+            }
+
+            connection.delete_container_if_exists(topic_id).await?;
+
+            handled.push(conn_string);
+        }
+
+        Ok(())
+    }
+
     pub async fn open_or_create_index_by_minute(
         &self,
         topic_id: &str,
@@ -133,6 +239,108 @@ impl AppContext {
         Some(Arc::new(result))
     }
 
+    pub async fn get_zstd_dictionaries_storage(
+        &self,
+        topic_id: &str,
+    ) -> TopicsSnapshotPageBlobStorage {
+        self.get_topic_blob_storage(
+            topic_id,
+            super::file_name_generators::generate_zstd_dictionaries_blob_name(),
+        )
+        .await
+    }
+
+    pub async fn get_header_index_manifest_storage(
+        &self,
+        topic_id: &str,
+    ) -> TopicsSnapshotPageBlobStorage {
+        self.get_topic_blob_storage(
+            topic_id,
+            super::file_name_generators::generate_header_index_manifest_blob_name(),
+        )
+        .await
+    }
+
+    pub async fn get_header_index_run_storage(
+        &self,
+        topic_id: &str,
+        run_id: i64,
+    ) -> TopicsSnapshotPageBlobStorage {
+        self.get_topic_blob_storage(
+            topic_id,
+            super::file_name_generators::generate_header_index_run_blob_name(run_id),
+        )
+        .await
+    }
+
+    pub async fn get_year_index_storage(
+        &self,
+        topic_id: &str,
+        year: Year,
+    ) -> TopicsSnapshotPageBlobStorage {
+        let page_blob = AzurePageBlobStorage::new(
+            self.messages_conn_string.clone(),
+            topic_id.to_string(),
+            super::file_name_generators::generate_year_index_blob_name(year),
+        )
+        .await;
+
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        TopicsSnapshotPageBlobStorage::new(page_blob)
+    }
+
+    // Page blob of the topic container in the archive storage account
+    pub async fn get_topic_blob_storage(
+        &self,
+        topic_id: &str,
+        blob_name: String,
+    ) -> TopicsSnapshotPageBlobStorage {
+        let page_blob = AzurePageBlobStorage::new(
+            self.archive_conn_string.clone(),
+            topic_id.to_string(),
+            blob_name,
+        )
+        .await;
+
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        TopicsSnapshotPageBlobStorage::new(page_blob)
+    }
+
+    pub fn get_parquet_export_target(&self, target: &str) -> Option<FileStorage> {
+        self.get_file_storage(self.settings.parquet_export.as_ref()?, target)
+    }
+
+    pub fn get_backup_storage(&self, target: &str) -> Option<FileStorage> {
+        self.get_file_storage(self.settings.backup.as_ref()?, target)
+    }
+
+    // Target is "local" or "blob". Blob container is created in the archive storage account
+    fn get_file_storage(
+        &self,
+        settings: &FileStorageSettingsModel,
+        target: &str,
+    ) -> Option<FileStorage> {
+        match target {
+            "local" => {
+                let local_dir = settings.local_dir.as_ref()?;
+                Some(FileStorage::LocalDir(local_dir.into()))
+            }
+            "blob" => {
+                let container = settings.blob_container.as_ref()?;
+                Some(FileStorage::BlobContainer {
+                    connection: self.archive_conn_string.clone(),
+                    container: container.to_string(),
+                    account: None,
+                })
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_storage_for_active_pages(&self) -> Arc<AzureStorageConnection> {
         self.topics_and_queue_conn_string.clone()
     }
@@ -3,3 +3,15 @@ use crate::typing::Year;
 pub fn generate_year_index_blob_name(year: Year) -> String {
     return format!(".{}.yearindex", year.get_value());
 }
+
+pub fn generate_zstd_dictionaries_blob_name() -> String {
+    ".zstd-dictionaries".to_string()
+}
+
+pub fn generate_header_index_manifest_blob_name() -> String {
+    ".header-index".to_string()
+}
+
+pub fn generate_header_index_run_blob_name(run_id: i64) -> String {
+    format!(".header-index.{}", run_id)
+}
@@ -0,0 +1,187 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use my_logger::LogEventCtx;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+const MAX_ITEMS: usize = 100;
+const MAX_ITEMS_PER_TOPIC: usize = 100;
+
+lazy_static::lazy_static! {
+    pub static ref LOGS: Arc<Logs> = Arc::new(Logs::new());
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum LogLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "Info",
+            LogLevel::Warning => "Warning",
+            LogLevel::Error => "Error",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LogItem {
+    pub date: DateTimeAsMicroseconds,
+    pub level: LogLevel,
+    pub topic_id: Option<String>,
+    pub process: String,
+    pub message: String,
+    pub ctx: Option<String>,
+}
+
+impl LogItem {
+    pub fn get_topic_id(&self) -> Option<&str> {
+        let topic_id = self.topic_id.as_ref()?;
+        Some(topic_id.as_str())
+    }
+}
+
+struct LogsData {
+    items: VecDeque<Arc<LogItem>>,
+    items_by_topic: HashMap<String, VecDeque<Arc<LogItem>>>,
+}
+
+pub struct Logs {
+    data: Mutex<LogsData>,
+}
+
+impl Logs {
+    pub fn new() -> Self {
+        Self {
+            data: Mutex::new(LogsData {
+                items: VecDeque::new(),
+                items_by_topic: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn add_info(&self, topic_id: Option<&str>, process: &str, message: String) {
+        my_logger::LOGGER.write_info(process.to_string(), message.clone(), create_ctx(topic_id));
+        self.add(LogLevel::Info, topic_id, process, message, None);
+    }
+
+    pub fn add_warning(&self, topic_id: Option<&str>, process: &str, message: String) {
+        my_logger::LOGGER.write_warning(process.to_string(), message.clone(), create_ctx(topic_id));
+        self.add(LogLevel::Warning, topic_id, process, message, None);
+    }
+
+    pub fn add_error(
+        &self,
+        topic_id: Option<&str>,
+        process: &str,
+        message: String,
+        ctx: Option<String>,
+    ) {
+        my_logger::LOGGER.write_error(process.to_string(), message.clone(), create_ctx(topic_id));
+        self.add(LogLevel::Error, topic_id, process, message, ctx);
+    }
+
+    fn add(
+        &self,
+        level: LogLevel,
+        topic_id: Option<&str>,
+        process: &str,
+        message: String,
+        ctx: Option<String>,
+    ) {
+        let item = Arc::new(LogItem {
+            date: DateTimeAsMicroseconds::now(),
+            level,
+            topic_id: topic_id.map(|itm| itm.to_string()),
+            process: process.to_string(),
+            message,
+            ctx,
+        });
+
+        let mut write_access = self.data.lock().unwrap();
+
+        if let Some(topic_id) = topic_id {
+            if !write_access.items_by_topic.contains_key(topic_id) {
+                write_access
+                    .items_by_topic
+                    .insert(topic_id.to_string(), VecDeque::new());
+            }
+
+            let items = write_access.items_by_topic.get_mut(topic_id).unwrap();
+            push_with_limit(items, item.clone(), MAX_ITEMS_PER_TOPIC);
+        }
+
+        push_with_limit(&mut write_access.items, item, MAX_ITEMS);
+    }
+
+    pub fn get(&self) -> Vec<Arc<LogItem>> {
+        let read_access = self.data.lock().unwrap();
+        read_access.items.iter().rev().cloned().collect()
+    }
+
+    pub fn get_by_topic(&self, topic_id: &str) -> Option<Vec<Arc<LogItem>>> {
+        let read_access = self.data.lock().unwrap();
+        let items = read_access.items_by_topic.get(topic_id)?;
+        Some(items.iter().rev().cloned().collect())
+    }
+}
+
+fn push_with_limit(items: &mut VecDeque<Arc<LogItem>>, item: Arc<LogItem>, max_items: usize) {
+    items.push_back(item);
+
+    while items.len() > max_items {
+        items.pop_front();
+    }
+}
+
+fn create_ctx(topic_id: Option<&str>) -> LogEventCtx {
+    match topic_id {
+        Some(topic_id) => LogEventCtx::new().add("topicId", topic_id),
+        None => LogEventCtx::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Logs;
+
+    #[test]
+    fn test_items_are_limited_and_grouped_by_topic() {
+        let logs = Logs::new();
+
+        for i in 0..150 {
+            logs.add(
+                super::LogLevel::Info,
+                Some("topic"),
+                "test",
+                format!("{}", i),
+                None,
+            );
+        }
+
+        logs.add(
+            super::LogLevel::Warning,
+            None,
+            "test",
+            "No topic".to_string(),
+            None,
+        );
+
+        let all = logs.get();
+
+        assert_eq!(all.len(), super::MAX_ITEMS);
+        assert_eq!(all[0].message, "No topic");
+
+        let by_topic = logs.get_by_topic("topic").unwrap();
+        assert_eq!(by_topic.len(), super::MAX_ITEMS_PER_TOPIC);
+        assert_eq!(by_topic[0].message, "149");
+
+        assert!(logs.get_by_topic("unknown").is_none());
+    }
+}
@@ -2,8 +2,10 @@ mod app_ctx;
 mod app_error;
 
 pub mod file_name_generators;
+mod logs;
 mod prometheus_metrics;
 
 pub use app_ctx::*;
+pub use logs::*;
 
 pub use prometheus_metrics::*;
@@ -0,0 +1,18 @@
+use prometheus::{IntCounterVec, Registry};
+const TOPIC_LABEL: &str = "topic";
+
+pub struct CounterByTopic(IntCounterVec);
+
+impl CounterByTopic {
+    pub fn new(registry: &Registry, name: &str, help: &str) -> Self {
+        let counter =
+            IntCounterVec::new(prometheus::Opts::new(name, help), &[TOPIC_LABEL]).unwrap();
+
+        registry.register(Box::new(counter.clone())).unwrap();
+        Self(counter)
+    }
+
+    pub fn inc_by(&self, topic_id: &str, value: u64) {
+        self.0.with_label_values(&[topic_id]).inc_by(value);
+    }
+}
@@ -19,9 +19,11 @@ impl GaugeByTopic {
         let result = self.0.remove_label_values(&[topic_id]);
 
         if let Err(err) = result {
-            println!(
-                "Failed to remove topic {} from prometheus metrics: {}",
-                topic_id, err
+            crate::app::LOGS.add_error(
+                Some(topic_id),
+                "PrometheusMetrics",
+                format!("Failed to remove topic from prometheus metrics: {}", err),
+                None,
             );
         }
     }
@@ -1,4 +1,6 @@
+mod counter_by_topic;
 mod gauge_by_topic;
 mod prometheus_metrics;
+pub use counter_by_topic::*;
 pub use gauge_by_topic::*;
 pub use prometheus_metrics::*;
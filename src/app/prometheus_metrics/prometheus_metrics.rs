@@ -1,21 +1,62 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
-use prometheus::{Encoder, IntGauge, Registry, TextEncoder};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramTimer, HistogramVec, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
 use tokio::sync::Mutex;
 
-use super::GaugeByTopic;
+use super::{CounterByTopic, GaugeByTopic};
 
 pub struct PrometheusMetricsToUpdate {
     pub not_persisted_size: usize,
     pub content_size: usize,
+    pub last_saved_moment: i64,
+}
+
+pub enum SubPageRestoreResult {
+    Hit,
+    Miss,
+    Missing,
+}
+
+impl SubPageRestoreResult {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubPageRestoreResult::Hit => "hit",
+            SubPageRestoreResult::Miss => "miss",
+            SubPageRestoreResult::Missing => "missing",
+        }
+    }
 }
 
 pub struct PrometheusMetrics {
     registry: Registry,
     topic_persist_queue_size: GaugeByTopic,
     cached_messages_size: GaugeByTopic,
+    topic_last_saved_moment: GaugeByTopic,
     active_topics: Mutex<HashSet<String>>,
     http_connections_amount: IntGauge,
+
+    archive_read_duration: Histogram,
+    archive_write_duration: Histogram,
+    index_flush_duration: Histogram,
+    grpc_call_duration: HistogramVec,
+
+    page_blob_retries: IntCounterVec,
+    page_blob_failures: IntCounterVec,
+    sub_page_restores: IntCounterVec,
+    snapshot_anomalies: IntCounterVec,
+
+    topic_bytes_in: CounterByTopic,
+    topic_bytes_out: CounterByTopic,
+    topic_messages_in: CounterByTopic,
+
+    replication_lag_events: IntGauge,
+    replication_lag_ms: IntGauge,
 }
 
 impl PrometheusMetrics {
@@ -30,18 +71,115 @@ impl PrometheusMetrics {
         let cached_messages_size =
             GaugeByTopic::new(&registry, "cached_messages_size", "Cached messages size");
 
+        let topic_last_saved_moment = GaugeByTopic::new(
+            &registry,
+            "topic_last_saved_moment",
+            "Unix time in seconds when topic sub page was archived last time",
+        );
+
         let http_connections_amount = create_http_connections_amount();
 
         registry
             .register(Box::new(http_connections_amount.clone()))
             .unwrap();
 
+        let archive_read_duration = create_histogram(
+            &registry,
+            "archive_read_duration_sec",
+            "Duration of reading sub page payload from archive",
+        );
+
+        let archive_write_duration = create_histogram(
+            &registry,
+            "archive_write_duration_sec",
+            "Duration of writing sub page payload to archive",
+        );
+
+        let index_flush_duration = create_histogram(
+            &registry,
+            "index_flush_duration_sec",
+            "Duration of flushing minute index to storage",
+        );
+
+        let grpc_call_duration = create_histogram_vec(
+            &registry,
+            "grpc_call_duration_sec",
+            "Duration of grpc calls",
+            "method",
+        );
+
+        let page_blob_retries = create_counter_vec(
+            &registry,
+            "page_blob_retries",
+            "Amount of page blob operations retried after a failed attempt",
+            "operation",
+        );
+
+        let page_blob_failures = create_counter_vec(
+            &registry,
+            "page_blob_failures",
+            "Amount of page blob operations failed after the retries of the storage client",
+            "operation",
+        );
+
+        let sub_page_restores = create_counter_vec(
+            &registry,
+            "sub_page_restores",
+            "Amount of sub page reads by result: hit - in memory, miss - restored from archive, missing - not found",
+            "result",
+        );
+
+        let snapshot_anomalies = create_counter_vec(
+            &registry,
+            "snapshot_anomalies",
+            "Amount of anomalies found in incoming queue snapshots",
+            "kind",
+        );
+
+        let topic_bytes_in =
+            CounterByTopic::new(&registry, "topic_bytes_in", "Amount of bytes persisted");
+
+        let topic_bytes_out =
+            CounterByTopic::new(&registry, "topic_bytes_out", "Amount of bytes read");
+
+        let topic_messages_in = CounterByTopic::new(
+            &registry,
+            "topic_messages_in",
+            "Amount of messages persisted",
+        );
+
+        let replication_lag_events = create_int_gauge(
+            &registry,
+            "replication_lag_events",
+            "Amount of events of the primary not applied by the secondary yet",
+        );
+
+        let replication_lag_ms = create_int_gauge(
+            &registry,
+            "replication_lag_ms",
+            "Time between the last event of the primary and the last event applied by the secondary",
+        );
+
         return Self {
             registry,
             topic_persist_queue_size,
             cached_messages_size,
+            topic_last_saved_moment,
             active_topics: Mutex::new(HashSet::new()),
             http_connections_amount,
+            archive_read_duration,
+            archive_write_duration,
+            index_flush_duration,
+            grpc_call_duration,
+            page_blob_retries,
+            page_blob_failures,
+            sub_page_restores,
+            snapshot_anomalies,
+            topic_bytes_in,
+            topic_bytes_out,
+            topic_messages_in,
+            replication_lag_events,
+            replication_lag_ms,
         };
     }
     pub async fn update(
@@ -60,6 +198,9 @@ impl PrometheusMetrics {
 
                     self.cached_messages_size
                         .update_value(active_topic_id.as_str(), metrics.content_size as i64);
+
+                    self.topic_last_saved_moment
+                        .update_value(active_topic_id.as_str(), metrics.last_saved_moment);
                 }
                 None => {
                     self.topic_persist_queue_size
@@ -67,6 +208,9 @@ impl PrometheusMetrics {
 
                     self.cached_messages_size
                         .remove_topic(active_topic_id.as_str());
+
+                    self.topic_last_saved_moment
+                        .remove_topic(active_topic_id.as_str());
                 }
             }
         }
@@ -78,10 +222,66 @@ impl PrometheusMetrics {
             self.cached_messages_size
                 .update_value(topic_id, metrics.content_size as i64);
 
+            self.topic_last_saved_moment
+                .update_value(topic_id, metrics.last_saved_moment);
+
             active_topics.insert(topic_id.to_string());
         }
     }
 
+    pub fn observe_archive_read(&self, duration: Duration) {
+        self.archive_read_duration.observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_archive_write(&self, duration: Duration) {
+        self.archive_write_duration.observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_index_flush(&self, duration: Duration) {
+        self.index_flush_duration.observe(duration.as_secs_f64());
+    }
+
+    pub fn start_grpc_call_timer(&self, method: &str) -> HistogramTimer {
+        self.grpc_call_duration
+            .with_label_values(&[method])
+            .start_timer()
+    }
+
+    pub fn page_blob_retry(&self, operation: &str) {
+        self.page_blob_retries.with_label_values(&[operation]).inc();
+    }
+
+    pub fn page_blob_failure(&self, operation: &str) {
+        self.page_blob_failures
+            .with_label_values(&[operation])
+            .inc();
+    }
+
+    pub fn sub_page_restored(&self, result: SubPageRestoreResult) {
+        self.sub_page_restores
+            .with_label_values(&[result.as_str()])
+            .inc();
+    }
+
+    pub fn snapshot_anomaly(&self, kind: &str) {
+        self.snapshot_anomalies.with_label_values(&[kind]).inc();
+    }
+
+    pub fn messages_persisted(&self, topic_id: &str, messages_amount: usize, bytes: usize) {
+        self.topic_messages_in
+            .inc_by(topic_id, messages_amount as u64);
+        self.topic_bytes_in.inc_by(topic_id, bytes as u64);
+    }
+
+    pub fn bytes_read(&self, topic_id: &str, bytes: usize) {
+        self.topic_bytes_out.inc_by(topic_id, bytes as u64);
+    }
+
+    pub fn update_replication_lag(&self, events: i64, duration_ms: i64) {
+        self.replication_lag_events.set(events);
+        self.replication_lag_ms.set(duration_ms);
+    }
+
     pub fn build_prometheus_content(&self) -> String {
         let mut buffer = vec![];
         let encoder = TextEncoder::new();
@@ -95,3 +295,27 @@ impl PrometheusMetrics {
 fn create_http_connections_amount() -> IntGauge {
     IntGauge::new("http_connections_amount", "Amount of Http Connections").unwrap()
 }
+
+fn create_int_gauge(registry: &Registry, name: &str, help: &str) -> IntGauge {
+    let gauge = IntGauge::new(name, help).unwrap();
+    registry.register(Box::new(gauge.clone())).unwrap();
+    gauge
+}
+
+fn create_histogram(registry: &Registry, name: &str, help: &str) -> Histogram {
+    let histogram = Histogram::with_opts(HistogramOpts::new(name, help)).unwrap();
+    registry.register(Box::new(histogram.clone())).unwrap();
+    histogram
+}
+
+fn create_histogram_vec(registry: &Registry, name: &str, help: &str, label: &str) -> HistogramVec {
+    let histogram = HistogramVec::new(HistogramOpts::new(name, help), &[label]).unwrap();
+    registry.register(Box::new(histogram.clone())).unwrap();
+    histogram
+}
+
+fn create_counter_vec(registry: &Registry, name: &str, help: &str, label: &str) -> IntCounterVec {
+    let counter = IntCounterVec::new(Opts::new(name, help), &[label]).unwrap();
+    registry.register(Box::new(counter.clone())).unwrap();
+    counter
+}
@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, HashMap};
+
+use my_service_bus::{abstractions::MessageId, shared::protobuf_models::MessageProtobufModel};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+use super::{toc::SubPagePosition, ArchiveFileNo};
+
+#[derive(Clone, Debug)]
+pub struct ArchiveFileStats {
+    pub archive_file_no: i64,
+    pub blob_size: usize,
+    pub populated_toc_slots: usize,
+    pub compressed_size: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ArchivedMessageInfo {
+    pub message_id: MessageId,
+    pub created: DateTimeAsMicroseconds,
+}
+
+impl ArchivedMessageInfo {
+    pub fn from_message(msg: &MessageProtobufModel) -> Self {
+        Self {
+            message_id: msg.get_message_id(),
+            created: msg.get_created(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TopicArchiveStats {
+    pub files: BTreeMap<i64, ArchiveFileStats>,
+    pub first_message: Option<ArchivedMessageInfo>,
+    pub last_message: Option<ArchivedMessageInfo>,
+    // Totals of the sub pages with known uncompressed length. Scan reads it from the payload headers,
+    // so encrypted payloads are counted only when they are archived after the scan
+    pub measured_compressed_size: usize,
+    pub measured_uncompressed_size: usize,
+}
+
+impl TopicArchiveStats {
+    pub fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+            first_message: None,
+            last_message: None,
+            measured_compressed_size: 0,
+            measured_uncompressed_size: 0,
+        }
+    }
+
+    pub fn get_compression_ratio(&self) -> Option<f64> {
+        if self.measured_compressed_size == 0 {
+            return None;
+        }
+
+        Some(self.measured_uncompressed_size as f64 / self.measured_compressed_size as f64)
+    }
+
+    pub fn update_first_message(&mut self, first: ArchivedMessageInfo) {
+        if let Some(current) = &self.first_message {
+            if current.message_id.get_value() <= first.message_id.get_value() {
+                return;
+            }
+        }
+
+        self.first_message = Some(first);
+    }
+
+    pub fn update_last_message(&mut self, last: ArchivedMessageInfo) {
+        if let Some(current) = &self.last_message {
+            if current.message_id.get_value() >= last.message_id.get_value() {
+                return;
+            }
+        }
+
+        self.last_message = Some(last);
+    }
+
+    pub fn sub_page_archived(
+        &mut self,
+        archive_file_no: ArchiveFileNo,
+        pos: SubPagePosition,
+        uncompressed_size: usize,
+        first: ArchivedMessageInfo,
+        last: ArchivedMessageInfo,
+    ) {
+        let file_stats = self
+            .files
+            .entry(archive_file_no.get_value())
+            .or_insert_with(|| ArchiveFileStats {
+                archive_file_no: archive_file_no.get_value(),
+                blob_size: 0,
+                populated_toc_slots: 0,
+                compressed_size: 0,
+            });
+
+        let end_of_payload = pos.offset as usize + pos.length as usize;
+
+        if file_stats.blob_size < end_of_payload {
+            file_stats.blob_size = end_of_payload;
+        }
+
+        file_stats.populated_toc_slots += 1;
+        file_stats.compressed_size += pos.length as u64;
+
+        self.measured_compressed_size += pos.length as usize;
+        self.measured_uncompressed_size += uncompressed_size;
+
+        self.update_first_message(first);
+        self.update_last_message(last);
+    }
+}
+
+pub struct ArchiveStatsCache {
+    items: Mutex<HashMap<String, TopicArchiveStats>>,
+}
+
+impl ArchiveStatsCache {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get(&self, topic_id: &str) -> Option<TopicArchiveStats> {
+        let read_access = self.items.lock().await;
+        read_access.get(topic_id).cloned()
+    }
+
+    pub async fn insert(&self, topic_id: &str, stats: TopicArchiveStats) {
+        let mut write_access = self.items.lock().await;
+        write_access.insert(topic_id.to_string(), stats);
+    }
+
+    pub async fn remove(&self, topic_id: &str) {
+        let mut write_access = self.items.lock().await;
+        write_access.remove(topic_id);
+    }
+
+    // Stats are updated only if they are already scanned. Otherwise the next request is going to scan TOCs
+    pub async fn sub_page_archived(
+        &self,
+        topic_id: &str,
+        archive_file_no: ArchiveFileNo,
+        pos: SubPagePosition,
+        uncompressed_size: usize,
+        first: ArchivedMessageInfo,
+        last: ArchivedMessageInfo,
+    ) {
+        let mut write_access = self.items.lock().await;
+
+        if let Some(stats) = write_access.get_mut(topic_id) {
+            stats.sub_page_archived(archive_file_no, pos, uncompressed_size, first, last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::MessageId;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::archive_storage::{toc::SubPagePosition, ArchiveFileNo};
+
+    use super::{ArchivedMessageInfo, TopicArchiveStats};
+
+    fn message(message_id: i64, created: i64) -> ArchivedMessageInfo {
+        ArchivedMessageInfo {
+            message_id: MessageId::new(message_id),
+            created: DateTimeAsMicroseconds::new(created),
+        }
+    }
+
+    #[test]
+    fn test_sub_page_archived() {
+        let mut stats = TopicArchiveStats::new();
+
+        stats.sub_page_archived(
+            ArchiveFileNo::new(0),
+            SubPagePosition {
+                offset: 1024,
+                length: 100,
+            },
+            400,
+            message(1000, 10),
+            message(1999, 20),
+        );
+
+        stats.sub_page_archived(
+            ArchiveFileNo::new(0),
+            SubPagePosition {
+                offset: 1124,
+                length: 100,
+            },
+            200,
+            message(0, 1),
+            message(999, 5),
+        );
+
+        let file_stats = stats.files.get(&0).unwrap();
+
+        assert_eq!(file_stats.populated_toc_slots, 2);
+        assert_eq!(file_stats.compressed_size, 200);
+        assert_eq!(file_stats.blob_size, 1224);
+
+        assert_eq!(stats.first_message.unwrap().message_id.get_value(), 0);
+        assert_eq!(stats.last_message.unwrap().message_id.get_value(), 1999);
+
+        assert_eq!(stats.get_compression_ratio().unwrap(), 3.0);
+    }
+}
@@ -4,18 +4,34 @@ use my_azure_page_blob_random_access::{
 };
 use my_azure_storage_sdk::page_blob::MyAzurePageBlobStorage;
 use my_service_bus::shared::sub_page::SubPageId;
+use tokio::sync::RwLock;
 
 use crate::settings::PAGE_BLOB_MAX_PAGES_TO_UPLOAD_PER_ROUND_TRIP;
 
 use super::{
-    consts::{CALCULATED_TOC_PAGES_AMOUNT, TOC_SIZE_IN_BITES},
+    consts::{CALCULATED_TOC_PAGES_AMOUNT, TOC_SIZE, TOC_SIZE_IN_BITES},
     toc::SubPagePosition,
     ArchiveFileNo,
 };
 
+pub struct WritePayloadResult {
+    pub pos: SubPagePosition,
+    // Previous position of the sub page. Its region is orphaned until the file is compacted
+    pub overwritten: Option<SubPagePosition>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CompactionResult {
+    pub moved_sub_pages: usize,
+    pub blob_size_before: usize,
+    pub blob_size_after: usize,
+}
+
 pub struct ArchiveStorage {
     pub archive_file_no: ArchiveFileNo,
     pub page_blob: PageBlobRandomAccess<MyAzurePageBlobStorageWithRetries>,
+    // Payloads are moved during compaction, so reads and writes are not allowed in the middle of it
+    lock: RwLock<()>,
 }
 
 impl ArchiveStorage {
@@ -30,11 +46,7 @@ impl ArchiveStorage {
         match result {
             Ok(props) => {
                 if props.get_blob_size() >= TOC_SIZE_IN_BITES {
-                    return Self {
-                        archive_file_no,
-                        page_blob,
-                    }
-                    .into();
+                    return Self::new(archive_file_no, page_blob).into();
                 } else {
                     None
                 }
@@ -62,20 +74,55 @@ impl ArchiveStorage {
             page_blob.resize(CALCULATED_TOC_PAGES_AMOUNT).await.unwrap();
         }
 
-        Self {
+        Self::new(
             archive_file_no,
-            page_blob: PageBlobRandomAccess::new(
+            PageBlobRandomAccess::new(
                 page_blob,
                 true,
                 PAGE_BLOB_MAX_PAGES_TO_UPLOAD_PER_ROUND_TRIP,
             ),
+        )
+    }
+
+    fn new(
+        archive_file_no: ArchiveFileNo,
+        page_blob: PageBlobRandomAccess<MyAzurePageBlobStorageWithRetries>,
+    ) -> Self {
+        Self {
+            archive_file_no,
+            page_blob,
+            lock: RwLock::new(()),
         }
     }
 
+    // Space after TOC which is not referenced by TOC. Calculated from the file itself,
+    // so regions orphaned before the file was opened are counted as well
+    pub async fn get_orphaned_size(&self) -> usize {
+        let _read_access = self.lock.read().await;
+
+        let populated =
+            super::toc::read_populated_positions(&self.page_blob, self.archive_file_no).await;
+
+        let live_size: usize = populated.iter().map(|(_, pos)| pos.length as usize).sum();
+
+        self.get_blob_size()
+            .await
+            .saturating_sub(TOC_SIZE + live_size)
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            archive_file_no = self.archive_file_no.get_value(),
+            sub_page_id = sub_page_id.get_value(),
+        ),
+    )]
     pub async fn read_sub_page_payload(
         &self,
         sub_page_id: SubPageId,
     ) -> Result<Option<ReadChunk>, PageBlobRandomAccessError> {
+        let _read_access = self.lock.read().await;
+
         let pos =
             super::toc::read_file_position(&self.page_blob, self.archive_file_no, sub_page_id)
                 .await;
@@ -92,18 +139,71 @@ impl ArchiveStorage {
         Ok(Some(result))
     }
 
-    pub async fn write_payload(&self, sub_page_id: SubPageId, payload: &[u8]) {
-        let pos =
+    // First bytes of the payloads at the positions read from TOC, in the same order. Shorter payloads
+    // are read whole. Prefixes which are close to each other are read by one request
+    pub async fn read_payload_prefixes(
+        &self,
+        positions: &[SubPagePosition],
+        max_len: usize,
+    ) -> Result<Vec<Vec<u8>>, PageBlobRandomAccessError> {
+        let _read_access = self.lock.read().await;
+
+        let mut result = vec![Vec::new(); positions.len()];
+
+        for range in group_prefix_reads(positions, max_len) {
+            let chunk = self
+                .page_blob
+                .read(range.offset, range.end - range.offset)
+                .await?;
+
+            let chunk = chunk.as_slice();
+
+            for index in range.indexes {
+                let pos = positions[index];
+                let start = pos.offset as usize - range.offset;
+                let len = (pos.length as usize).min(max_len);
+                result[index] = chunk[start..start + len].to_vec();
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[tracing::instrument(skip_all, fields(archive_file_no = self.archive_file_no.get_value()))]
+    pub async fn get_populated_sub_pages(&self) -> Vec<(SubPageId, SubPagePosition)> {
+        super::toc::read_populated_positions(&self.page_blob, self.archive_file_no).await
+    }
+
+    pub async fn get_blob_size(&self) -> usize {
+        let props = self.page_blob.get_blob_properties().await.unwrap();
+        props.get_blob_size()
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            archive_file_no = self.archive_file_no.get_value(),
+            sub_page_id = sub_page_id.get_value(),
+            size = payload.len(),
+        ),
+    )]
+    // Payload is always appended. If the sub page is already archived - TOC is repointed to the new version
+    pub async fn write_payload(
+        &self,
+        sub_page_id: SubPageId,
+        payload: &[u8],
+    ) -> WritePayloadResult {
+        let _write_access = self.lock.write().await;
+
+        let prev_pos =
             super::toc::read_file_position(&self.page_blob, self.archive_file_no, sub_page_id)
                 .await;
 
-        if pos.length > 0 {
-            println!(
-                "Overwrite payload for sub_page_id: {}",
-                sub_page_id.get_value()
-            );
-            return;
-        }
+        let overwritten = if prev_pos.length > 0 {
+            Some(prev_pos)
+        } else {
+            None
+        };
 
         let blob_size = self.page_blob.get_blob_properties().await.unwrap();
 
@@ -119,7 +219,145 @@ impl ArchiveStorage {
 
         super::toc::write_file_position(&self.page_blob, self.archive_file_no, sub_page_id, pos)
             .await;
+
+        WritePayloadResult { pos, overwritten }
     }
+
+    // Drops every payload of the file: TOC is zeroed and the blob is shrunk back to the TOC
+    pub async fn truncate(&self) {
+        let _write_access = self.lock.write().await;
+
+        self.page_blob
+            .resize(CALCULATED_TOC_PAGES_AMOUNT)
+            .await
+            .unwrap();
+
+        self.page_blob
+            .write(0, vec![0u8; TOC_SIZE].as_slice())
+            .await
+            .unwrap();
+    }
+
+    // Moves payloads towards the TOC so regions orphaned by overwrites are reclaimed and the blob is shrunk.
+    // Payload is copied first and TOC is repointed after, so TOC points to an intact payload
+    // even if compaction is interrupted.
+    #[tracing::instrument(skip_all, fields(archive_file_no = self.archive_file_no.get_value()))]
+    pub async fn compact(&self) -> Result<CompactionResult, PageBlobRandomAccessError> {
+        let _write_access = self.lock.write().await;
+
+        let blob_size_before = self.get_blob_size().await;
+
+        let mut populated =
+            super::toc::read_populated_positions(&self.page_blob, self.archive_file_no).await;
+
+        populated.sort_by_key(|(_, pos)| pos.offset);
+
+        let mut end_of_data = TOC_SIZE as u64;
+        let mut moved_sub_pages = 0;
+
+        for (sub_page_id, mut pos) in populated {
+            if pos.offset == end_of_data {
+                end_of_data += pos.length as u64;
+                continue;
+            }
+
+            // New region overlaps the current one. Payload goes through the end of the blob
+            if end_of_data + pos.length as u64 > pos.offset {
+                let blob_size = self.get_blob_size().await as u64;
+                pos = self.move_payload(sub_page_id, pos, blob_size).await?;
+            }
+
+            self.move_payload(sub_page_id, pos, end_of_data).await?;
+
+            end_of_data += pos.length as u64;
+            moved_sub_pages += 1;
+        }
+
+        let pages_amount = (end_of_data as usize - 1) / 512 + 1;
+
+        if pages_amount * 512 < blob_size_before {
+            self.page_blob.resize(pages_amount).await.unwrap();
+        }
+
+        Ok(CompactionResult {
+            moved_sub_pages,
+            blob_size_before,
+            blob_size_after: self.get_blob_size().await,
+        })
+    }
+
+    async fn move_payload(
+        &self,
+        sub_page_id: SubPageId,
+        pos: SubPagePosition,
+        offset: u64,
+    ) -> Result<SubPagePosition, PageBlobRandomAccessError> {
+        let payload = self
+            .page_blob
+            .read(pos.offset as usize, pos.length as usize)
+            .await?;
+
+        let new_pos = SubPagePosition {
+            offset,
+            length: pos.length,
+        };
+
+        self.page_blob
+            .write(new_pos.offset as usize, payload.as_slice())
+            .await
+            .unwrap();
+
+        super::toc::write_file_position(
+            &self.page_blob,
+            self.archive_file_no,
+            sub_page_id,
+            new_pos,
+        )
+        .await;
+
+        Ok(new_pos)
+    }
+}
+
+// Prefixes are merged into one read while the gap between them and the size of the read are small
+const PREFIX_READ_MAX_GAP: usize = 64 * 1024;
+const PREFIX_READ_MAX_SIZE: usize = 4 * 1024 * 1024;
+
+struct PrefixReadRange {
+    offset: usize,
+    end: usize,
+    indexes: Vec<usize>,
+}
+
+fn group_prefix_reads(positions: &[SubPagePosition], max_len: usize) -> Vec<PrefixReadRange> {
+    let mut sorted: Vec<usize> = (0..positions.len()).collect();
+    sorted.sort_by_key(|index| positions[*index].offset);
+
+    let mut result: Vec<PrefixReadRange> = Vec::new();
+
+    for index in sorted {
+        let pos = positions[index];
+        let offset = pos.offset as usize;
+        let end = offset + (pos.length as usize).min(max_len);
+
+        if let Some(range) = result.last_mut() {
+            if offset <= range.end + PREFIX_READ_MAX_GAP
+                && end.max(range.end) - range.offset <= PREFIX_READ_MAX_SIZE
+            {
+                range.end = range.end.max(end);
+                range.indexes.push(index);
+                continue;
+            }
+        }
+
+        result.push(PrefixReadRange {
+            offset,
+            end,
+            indexes: vec![index],
+        });
+    }
+
+    result
 }
 
 #[cfg(test)]
@@ -193,4 +431,193 @@ mod tests {
         let result = result.unwrap();
         assert_eq!(result.as_slice(), src_payload);
     }
+
+    #[tokio::test]
+    async fn test_get_populated_sub_pages() {
+        let azure_connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let page_blob = AzurePageBlobStorage::new(azure_connection, "test", "test").await;
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        let archive_storage =
+            super::ArchiveStorage::open_or_create(ArchiveFileNo::new(0), page_blob).await;
+
+        archive_storage
+            .write_payload(SubPageId::new(3), "Hello".as_bytes())
+            .await;
+
+        archive_storage
+            .write_payload(SubPageId::new(5), "World!".as_bytes())
+            .await;
+
+        let result = archive_storage.get_populated_sub_pages().await;
+
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(result[0].0.get_value(), 3);
+        assert_eq!(result[0].1.length, 5);
+
+        assert_eq!(result[1].0.get_value(), 5);
+        assert_eq!(result[1].1.length, 6);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_and_compact() {
+        let azure_connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let page_blob = AzurePageBlobStorage::new(azure_connection.clone(), "test", "test").await;
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        let archive_storage =
+            super::ArchiveStorage::open_or_create(ArchiveFileNo::new(0), page_blob).await;
+
+        let first = vec![1u8; 1024];
+        let second = vec![2u8; 1024];
+        let overwritten = vec![3u8; 2048];
+
+        archive_storage
+            .write_payload(SubPageId::new(0), first.as_slice())
+            .await;
+
+        archive_storage
+            .write_payload(SubPageId::new(1), second.as_slice())
+            .await;
+
+        let result = archive_storage
+            .write_payload(SubPageId::new(0), overwritten.as_slice())
+            .await;
+
+        assert_eq!(result.overwritten.unwrap().length, 1024);
+        assert_eq!(archive_storage.get_orphaned_size().await, 1024);
+
+        // Orphaned region is found by the instance which did not overwrite it
+        let page_blob = AzurePageBlobStorage::new(azure_connection.clone(), "test", "test").await;
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        let reopened = super::ArchiveStorage::open_if_exists(ArchiveFileNo::new(0), page_blob)
+            .await
+            .unwrap();
+
+        assert_eq!(reopened.get_orphaned_size().await, 1024);
+
+        let payload = archive_storage
+            .read_sub_page_payload(SubPageId::new(0))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(payload.as_slice(), overwritten.as_slice());
+
+        let result = archive_storage.compact().await.unwrap();
+
+        assert_eq!(result.moved_sub_pages, 2);
+        assert_eq!(result.blob_size_before - result.blob_size_after, 1024);
+        assert_eq!(archive_storage.get_orphaned_size().await, 0);
+
+        let payload = archive_storage
+            .read_sub_page_payload(SubPageId::new(0))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(payload.as_slice(), overwritten.as_slice());
+
+        let payload = archive_storage
+            .read_sub_page_payload(SubPageId::new(1))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(payload.as_slice(), second.as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_truncate() {
+        let azure_connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let page_blob = AzurePageBlobStorage::new(azure_connection, "test", "test").await;
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        let archive_storage =
+            super::ArchiveStorage::open_or_create(ArchiveFileNo::new(0), page_blob).await;
+
+        archive_storage
+            .write_payload(SubPageId::new(0), vec![1u8; 1024].as_slice())
+            .await;
+
+        archive_storage.truncate().await;
+
+        assert!(archive_storage.get_populated_sub_pages().await.is_empty());
+        assert_eq!(archive_storage.get_blob_size().await, super::TOC_SIZE);
+
+        // Payload written after truncate is not appended after the dropped one
+        let result = archive_storage
+            .write_payload(SubPageId::new(0), vec![2u8; 1024].as_slice())
+            .await;
+
+        assert!(result.overwritten.is_none());
+        assert_eq!(result.pos.offset, super::TOC_SIZE as u64);
+        assert_eq!(archive_storage.get_orphaned_size().await, 0);
+    }
+
+    #[test]
+    fn test_group_prefix_reads() {
+        use super::SubPagePosition;
+
+        let positions = vec![
+            SubPagePosition {
+                offset: 2000,
+                length: 100,
+            },
+            SubPagePosition {
+                offset: 1000,
+                length: 1000,
+            },
+            SubPagePosition {
+                offset: 10_000_000,
+                length: 10,
+            },
+        ];
+
+        let result = super::group_prefix_reads(positions.as_slice(), 30);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].offset, 1000);
+        assert_eq!(result[0].end, 2030);
+        assert_eq!(result[0].indexes, vec![1, 0]);
+        assert_eq!(result[1].offset, 10_000_000);
+        assert_eq!(result[1].end, 10_000_010);
+    }
+
+    #[tokio::test]
+    async fn test_read_payload_prefixes() {
+        let azure_connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let page_blob = AzurePageBlobStorage::new(azure_connection, "test", "test").await;
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        let archive_storage =
+            super::ArchiveStorage::open_or_create(ArchiveFileNo::new(0), page_blob).await;
+
+        let first = archive_storage
+            .write_payload(SubPageId::new(0), "Hello world".as_bytes())
+            .await;
+
+        let second = archive_storage
+            .write_payload(SubPageId::new(1), "Hi".as_bytes())
+            .await;
+
+        let result = archive_storage
+            .read_payload_prefixes(&[second.pos, first.pos], 5)
+            .await
+            .unwrap();
+
+        assert_eq!(result[0].as_slice(), "Hi".as_bytes());
+        assert_eq!(result[1].as_slice(), "Hello".as_bytes());
+    }
 }
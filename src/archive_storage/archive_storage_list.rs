@@ -29,7 +29,7 @@ impl ArchiveStorageList {
         }
     }
 
-    async fn get_existing(
+    pub async fn get_existing(
         &self,
         topic_id: &str,
         archive_file_no: ArchiveFileNo,
@@ -62,6 +62,11 @@ impl ArchiveStorageList {
             .insert(archive_file_no.get_value(), storage);
     }
 
+    pub async fn remove_topic(&self, topic_id: &str) {
+        let mut write_access = self.items.lock().await;
+        write_access.remove(topic_id);
+    }
+
     pub async fn get_or_create(
         &self,
         archive_file_no: ArchiveFileNo,
@@ -106,30 +111,116 @@ impl ArchiveStorageList {
             return Some(page_blob);
         }
 
-        let page_blob = page_blob_creator.create(topic_id, archive_file_no).await;
+        let archive_storage = open(archive_file_no, topic_id, page_blob_creator).await?;
 
-        let page_blob =
-            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(3));
+        self.insert(topic_id, archive_file_no, archive_storage.clone())
+            .await;
 
-        let blob_props = page_blob.get_blob_properties().await;
+        Some(archive_storage)
+    }
 
-        if blob_props.is_err() {
-            return None;
+    // File which is not opened yet is not cached, so scanning every archive file of a topic
+    // with a long history does not keep all of them in memory
+    pub async fn get_existing_or_open(
+        &self,
+        archive_file_no: ArchiveFileNo,
+        topic_id: &str,
+        page_blob_creator: &impl ArchivePageBlobCreator,
+    ) -> Option<Arc<ArchiveStorage>> {
+        if let Some(page_blob) = self.get_existing(topic_id, archive_file_no).await {
+            return Some(page_blob);
         }
 
-        let blob_props = blob_props.unwrap();
+        open(archive_file_no, topic_id, page_blob_creator).await
+    }
 
-        if blob_props.get_pages_amount() < CALCULATED_TOC_PAGES_AMOUNT {
-            return None;
+    // File is in the list while it is pinned, so writes, compaction and backup of the file share its lock.
+    // File which is not opened yet is removed from the list when it is unpinned
+    pub async fn pin(
+        &self,
+        archive_file_no: ArchiveFileNo,
+        topic_id: &str,
+        page_blob_creator: &impl ArchivePageBlobCreator,
+    ) -> Option<PinnedArchiveStorage> {
+        if let Some(storage) = self.get_existing(topic_id, archive_file_no).await {
+            return Some(PinnedArchiveStorage {
+                storage,
+                archive_file_no,
+                was_cached: true,
+            });
         }
 
-        let archive_storage = ArchiveStorage::open_if_exists(archive_file_no, page_blob).await?;
+        let storage = open(archive_file_no, topic_id, page_blob_creator).await?;
 
-        let archive_storage = Arc::new(archive_storage);
+        let mut write_access = self.items.lock().await;
 
-        self.insert(topic_id, archive_file_no, archive_storage.clone())
-            .await;
+        // Storage opened by a write in the meantime is taken instead
+        let storage = write_access
+            .entry(topic_id.to_string())
+            .or_default()
+            .entry(archive_file_no.get_value())
+            .or_insert(storage)
+            .clone();
+
+        Some(PinnedArchiveStorage {
+            storage,
+            archive_file_no,
+            was_cached: false,
+        })
+    }
 
-        Some(archive_storage)
+    // File is removed only if nobody uses it, so it is not opened twice
+    pub async fn unpin(&self, topic_id: &str, pinned: PinnedArchiveStorage) {
+        if pinned.was_cached {
+            return;
+        }
+
+        let archive_file_no = pinned.archive_file_no;
+        drop(pinned);
+
+        let mut write_access = self.items.lock().await;
+
+        if let Some(archive_storages) = write_access.get_mut(topic_id) {
+            let unused = archive_storages
+                .get(&archive_file_no.get_value())
+                .map(|itm| Arc::strong_count(itm) == 1)
+                .unwrap_or(false);
+
+            if unused {
+                archive_storages.remove(&archive_file_no.get_value());
+            }
+        }
+    }
+}
+
+pub struct PinnedArchiveStorage {
+    pub storage: Arc<ArchiveStorage>,
+    archive_file_no: ArchiveFileNo,
+    was_cached: bool,
+}
+
+async fn open(
+    archive_file_no: ArchiveFileNo,
+    topic_id: &str,
+    page_blob_creator: &impl ArchivePageBlobCreator,
+) -> Option<Arc<ArchiveStorage>> {
+    let page_blob = page_blob_creator.create(topic_id, archive_file_no).await;
+
+    let page_blob = MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(3));
+
+    let blob_props = page_blob.get_blob_properties().await;
+
+    if blob_props.is_err() {
+        return None;
+    }
+
+    let blob_props = blob_props.unwrap();
+
+    if blob_props.get_pages_amount() < CALCULATED_TOC_PAGES_AMOUNT {
+        return None;
     }
+
+    let archive_storage = ArchiveStorage::open_if_exists(archive_file_no, page_blob).await?;
+
+    Some(Arc::new(archive_storage))
 }
@@ -1,8 +1,10 @@
 mod archive_file_no;
+mod archive_stats;
 mod archive_storage;
 mod archive_storage_list;
 mod consts;
 pub mod toc;
 pub use archive_file_no::*;
+pub use archive_stats::*;
 pub use archive_storage::*;
 pub use archive_storage_list::*;
@@ -4,8 +4,12 @@ use my_azure_page_blob_random_access::PageBlobRandomAccess;
 use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::BinaryPayloadBuilder;
 
-use super::{consts::TOC_STRUCTURE_SIZE, ArchiveFileNo};
+use super::{
+    consts::{TOC_SIZE_IN_BITES, TOC_STRUCTURE_SIZE},
+    ArchiveFileNo,
+};
 
+#[derive(Clone, Copy, Debug)]
 pub struct SubPagePosition {
     pub offset: u64,
     pub length: u32,
@@ -49,3 +53,38 @@ pub async fn write_file_position(
 
     page_blob.write(offset, buffer_builder).await.unwrap();
 }
+
+pub async fn read_populated_positions(
+    page_blob: &PageBlobRandomAccess<MyAzurePageBlobStorageWithRetries>,
+    archive_file_no: ArchiveFileNo,
+) -> Vec<(SubPageId, SubPagePosition)> {
+    let toc = page_blob.read(0, TOC_SIZE_IN_BITES).await.unwrap();
+
+    let first_sub_page_id = archive_file_no.get_first_sub_page_id().get_value();
+
+    let mut result = Vec::new();
+
+    for (no, toc_item) in toc.as_slice().chunks(TOC_STRUCTURE_SIZE).enumerate() {
+        let mut offset = [0u8; 8];
+        let mut length = [0u8; 4];
+
+        offset.clone_from_slice(&toc_item[0..8]);
+        length.clone_from_slice(&toc_item[8..12]);
+
+        let length = u32::from_le_bytes(length);
+
+        if length == 0 {
+            continue;
+        }
+
+        result.push((
+            SubPageId::new(first_sub_page_id + no as i64),
+            SubPagePosition {
+                offset: u64::from_le_bytes(offset),
+                length,
+            },
+        ));
+    }
+
+    result
+}
@@ -0,0 +1,372 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use hmac::{Hmac, Mac};
+use my_azure_storage_sdk::{AzureStorageConnection, AzureStorageError};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use sha2::Sha256;
+use tokio::sync::Mutex;
+
+use crate::topics_snapshot::page_blob_storage::TopicsSnapshotPageBlobStorage;
+
+use super::{AuditLogItemProtobufModel, AuditLogProtobufModel};
+
+const BLOB_NAME: &str = "audit-log";
+const SEGMENT_SIZE: usize = 1000;
+
+struct AuditLogSegments {
+    segment_no: i64,
+    // Oldest segment goes first
+    sealed: VecDeque<Vec<Arc<AuditLogItemProtobufModel>>>,
+    active: Vec<Arc<AuditLogItemProtobufModel>>,
+}
+
+// Append-only log of administrative operations. Items are appended to the active segment,
+// which is rewritten on every new item, since admin operations are rare. Full segment is sealed
+// to its own blob. All sealed segments are kept unless retained_segments is set.
+pub struct AuditLog {
+    connection: Arc<AzureStorageConnection>,
+    active_storage: TopicsSnapshotPageBlobStorage,
+    segment_size: usize,
+    retained_segments: Option<usize>,
+    segments: Mutex<AuditLogSegments>,
+}
+
+impl AuditLog {
+    pub async fn load(
+        connection: Arc<AzureStorageConnection>,
+        retained_segments: Option<usize>,
+    ) -> Self {
+        Self::load_with_limits(connection, SEGMENT_SIZE, retained_segments).await
+    }
+
+    // Corrupted segment is not read as an empty log, so service does not start with it
+    async fn load_with_limits(
+        connection: Arc<AzureStorageConnection>,
+        segment_size: usize,
+        retained_segments: Option<usize>,
+    ) -> Self {
+        let active_storage = crate::settings::create_topics_page_blob(&connection, BLOB_NAME).await;
+
+        let active = read_segment(&active_storage).await.unwrap_or_default();
+
+        let mut sealed = VecDeque::new();
+
+        for segment_no in get_retained_segments(active.segment_no, retained_segments) {
+            let storage = get_segment_storage(&connection, segment_no).await;
+
+            if let Some(segment) = read_segment(&storage).await {
+                sealed.push_back(segment.items.into_iter().map(Arc::new).collect());
+            }
+        }
+
+        Self {
+            connection,
+            active_storage,
+            segment_size,
+            retained_segments,
+            segments: Mutex::new(AuditLogSegments {
+                segment_no: active.segment_no,
+                sealed,
+                active: active.items.into_iter().map(Arc::new).collect(),
+            }),
+        }
+    }
+
+    // Item stays in memory even if it is not persisted and is persisted with the next one
+    pub async fn add(&self, item: AuditLogItemProtobufModel) -> Result<(), AzureStorageError> {
+        let mut write_access = self.segments.lock().await;
+
+        write_access.active.push(Arc::new(item));
+
+        if write_access.active.len() >= self.segment_size {
+            return self.seal_active_segment(&mut write_access).await;
+        }
+
+        let model = to_model(write_access.segment_no, write_access.active.as_slice());
+        self.active_storage.write_topics_snapshot(&model).await
+    }
+
+    // Sealed segment is written before the active one is cleared, so items are not lost
+    // if the service stops in between. Segment is sealed again on the next item then
+    async fn seal_active_segment(
+        &self,
+        segments: &mut AuditLogSegments,
+    ) -> Result<(), AzureStorageError> {
+        let segment_no = segments.segment_no;
+
+        get_segment_storage(&self.connection, segment_no)
+            .await
+            .write_topics_snapshot(&to_model(segment_no, segments.active.as_slice()))
+            .await?;
+
+        self.active_storage
+            .write_topics_snapshot(&to_model(segment_no + 1, &[]))
+            .await?;
+
+        let active = std::mem::take(&mut segments.active);
+        segments.sealed.push_back(active);
+        segments.segment_no = segment_no + 1;
+
+        let retained_segments = match self.retained_segments {
+            Some(retained_segments) => retained_segments,
+            None => return Ok(()),
+        };
+
+        while segments.sealed.len() > retained_segments {
+            segments.sealed.pop_front();
+        }
+
+        let expired_segment_no = segment_no - retained_segments as i64;
+
+        if expired_segment_no >= 0 {
+            // Segment is already deleted if the previous seal is repeated
+            let _ = get_segment_storage(&self.connection, expired_segment_no)
+                .await
+                .delete()
+                .await;
+        }
+
+        Ok(())
+    }
+
+    // Newest items go first
+    pub async fn get(
+        &self,
+        topic_id: Option<&str>,
+        from: Option<DateTimeAsMicroseconds>,
+        to: Option<DateTimeAsMicroseconds>,
+    ) -> Vec<Arc<AuditLogItemProtobufModel>> {
+        let read_access = self.segments.lock().await;
+
+        read_access
+            .sealed
+            .iter()
+            .flatten()
+            .chain(read_access.active.iter())
+            .rev()
+            .filter(|itm| match topic_id {
+                Some(topic_id) => itm.topic_id.as_deref() == Some(topic_id),
+                None => true,
+            })
+            .filter(|itm| match from {
+                Some(from) => itm.created >= from.unix_microseconds,
+                None => true,
+            })
+            .filter(|itm| match to {
+                Some(to) => itm.created <= to.unix_microseconds,
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub async fn get_last(&self, action: &str) -> Option<Arc<AuditLogItemProtobufModel>> {
+        let read_access = self.segments.lock().await;
+
+        read_access
+            .sealed
+            .iter()
+            .flatten()
+            .chain(read_access.active.iter())
+            .rev()
+            .find(|itm| itm.action == action)
+            .cloned()
+    }
+}
+
+fn to_model(segment_no: i64, items: &[Arc<AuditLogItemProtobufModel>]) -> AuditLogProtobufModel {
+    AuditLogProtobufModel {
+        items: items.iter().map(|itm| itm.as_ref().clone()).collect(),
+        segment_no,
+    }
+}
+
+// Oldest segment goes first
+fn get_retained_segments(
+    active_segment_no: i64,
+    retained_segments: Option<usize>,
+) -> std::ops::Range<i64> {
+    match retained_segments {
+        Some(retained_segments) => {
+            (active_segment_no - retained_segments as i64).max(0)..active_segment_no
+        }
+        None => 0..active_segment_no,
+    }
+}
+
+async fn get_segment_storage(
+    connection: &Arc<AzureStorageConnection>,
+    segment_no: i64,
+) -> TopicsSnapshotPageBlobStorage {
+    crate::settings::create_topics_page_blob(
+        connection,
+        format!("{}.{}", BLOB_NAME, segment_no).as_str(),
+    )
+    .await
+}
+
+async fn read_segment(storage: &TopicsSnapshotPageBlobStorage) -> Option<AuditLogProtobufModel> {
+    match storage.read_model::<AuditLogProtobufModel>().await {
+        Ok(model) => model,
+        Err(err) => panic!("Can not read audit log. Err: {:?}", err),
+    }
+}
+
+// Secret key is never written to the audit log. Only its HMAC with the server secret is,
+// so the key can not be brute forced from the log. Keys are not told apart without the secret
+pub fn get_api_key_identity(identity_secret: Option<&str>, api_key: &str) -> String {
+    let identity_secret = match identity_secret {
+        Some(identity_secret) => identity_secret,
+        None => return "api-key".to_string(),
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(identity_secret.as_bytes())
+        .expect("HMAC accepts keys of any size");
+    mac.update(api_key.as_bytes());
+
+    let hash = mac.finalize().into_bytes();
+
+    let mut result = "api-key:".to_string();
+    for b in &hash[..8] {
+        result.push_str(format!("{:02x}", b).as_str());
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use my_azure_storage_sdk::AzureStorageConnection;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::audit_log::AuditLogItemProtobufModel;
+
+    use super::{get_api_key_identity, AuditLog};
+
+    fn item(created: i64, action: &str, topic_id: Option<&str>) -> AuditLogItemProtobufModel {
+        AuditLogItemProtobufModel {
+            created,
+            action: action.to_string(),
+            initiator: "test".to_string(),
+            topic_id: topic_id.map(|itm| itm.to_string()),
+            parameters: "".to_string(),
+        }
+    }
+
+    async fn get_created(audit_log: &AuditLog) -> Vec<i64> {
+        audit_log
+            .get(None, None, None)
+            .await
+            .iter()
+            .map(|itm| itm.created)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_items_are_persisted_and_filtered() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let audit_log = AuditLog::load(connection.clone(), None).await;
+
+        audit_log
+            .add(item(1, "DeleteTopic", Some("a")))
+            .await
+            .unwrap();
+        audit_log
+            .add(item(2, "DeleteTopic", Some("b")))
+            .await
+            .unwrap();
+        audit_log
+            .add(item(3, "RestoreTopic", Some("a")))
+            .await
+            .unwrap();
+
+        let audit_log = AuditLog::load(connection, None).await;
+
+        let created: Vec<i64> = audit_log
+            .get(Some("a"), None, None)
+            .await
+            .iter()
+            .map(|itm| itm.created)
+            .collect();
+
+        assert_eq!(created, vec![3, 1]);
+
+        let created: Vec<i64> = audit_log
+            .get(
+                None,
+                Some(DateTimeAsMicroseconds::new(2)),
+                Some(DateTimeAsMicroseconds::new(2)),
+            )
+            .await
+            .iter()
+            .map(|itm| itm.created)
+            .collect();
+
+        assert_eq!(created, vec![2]);
+
+        assert_eq!(audit_log.get_last("DeleteTopic").await.unwrap().created, 2);
+    }
+
+    #[tokio::test]
+    async fn test_segments_are_rotated_with_retention() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let audit_log = AuditLog::load_with_limits(connection.clone(), 2, Some(2)).await;
+
+        for created in 1..=7 {
+            audit_log
+                .add(item(created, "DeleteTopic", None))
+                .await
+                .unwrap();
+        }
+
+        // Segment with items 1 and 2 is expired
+        assert_eq!(get_created(&audit_log).await, vec![7, 6, 5, 4, 3]);
+
+        let audit_log = AuditLog::load_with_limits(connection, 2, Some(2)).await;
+        assert_eq!(get_created(&audit_log).await, vec![7, 6, 5, 4, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_segments_are_kept_without_retention() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let audit_log = AuditLog::load_with_limits(connection.clone(), 2, None).await;
+
+        for created in 1..=5 {
+            audit_log
+                .add(item(created, "DeleteTopic", None))
+                .await
+                .unwrap();
+        }
+
+        let audit_log = AuditLog::load_with_limits(connection, 2, None).await;
+        assert_eq!(get_created(&audit_log).await, vec![5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_api_key_identity() {
+        let identity = get_api_key_identity(Some("server-secret"), "api-key-a");
+
+        assert_eq!(identity.len(), "api-key:".len() + 16);
+        assert!(!identity.contains("api-key-a"));
+
+        assert_eq!(
+            identity,
+            get_api_key_identity(Some("server-secret"), "api-key-a")
+        );
+        assert_ne!(
+            identity,
+            get_api_key_identity(Some("server-secret"), "api-key-b")
+        );
+        assert_ne!(
+            identity,
+            get_api_key_identity(Some("other-secret"), "api-key-a")
+        );
+
+        assert_eq!(get_api_key_identity(None, "api-key-a"), "api-key");
+    }
+}
@@ -0,0 +1,5 @@
+mod audit_log;
+pub use audit_log::*;
+#[allow(non_snake_case)]
+mod protobuf_model;
+pub use protobuf_model::*;
@@ -0,0 +1,22 @@
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuditLogProtobufModel {
+    #[prost(message, repeated, tag = "1")]
+    pub items: Vec<AuditLogItemProtobufModel>,
+    // Segment of the active blob. Sealed segments are written to the blobs with their numbers
+    #[prost(int64, tag = "2")]
+    pub segment_no: i64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuditLogItemProtobufModel {
+    #[prost(int64, tag = "1")]
+    pub created: i64,
+    #[prost(string, tag = "2")]
+    pub action: String,
+    #[prost(string, tag = "3")]
+    pub initiator: String,
+    #[prost(string, optional, tag = "4")]
+    pub topic_id: Option<String>,
+    #[prost(string, tag = "5")]
+    pub parameters: String,
+}
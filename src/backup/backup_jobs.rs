@@ -0,0 +1,72 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub enum BackupJobState {
+    Running,
+    Finished {
+        topics: usize,
+        sub_pages: usize,
+        files: usize,
+        size: usize,
+    },
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupJob {
+    pub name: String,
+    pub target: String,
+    pub started: DateTimeAsMicroseconds,
+    pub finished: Option<DateTimeAsMicroseconds>,
+    pub state: BackupJobState,
+}
+
+// Backups made since the start of the service. Only one backup can run at a time
+pub struct BackupJobs {
+    jobs: Mutex<Vec<BackupJob>>,
+}
+
+impl BackupJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(Vec::new()),
+        }
+    }
+
+    // Returns false if a backup is already running
+    pub async fn try_start(&self, name: &str, target: String, now: DateTimeAsMicroseconds) -> bool {
+        let mut write_access = self.jobs.lock().await;
+
+        if write_access
+            .iter()
+            .any(|job| matches!(job.state, BackupJobState::Running))
+        {
+            return false;
+        }
+
+        write_access.push(BackupJob {
+            name: name.to_string(),
+            target,
+            started: now,
+            finished: None,
+            state: BackupJobState::Running,
+        });
+
+        true
+    }
+
+    pub async fn finish(&self, name: &str, state: BackupJobState, now: DateTimeAsMicroseconds) {
+        let mut write_access = self.jobs.lock().await;
+
+        if let Some(job) = write_access.iter_mut().rev().find(|job| job.name == name) {
+            job.finished = Some(now);
+            job.state = state;
+        }
+    }
+
+    pub async fn get_all(&self) -> Vec<BackupJob> {
+        let read_access = self.jobs.lock().await;
+        read_access.clone()
+    }
+}
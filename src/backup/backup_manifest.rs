@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+pub const BACKUP_MANIFEST_VERSION: u32 = 1;
+
+const BACKUP_MANIFEST_FILE_NAME: &str = "manifest.json";
+
+// Manifest is written the last, so a backup without manifest is incomplete.
+// Paths of files are relative to the folder of the backup
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupManifestModel {
+    #[serde(rename = "version")]
+    pub version: u32,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "created")]
+    pub created: String,
+    #[serde(rename = "appVersion")]
+    pub app_version: String,
+    #[serde(rename = "topicsSnapshot")]
+    pub topics_snapshot: BackupFileModel,
+    #[serde(rename = "activePages")]
+    pub active_pages: BackupFileModel,
+    #[serde(rename = "topics")]
+    pub topics: Vec<BackupTopicModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupFileModel {
+    #[serde(rename = "path")]
+    pub path: String,
+    #[serde(rename = "size")]
+    pub size: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupTopicModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    // Fence: backup has messages of the topic before this message id
+    #[serde(rename = "messageId")]
+    pub message_id: i64,
+    #[serde(rename = "subPages")]
+    pub sub_pages: usize,
+    // Chunks of archived sub pages
+    #[serde(rename = "archive")]
+    pub archive: Vec<BackupFileModel>,
+    #[serde(rename = "yearIndexes")]
+    pub year_indexes: Vec<BackupYearIndexModel>,
+    // Page blobs of the topic in archive storage: zstd dictionaries and header index
+    #[serde(rename = "blobs")]
+    pub blobs: Vec<BackupBlobModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupYearIndexModel {
+    #[serde(rename = "year")]
+    pub year: u32,
+    #[serde(rename = "file")]
+    pub file: BackupFileModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupBlobModel {
+    #[serde(rename = "blobName")]
+    pub blob_name: String,
+    #[serde(rename = "file")]
+    pub file: BackupFileModel,
+}
+
+impl BackupManifestModel {
+    pub fn get_manifest_path(name: &str) -> String {
+        format!("{}/{}", name, BACKUP_MANIFEST_FILE_NAME)
+    }
+
+    pub fn get_file_path(name: &str, file: &BackupFileModel) -> String {
+        format!("{}/{}", name, file.path)
+    }
+
+    pub fn parse(content: &[u8]) -> Result<Self, String> {
+        let result: Self = match serde_json::from_slice(content) {
+            Ok(result) => result,
+            Err(err) => return Err(format!("Invalid backup manifest. Err: {:?}", err)),
+        };
+
+        if result.version > BACKUP_MANIFEST_VERSION {
+            return Err(format!(
+                "Backup manifest version {} is not supported. Max supported version is {}",
+                result.version, BACKUP_MANIFEST_VERSION
+            ));
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_files(&self) -> Vec<&BackupFileModel> {
+        let mut result = vec![&self.topics_snapshot, &self.active_pages];
+
+        for topic in &self.topics {
+            result.extend(topic.archive.iter());
+            result.extend(topic.year_indexes.iter().map(|itm| &itm.file));
+            result.extend(topic.blobs.iter().map(|itm| &itm.file));
+        }
+
+        result
+    }
+
+    pub fn get_sub_pages_amount(&self) -> usize {
+        self.topics.iter().map(|itm| itm.sub_pages).sum()
+    }
+}
+
+impl BackupTopicModel {
+    pub fn new(topic_id: &str, message_id: i64) -> Self {
+        Self {
+            topic_id: topic_id.to_string(),
+            message_id,
+            sub_pages: 0,
+            archive: vec![],
+            year_indexes: vec![],
+            blobs: vec![],
+        }
+    }
+
+    pub fn get_archive_chunk_path(topic_id: &str, first_sub_page_id: i64) -> String {
+        format!("topics/{}/archive/{:019}.pb", topic_id, first_sub_page_id)
+    }
+
+    pub fn get_year_index_path(topic_id: &str, year: u32) -> String {
+        format!("topics/{}/year-index/{}", topic_id, year)
+    }
+
+    pub fn get_blob_path(topic_id: &str, blob_name: &str) -> String {
+        format!("topics/{}/blobs/{}", topic_id, blob_name)
+    }
+}
+
+// Name is used as a folder of the backup
+pub fn is_valid_backup_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> BackupFileModel {
+        BackupFileModel {
+            path: path.to_string(),
+            size: 10,
+        }
+    }
+
+    fn manifest() -> BackupManifestModel {
+        let mut topic = BackupTopicModel::new("orders", 250_000);
+        topic.sub_pages = 2;
+        topic.archive.push(file(
+            BackupTopicModel::get_archive_chunk_path("orders", 0).as_str(),
+        ));
+        topic.year_indexes.push(BackupYearIndexModel {
+            year: 2024,
+            file: file(BackupTopicModel::get_year_index_path("orders", 2024).as_str()),
+        });
+        topic.blobs.push(BackupBlobModel {
+            blob_name: ".zstd-dictionaries".to_string(),
+            file: file(BackupTopicModel::get_blob_path("orders", ".zstd-dictionaries").as_str()),
+        });
+
+        BackupManifestModel {
+            version: BACKUP_MANIFEST_VERSION,
+            name: "nightly".to_string(),
+            created: "2024-05-01T00:00:00".to_string(),
+            app_version: "1.0.0".to_string(),
+            topics_snapshot: file("topics-snapshot.pb"),
+            active_pages: file("active-pages.pb"),
+            topics: vec![topic],
+        }
+    }
+
+    #[test]
+    fn test_serialize_and_parse() {
+        let content = serde_json::to_vec(&manifest()).unwrap();
+
+        let result = BackupManifestModel::parse(content.as_slice()).unwrap();
+
+        assert_eq!(result.name, "nightly");
+        assert_eq!(result.get_files().len(), 5);
+        assert_eq!(result.get_sub_pages_amount(), 2);
+        assert_eq!(
+            result.topics[0].archive[0].path,
+            "topics/orders/archive/0000000000000000000.pb"
+        );
+        assert_eq!(
+            BackupManifestModel::get_file_path("nightly", &result.active_pages),
+            "nightly/active-pages.pb"
+        );
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut manifest = manifest();
+        manifest.version = BACKUP_MANIFEST_VERSION + 1;
+
+        let content = serde_json::to_vec(&manifest).unwrap();
+
+        assert!(BackupManifestModel::parse(content.as_slice()).is_err());
+        assert!(BackupManifestModel::parse(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_backup_name() {
+        assert!(is_valid_backup_name("2024-05-01_nightly"));
+        assert!(!is_valid_backup_name(""));
+        assert!(!is_valid_backup_name("../nightly"));
+        assert!(!is_valid_backup_name("a/b"));
+    }
+}
@@ -0,0 +1,20 @@
+// Archived sub pages are kept in backup as they are stored in archive: compressed and encrypted
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BackupSubPageProtobufModel {
+    #[prost(int64, tag = "1")]
+    pub sub_page_id: i64,
+    #[prost(bytes, tag = "2")]
+    pub payload: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BackupSubPagesProtobufModel {
+    #[prost(message, repeated, tag = "1")]
+    pub sub_pages: Vec<BackupSubPageProtobufModel>,
+}
+
+impl BackupSubPagesProtobufModel {
+    pub fn get_payload_size(&self) -> usize {
+        self.sub_pages.iter().map(|itm| itm.payload.len()).sum()
+    }
+}
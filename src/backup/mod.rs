@@ -0,0 +1,8 @@
+mod backup_jobs;
+mod backup_manifest;
+mod backup_sub_pages;
+mod restore_cli;
+pub use backup_jobs::*;
+pub use backup_manifest::*;
+pub use backup_sub_pages::*;
+pub use restore_cli::*;
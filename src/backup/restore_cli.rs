@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use crate::app::AppContext;
+
+pub const RESTORE_BACKUP_COMMAND: &str = "restore-backup";
+
+pub const RESTORE_BACKUP_USAGE: &str =
+    "Usage: restore-backup --source local|blob --name <backup name>";
+
+pub struct RestoreBackupCliArgs {
+    pub source: String,
+    pub name: String,
+}
+
+impl RestoreBackupCliArgs {
+    // Args go after the command name
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut source = None;
+        let mut name = None;
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let value = match args.next() {
+                Some(value) => value.to_string(),
+                None => return Err(format!("Value of {} is missing", arg)),
+            };
+
+            match arg.as_str() {
+                "--source" => source = Some(value),
+                "--name" => name = Some(value),
+                _ => return Err(format!("Unknown argument {}", arg)),
+            }
+        }
+
+        Ok(Self {
+            source: source.ok_or("--source is missing".to_string())?,
+            name: name.ok_or("--name is missing".to_string())?,
+        })
+    }
+}
+
+// Restores backup into storages of the settings instead of starting the service.
+// Service is started afterwards as usual and picks restored data up
+pub async fn run_restore_cli(app: Arc<AppContext>, args: &[String]) {
+    let args = match RestoreBackupCliArgs::parse(args) {
+        Ok(args) => args,
+        Err(err) => {
+            println!("{}\n{}", err, RESTORE_BACKUP_USAGE);
+            return;
+        }
+    };
+
+    let storage = match crate::operations::get_backup_storage(&app, args.source.as_str()) {
+        Ok(storage) => storage,
+        Err(err) => {
+            println!("{:?}", err);
+            return;
+        }
+    };
+
+    match crate::operations::restore_backup(&app, &storage, args.name.as_str()).await {
+        Ok(manifest) => {
+            println!(
+                "Restored backup {} created at {}: {} topics, {} sub pages",
+                manifest.name,
+                manifest.created,
+                manifest.topics.len(),
+                manifest.get_sub_pages_amount()
+            );
+        }
+        Err(err) => println!("Restore failed: {:?}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RestoreBackupCliArgs;
+
+    fn to_args(src: &str) -> Vec<String> {
+        src.split(' ').map(|itm| itm.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = RestoreBackupCliArgs::parse(&to_args("--source blob --name nightly")).unwrap();
+
+        assert_eq!(args.source, "blob");
+        assert_eq!(args.name, "nightly");
+
+        assert!(RestoreBackupCliArgs::parse(&to_args("--source blob")).is_err());
+        assert!(RestoreBackupCliArgs::parse(&to_args("--source blob --name")).is_err());
+        assert!(RestoreBackupCliArgs::parse(&to_args("--source blob --name a --x 1")).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, sync::Arc};
+
+use base64::Engine;
+
+pub const ENCRYPTION_KEY_SIZE: usize = 32;
+
+pub struct EncryptionKey {
+    pub id: u32,
+    pub data: [u8; ENCRYPTION_KEY_SIZE],
+}
+
+// Payloads are decrypted with the key id from their header,
+// so rotated keys must be provided as long as there are payloads encrypted with them
+pub trait EncryptionKeyProvider: Send + Sync {
+    fn get_key(&self, key_id: u32) -> Option<Arc<EncryptionKey>>;
+}
+
+pub struct LocalKeyFileProvider {
+    keys: HashMap<u32, Arc<EncryptionKey>>,
+}
+
+impl LocalKeyFileProvider {
+    pub fn load(file_name: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(file_name)
+            .map_err(|err| format!("Can not read key file {}. Err: {:?}", file_name, err))?;
+
+        Self::parse(content.as_str())
+    }
+
+    // Each line is <key_id>:<base64 of 32 bytes key>. Empty lines and lines starting with # are skipped
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut keys = HashMap::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key_id, key) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid key line: {}", line))?;
+
+            let key_id: u32 = key_id
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid key id: {}", key_id))?;
+
+            let key = base64::engine::general_purpose::STANDARD
+                .decode(key.trim())
+                .map_err(|err| format!("Key {} is not a valid base64. Err: {:?}", key_id, err))?;
+
+            if key.len() != ENCRYPTION_KEY_SIZE {
+                return Err(format!(
+                    "Key {} must be {} bytes long",
+                    key_id, ENCRYPTION_KEY_SIZE
+                ));
+            }
+
+            let mut data = [0u8; ENCRYPTION_KEY_SIZE];
+            data.copy_from_slice(key.as_slice());
+
+            if keys
+                .insert(key_id, Arc::new(EncryptionKey { id: key_id, data }))
+                .is_some()
+            {
+                return Err(format!("Key {} is duplicated", key_id));
+            }
+        }
+
+        Ok(Self { keys })
+    }
+}
+
+impl EncryptionKeyProvider for LocalKeyFileProvider {
+    fn get_key(&self, key_id: u32) -> Option<Arc<EncryptionKey>> {
+        self.keys.get(&key_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncryptionKeyProvider, LocalKeyFileProvider};
+
+    #[test]
+    fn test_parse_key_file() {
+        let content = "# keys\n\n1: AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=\n2:Hx4dHBsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAAA=\n";
+
+        let provider = LocalKeyFileProvider::parse(content).unwrap();
+
+        let key = provider.get_key(1).unwrap();
+        assert_eq!(key.id, 1);
+        assert_eq!(key.data[31], 31);
+
+        assert!(provider.get_key(2).is_some());
+        assert!(provider.get_key(3).is_none());
+    }
+
+    #[test]
+    fn test_parse_invalid_key_file() {
+        assert!(LocalKeyFileProvider::parse("1:AAEC").is_err());
+        assert!(LocalKeyFileProvider::parse("key:AAEC").is_err());
+        assert!(LocalKeyFileProvider::parse("AAEC").is_err());
+    }
+}
@@ -0,0 +1,4 @@
+mod key_provider;
+mod payload_encryption;
+pub use key_provider::*;
+pub use payload_encryption::*;
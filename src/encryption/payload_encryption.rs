@@ -0,0 +1,248 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use my_service_bus::shared::sub_page::SubPageId;
+
+use super::EncryptionKeyProvider;
+
+// Encrypted payload is the compressed payload wrapped with the header:
+// magic[4] key_id[4] nonce[12] and AES-256-GCM ciphertext with tag after it.
+// Payloads without the header are read as they are.
+// Topic and sub page id are authenticated as well, so a payload moved to another sub page is not decrypted
+const ENCRYPTED_PAYLOAD_MAGIC: [u8; 4] = *b"MSBE";
+const NONCE_SIZE: usize = 12;
+const ENCRYPTION_HEADER_SIZE: usize = 4 + 4 + NONCE_SIZE;
+
+#[derive(Debug)]
+pub enum EncryptionError {
+    UnknownKey(u32),
+    InvalidHeader,
+    DecryptionFailed,
+}
+
+pub struct PayloadEncryption {
+    key_provider: Option<Arc<dyn EncryptionKeyProvider>>,
+    // Key id new payloads of the topic are encrypted with. Keys are rotated by changing it
+    by_topic: HashMap<String, u32>,
+}
+
+impl PayloadEncryption {
+    pub fn new(key_provider: Option<Arc<dyn EncryptionKeyProvider>>) -> Self {
+        Self {
+            key_provider,
+            by_topic: HashMap::new(),
+        }
+    }
+
+    pub fn set_topic_key(&mut self, topic_id: &str, key_id: u32) -> Result<(), EncryptionError> {
+        self.get_cipher(key_id)?;
+        self.by_topic.insert(topic_id.to_string(), key_id);
+        Ok(())
+    }
+
+    fn get_cipher(&self, key_id: u32) -> Result<Aes256Gcm, EncryptionError> {
+        let key = self
+            .key_provider
+            .as_ref()
+            .and_then(|provider| provider.get_key(key_id))
+            .ok_or(EncryptionError::UnknownKey(key_id))?;
+
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.data)))
+    }
+
+    // Payload of the topic without encryption key is returned as it is
+    pub fn encrypt(&self, topic_id: &str, sub_page_id: SubPageId, payload: Vec<u8>) -> Vec<u8> {
+        let key_id = match self.by_topic.get(topic_id) {
+            Some(key_id) => *key_id,
+            None => return payload,
+        };
+
+        // Key is checked when it is set to the topic
+        let cipher = self.get_cipher(key_id).unwrap();
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = get_aad(topic_id, sub_page_id);
+
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: payload.as_slice(),
+                    aad: aad.as_slice(),
+                },
+            )
+            .unwrap();
+
+        let mut result = Vec::with_capacity(ENCRYPTION_HEADER_SIZE + ciphertext.len());
+        result.extend_from_slice(&ENCRYPTED_PAYLOAD_MAGIC);
+        result.extend_from_slice(&key_id.to_le_bytes());
+        result.extend_from_slice(nonce.as_slice());
+        result.extend_from_slice(ciphertext.as_slice());
+        result
+    }
+
+    pub fn decrypt<'s>(
+        &self,
+        topic_id: &str,
+        sub_page_id: SubPageId,
+        payload: &'s [u8],
+    ) -> Result<Cow<'s, [u8]>, EncryptionError> {
+        if !payload.starts_with(&ENCRYPTED_PAYLOAD_MAGIC) {
+            return Ok(Cow::Borrowed(payload));
+        }
+
+        if payload.len() < ENCRYPTION_HEADER_SIZE {
+            return Err(EncryptionError::InvalidHeader);
+        }
+
+        let mut key_id = [0u8; 4];
+        key_id.copy_from_slice(&payload[4..8]);
+        let key_id = u32::from_le_bytes(key_id);
+
+        let cipher = self.get_cipher(key_id)?;
+
+        let nonce = Nonce::from_slice(&payload[8..ENCRYPTION_HEADER_SIZE]);
+
+        let aad = get_aad(topic_id, sub_page_id);
+
+        let result = cipher
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: &payload[ENCRYPTION_HEADER_SIZE..],
+                    aad: aad.as_slice(),
+                },
+            )
+            .map_err(|_| EncryptionError::DecryptionFailed)?;
+
+        Ok(Cow::Owned(result))
+    }
+}
+
+// topic_id[..] sub_page_id[8]
+fn get_aad(topic_id: &str, sub_page_id: SubPageId) -> Vec<u8> {
+    let mut result = Vec::with_capacity(topic_id.len() + 8);
+    result.extend_from_slice(topic_id.as_bytes());
+    result.extend_from_slice(&sub_page_id.get_value().to_le_bytes());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use my_service_bus::shared::sub_page::SubPageId;
+
+    use crate::encryption::LocalKeyFileProvider;
+
+    use super::{EncryptionError, PayloadEncryption};
+
+    const KEYS: &str = "1:AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=\n2:Hx4dHBsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAAA=";
+
+    fn create_encryption(keys: &str) -> PayloadEncryption {
+        let provider = LocalKeyFileProvider::parse(keys).unwrap();
+        PayloadEncryption::new(Some(Arc::new(provider)))
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let mut encryption = create_encryption(KEYS);
+        encryption.set_topic_key("pii", 1).unwrap();
+
+        let payload = b"compressed payload".to_vec();
+
+        let encrypted = encryption.encrypt("pii", SubPageId::new(5), payload.clone());
+        assert_ne!(encrypted, payload);
+
+        let decrypted = encryption
+            .decrypt("pii", SubPageId::new(5), encrypted.as_slice())
+            .unwrap();
+        assert_eq!(decrypted.as_ref(), payload.as_slice());
+
+        let not_encrypted = encryption.encrypt("other", SubPageId::new(5), payload.clone());
+        assert_eq!(not_encrypted, payload);
+        assert_eq!(
+            encryption
+                .decrypt("other", SubPageId::new(5), not_encrypted.as_slice())
+                .unwrap()
+                .as_ref(),
+            payload.as_slice()
+        );
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let mut encryption = create_encryption(KEYS);
+        encryption.set_topic_key("pii", 1).unwrap();
+        let encrypted_with_first = encryption.encrypt("pii", SubPageId::new(5), b"first".to_vec());
+
+        encryption.set_topic_key("pii", 2).unwrap();
+        let encrypted_with_second =
+            encryption.encrypt("pii", SubPageId::new(5), b"second".to_vec());
+
+        assert_eq!(
+            encryption
+                .decrypt("pii", SubPageId::new(5), encrypted_with_first.as_slice())
+                .unwrap()
+                .as_ref(),
+            b"first"
+        );
+
+        assert_eq!(
+            encryption
+                .decrypt("pii", SubPageId::new(5), encrypted_with_second.as_slice())
+                .unwrap()
+                .as_ref(),
+            b"second"
+        );
+
+        let without_first_key = create_encryption(&KEYS[KEYS.find('\n').unwrap()..]);
+
+        assert!(matches!(
+            without_first_key.decrypt("pii", SubPageId::new(5), encrypted_with_first.as_slice()),
+            Err(EncryptionError::UnknownKey(1))
+        ));
+    }
+
+    #[test]
+    fn test_payload_of_other_sub_page() {
+        let mut encryption = create_encryption(KEYS);
+        encryption.set_topic_key("pii", 1).unwrap();
+        encryption.set_topic_key("other", 1).unwrap();
+
+        let encrypted = encryption.encrypt("pii", SubPageId::new(5), b"payload".to_vec());
+
+        assert!(matches!(
+            encryption.decrypt("pii", SubPageId::new(6), encrypted.as_slice()),
+            Err(EncryptionError::DecryptionFailed)
+        ));
+
+        assert!(matches!(
+            encryption.decrypt("other", SubPageId::new(5), encrypted.as_slice()),
+            Err(EncryptionError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_payload() {
+        let mut encryption = create_encryption(KEYS);
+        encryption.set_topic_key("pii", 1).unwrap();
+
+        let mut encrypted = encryption.encrypt("pii", SubPageId::new(5), b"payload".to_vec());
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+
+        assert!(matches!(
+            encryption.decrypt("pii", SubPageId::new(5), encrypted.as_slice()),
+            Err(EncryptionError::DecryptionFailed)
+        ));
+
+        assert!(matches!(
+            encryption.set_topic_key("pii", 3),
+            Err(EncryptionError::UnknownKey(3))
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use hyper::{client::HttpConnector, Body, Client, Method, Request, StatusCode};
+use hyper_rustls::HttpsConnector;
+use sha2::Sha256;
+
+use super::FileStorageError;
+
+const API_VERSION: &str = "2021-08-06";
+
+const DEV_STORAGE_ACCOUNT_NAME: &str = "devstoreaccount1";
+const DEV_STORAGE_ACCOUNT_KEY: &str =
+    "Eby8vdM02xNOcqFlqUwW1gMIxVFBVBoXNc7/5A3k4Qm/N14DeXvLH3e+IXsqsUH54cWWJhqqAzyvqMcqV8Dtaw==";
+const DEV_STORAGE_BLOB_ENDPOINT: &str = "http://127.0.0.1:10000/devstoreaccount1";
+
+// Storage sdk has no conditional writes of blobs, so blobs which are written only if their ETag
+// is not changed are read and written by Blob service REST requests signed with the account key
+pub struct BlobAccount {
+    account_name: String,
+    account_key: Vec<u8>,
+    blob_endpoint: String,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl BlobAccount {
+    // Returns None if the connection string has no account key
+    pub fn from_conn_string(conn_string: &str) -> Option<Self> {
+        let mut protocol = "https";
+        let mut account_name = None;
+        let mut account_key = None;
+        let mut endpoint_suffix = "core.windows.net";
+        let mut blob_endpoint = None;
+
+        for item in conn_string.split(';') {
+            let (key, value) = match item.split_once('=') {
+                Some(key_value) => key_value,
+                None => continue,
+            };
+
+            match key.trim() {
+                "UseDevelopmentStorage" if value.trim() == "true" => {
+                    account_name = Some(DEV_STORAGE_ACCOUNT_NAME.to_string());
+                    account_key = Some(DEV_STORAGE_ACCOUNT_KEY.to_string());
+                    blob_endpoint = Some(DEV_STORAGE_BLOB_ENDPOINT.to_string());
+                }
+                "DefaultEndpointsProtocol" => protocol = value.trim(),
+                "AccountName" => account_name = Some(value.trim().to_string()),
+                "AccountKey" => account_key = Some(value.trim().to_string()),
+                "EndpointSuffix" => endpoint_suffix = value.trim(),
+                "BlobEndpoint" => blob_endpoint = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+
+        let account_name = account_name?;
+        let account_key = base64::engine::general_purpose::STANDARD
+            .decode(account_key?)
+            .ok()?;
+
+        let blob_endpoint = match blob_endpoint {
+            Some(blob_endpoint) => blob_endpoint.trim_end_matches('/').to_string(),
+            None => format!("{}://{}.blob.{}", protocol, account_name, endpoint_suffix),
+        };
+
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Some(Self {
+            account_name,
+            account_key,
+            blob_endpoint,
+            client: Client::builder().build(connector),
+        })
+    }
+
+    // Returns the content and the ETag of the blob or None if it does not exist
+    pub async fn download_with_etag(
+        &self,
+        container: &str,
+        blob_name: &str,
+    ) -> Result<Option<(Vec<u8>, String)>, FileStorageError> {
+        let request = self.build_request(Method::GET, container, blob_name, None, Vec::new())?;
+
+        let response = self.client.request(request).await.map_err(to_http_error)?;
+        let status = response.status();
+
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let etag = get_etag(&response);
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(to_http_error)?;
+
+        if status != StatusCode::OK {
+            return Err(to_status_error(status, body.as_ref()));
+        }
+
+        let etag = etag.ok_or(FileStorageError::HttpError(
+            "ETag is missing in the response".to_string(),
+        ))?;
+
+        Ok(Some((body.to_vec(), etag)))
+    }
+
+    // Block blob is written only if its ETag is still the same. None means that blob must not exist.
+    // Returns the new ETag or None if the blob is changed by someone else
+    pub async fn upload_if_match(
+        &self,
+        container: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        etag: Option<&str>,
+    ) -> Result<Option<String>, FileStorageError> {
+        let request = self.build_request(Method::PUT, container, blob_name, etag, content)?;
+
+        let response = self.client.request(request).await.map_err(to_http_error)?;
+        let status = response.status();
+
+        // 412 is returned if ETag does not match, 409 if the blob exists and must not
+        if status == StatusCode::PRECONDITION_FAILED || status == StatusCode::CONFLICT {
+            return Ok(None);
+        }
+
+        let new_etag = get_etag(&response);
+
+        if status != StatusCode::CREATED {
+            let body = hyper::body::to_bytes(response.into_body())
+                .await
+                .map_err(to_http_error)?;
+
+            return Err(to_status_error(status, body.as_ref()));
+        }
+
+        let new_etag = new_etag.ok_or(FileStorageError::HttpError(
+            "ETag is missing in the response".to_string(),
+        ))?;
+
+        Ok(Some(new_etag))
+    }
+
+    fn build_request(
+        &self,
+        method: Method,
+        container: &str,
+        blob_name: &str,
+        etag: Option<&str>,
+        content: Vec<u8>,
+    ) -> Result<Request<Body>, FileStorageError> {
+        let path = format!("/{}/{}", container, encode_blob_name(blob_name));
+        let url = format!("{}{}", self.blob_endpoint, path);
+
+        let date = chrono::Utc::now()
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+
+        let is_put = method == Method::PUT;
+
+        let content_length = if content.is_empty() {
+            String::new()
+        } else {
+            content.len().to_string()
+        };
+
+        let content_type = if is_put {
+            "application/octet-stream"
+        } else {
+            ""
+        };
+
+        let (if_match, if_none_match) = match (is_put, etag) {
+            (false, _) => ("", ""),
+            (true, Some(etag)) => (etag, ""),
+            (true, None) => ("", "*"),
+        };
+
+        let mut canonicalized_headers = Vec::new();
+
+        if is_put {
+            canonicalized_headers.push(("x-ms-blob-type", "BlockBlob"));
+        }
+
+        canonicalized_headers.push(("x-ms-date", date.as_str()));
+        canonicalized_headers.push(("x-ms-version", API_VERSION));
+
+        let string_to_sign = format!(
+            "{}\n\n\n{}\n\n{}\n\n\n{}\n{}\n\n\n{}/{}{}{}",
+            method.as_str(),
+            content_length,
+            content_type,
+            if_match,
+            if_none_match,
+            canonicalized_headers
+                .iter()
+                .map(|(key, value)| format!("{}:{}\n", key, value))
+                .collect::<String>(),
+            self.account_name,
+            get_url_path(self.blob_endpoint.as_str()),
+            path
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.account_key.as_slice())
+            .expect("HMAC accepts keys of any size");
+        mac.update(string_to_sign.as_bytes());
+        let signature =
+            base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes());
+
+        let mut builder = Request::builder().method(method).uri(url).header(
+            "Authorization",
+            format!("SharedKey {}:{}", self.account_name, signature),
+        );
+
+        for (key, value) in canonicalized_headers {
+            builder = builder.header(key, value);
+        }
+
+        if is_put {
+            builder = builder
+                .header("Content-Type", content_type)
+                .header("Content-Length", content.len());
+        }
+
+        if !if_match.is_empty() {
+            builder = builder.header("If-Match", if_match);
+        }
+
+        if !if_none_match.is_empty() {
+            builder = builder.header("If-None-Match", if_none_match);
+        }
+
+        builder
+            .body(Body::from(content))
+            .map_err(|err| FileStorageError::HttpError(format!("{:?}", err)))
+    }
+}
+
+// Path of the endpoint is a part of the signed resource. Endpoint of the emulator has the account in it
+fn get_url_path(blob_endpoint: &str) -> &str {
+    let without_scheme = match blob_endpoint.find("://") {
+        Some(index) => &blob_endpoint[index + 3..],
+        None => blob_endpoint,
+    };
+
+    match without_scheme.find('/') {
+        Some(index) => &without_scheme[index..],
+        None => "",
+    }
+}
+
+// Folders of the blob name are kept as they are
+fn encode_blob_name(blob_name: &str) -> String {
+    let mut result = String::with_capacity(blob_name.len());
+
+    for b in blob_name.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                result.push(b as char)
+            }
+            _ => result.push_str(format!("%{:02X}", b).as_str()),
+        }
+    }
+
+    result
+}
+
+fn get_etag(response: &hyper::Response<Body>) -> Option<String> {
+    response
+        .headers()
+        .get("ETag")
+        .and_then(|itm| itm.to_str().ok())
+        .map(|itm| itm.to_string())
+}
+
+fn to_http_error(err: impl std::fmt::Debug) -> FileStorageError {
+    FileStorageError::HttpError(format!("{:?}", err))
+}
+
+fn to_status_error(status: StatusCode, body: &[u8]) -> FileStorageError {
+    FileStorageError::HttpError(format!(
+        "Status code {}. {}",
+        status,
+        String::from_utf8_lossy(body)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_blob_name, get_url_path, BlobAccount};
+
+    #[test]
+    fn test_from_conn_string() {
+        let account = BlobAccount::from_conn_string(
+            "DefaultEndpointsProtocol=https;AccountName=acc;AccountKey=a2V5;EndpointSuffix=core.windows.net",
+        )
+        .unwrap();
+
+        assert_eq!(account.account_name, "acc");
+        assert_eq!(account.account_key, b"key".to_vec());
+        assert_eq!(account.blob_endpoint, "https://acc.blob.core.windows.net");
+
+        let account = BlobAccount::from_conn_string("UseDevelopmentStorage=true").unwrap();
+        assert_eq!(
+            get_url_path(account.blob_endpoint.as_str()),
+            "/devstoreaccount1"
+        );
+
+        assert!(BlobAccount::from_conn_string("AccountName=acc").is_none());
+    }
+
+    #[test]
+    fn test_encode_blob_name() {
+        assert_eq!(encode_blob_name(".leader-lease"), ".leader-lease");
+        assert_eq!(encode_blob_name("a b/c+d"), "a%20b/c%2Bd");
+    }
+}
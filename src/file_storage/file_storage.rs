@@ -0,0 +1,447 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio::io::AsyncWriteExt;
+
+use my_azure_storage_sdk::{
+    blob::BlobApi, blob_container::BlobContainersApi, block_blob::BlockBlobApi,
+    AzureStorageConnection, AzureStorageError,
+};
+
+use super::BlobAccount;
+
+#[derive(Debug)]
+pub enum FileStorageError {
+    IoError(std::io::Error),
+    AzureStorageError(AzureStorageError),
+    // Blob container is set without the account key for conditional writes
+    ConditionalWriteNotSupported,
+    HttpError(String),
+}
+
+impl From<std::io::Error> for FileStorageError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+impl From<AzureStorageError> for FileStorageError {
+    fn from(src: AzureStorageError) -> Self {
+        Self::AzureStorageError(src)
+    }
+}
+
+// Files are addressed by path relative to the directory or to the container. Folders are separated by '/'
+pub enum FileStorage {
+    LocalDir(PathBuf),
+    BlobContainer {
+        connection: Arc<AzureStorageConnection>,
+        container: String,
+        // Set only if files of the container are written conditionally
+        account: Option<Arc<BlobAccount>>,
+    },
+}
+
+impl FileStorage {
+    pub fn get_name(&self) -> String {
+        match self {
+            Self::LocalDir(path) => format!("dir {}", path.to_string_lossy()),
+            Self::BlobContainer { container, .. } => format!("container {}", container),
+        }
+    }
+
+    pub async fn write_file(&self, path: &str, content: Vec<u8>) -> Result<(), FileStorageError> {
+        match self {
+            Self::LocalDir(dir) => {
+                let file_name = dir.join(path);
+
+                if let Some(parent) = file_name.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                // File appears under its name only when it is completely written
+                let mut tmp_file = file_name.clone().into_os_string();
+                tmp_file.push(".tmp");
+
+                tokio::fs::write(&tmp_file, content).await?;
+                tokio::fs::rename(&tmp_file, file_name).await?;
+            }
+            Self::BlobContainer {
+                connection,
+                container,
+                ..
+            } => {
+                connection
+                    .create_container_if_not_exists(container.as_str())
+                    .await?;
+
+                connection
+                    .upload_block_blob(container.as_str(), path, content)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // File is written by chunks to tmp_path and appears under its final path on commit
+    pub async fn create_file(&self, tmp_path: &str) -> Result<FileStorageWriter, FileStorageError> {
+        match self {
+            Self::LocalDir(dir) => {
+                let tmp_file = dir.join(tmp_path);
+
+                if let Some(parent) = tmp_file.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                let file = tokio::fs::File::create(&tmp_file).await?;
+
+                Ok(FileStorageWriter::LocalFile {
+                    dir: dir.clone(),
+                    file,
+                    tmp_file,
+                })
+            }
+            Self::BlobContainer {
+                connection,
+                container,
+                ..
+            } => Ok(FileStorageWriter::BlockBlob {
+                connection: connection.clone(),
+                container: container.clone(),
+                content: Vec::new(),
+            }),
+        }
+    }
+
+    // Returns None if file does not exist
+    pub async fn read_file(&self, path: &str) -> Result<Option<Vec<u8>>, FileStorageError> {
+        match self {
+            Self::LocalDir(dir) => match tokio::fs::read(dir.join(path)).await {
+                Ok(content) => Ok(Some(content)),
+                Err(err) => {
+                    if err.kind() == std::io::ErrorKind::NotFound {
+                        return Ok(None);
+                    }
+
+                    Err(err.into())
+                }
+            },
+            Self::BlobContainer {
+                connection,
+                container,
+                ..
+            } => match connection.download_blob(container.as_str(), path).await {
+                Ok(content) => Ok(Some(content)),
+                Err(AzureStorageError::ContainerNotFound) => Ok(None),
+                Err(AzureStorageError::BlobNotFound) => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+        }
+    }
+
+    pub fn is_conditional_write_supported(&self) -> bool {
+        match self {
+            Self::LocalDir(_) => true,
+            Self::BlobContainer { account, .. } => account.is_some(),
+        }
+    }
+
+    // ETag of a local file is md5 of its content. ETag of a blob is the one of Blob service
+    pub async fn read_file_with_etag(
+        &self,
+        path: &str,
+    ) -> Result<Option<(Vec<u8>, String)>, FileStorageError> {
+        match self {
+            Self::LocalDir(dir) => read_local_file_with_etag(dir.join(path).as_path()).await,
+            Self::BlobContainer {
+                container, account, ..
+            } => match account {
+                Some(account) => account.download_with_etag(container.as_str(), path).await,
+                None => Err(FileStorageError::ConditionalWriteNotSupported),
+            },
+        }
+    }
+
+    // File is written only if its ETag is still the same. None means that file must not exist.
+    // Returns the new ETag or None if the file is changed by someone else
+    pub async fn write_file_if_match(
+        &self,
+        path: &str,
+        content: Vec<u8>,
+        etag: Option<&str>,
+    ) -> Result<Option<String>, FileStorageError> {
+        match self {
+            Self::LocalDir(dir) => write_local_file_if_match(dir.join(path), content, etag).await,
+            Self::BlobContainer {
+                connection,
+                container,
+                account,
+            } => {
+                let account = match account {
+                    Some(account) => account,
+                    None => return Err(FileStorageError::ConditionalWriteNotSupported),
+                };
+
+                if etag.is_none() {
+                    connection
+                        .create_container_if_not_exists(container.as_str())
+                        .await?;
+                }
+
+                account
+                    .upload_if_match(container.as_str(), path, content, etag)
+                    .await
+            }
+        }
+    }
+}
+
+fn get_etag(content: &[u8]) -> String {
+    format!("{:x}", md5::compute(content))
+}
+
+async fn read_local_file_with_etag(
+    file_name: &Path,
+) -> Result<Option<(Vec<u8>, String)>, FileStorageError> {
+    match tokio::fs::read(file_name).await {
+        Ok(content) => {
+            let etag = get_etag(content.as_slice());
+            Ok(Some((content, etag)))
+        }
+        Err(err) => {
+            if err.kind() == std::io::ErrorKind::NotFound {
+                return Ok(None);
+            }
+
+            Err(err.into())
+        }
+    }
+}
+
+// Lock of the lock file serializes conditional writes between processes of the same host.
+// Lock is released by OS when the process exits, so a crashed process does not leave it.
+// File locks are not reliable on network shares, so local dir is for instances of a single host
+async fn write_local_file_if_match(
+    file_name: PathBuf,
+    content: Vec<u8>,
+    etag: Option<&str>,
+) -> Result<Option<String>, FileStorageError> {
+    if let Some(parent) = file_name.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut lock_file = file_name.clone().into_os_string();
+    lock_file.push(".lock");
+
+    let lock_file = tokio::task::spawn_blocking(move || {
+        let lock_file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_file)?;
+
+        lock_file.lock()?;
+
+        Ok::<_, std::io::Error>(lock_file)
+    })
+    .await
+    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))??;
+
+    let current_etag = read_local_file_with_etag(file_name.as_path())
+        .await?
+        .map(|(_, etag)| etag);
+
+    if current_etag.as_deref() != etag {
+        return Ok(None);
+    }
+
+    let new_etag = get_etag(content.as_slice());
+
+    let mut tmp_file = file_name.clone().into_os_string();
+    tmp_file.push(".tmp");
+
+    tokio::fs::write(&tmp_file, content).await?;
+    tokio::fs::rename(&tmp_file, file_name).await?;
+
+    // Lock is released when the file is closed
+    drop(lock_file);
+
+    Ok(Some(new_etag))
+}
+
+pub enum FileStorageWriter {
+    LocalFile {
+        dir: PathBuf,
+        file: tokio::fs::File,
+        tmp_file: PathBuf,
+    },
+    // Block blob is uploaded by one request, so its content is kept until commit
+    BlockBlob {
+        connection: Arc<AzureStorageConnection>,
+        container: String,
+        content: Vec<u8>,
+    },
+}
+
+impl FileStorageWriter {
+    pub async fn append(&mut self, chunk: &[u8]) -> Result<(), FileStorageError> {
+        match self {
+            Self::LocalFile { file, .. } => file.write_all(chunk).await?,
+            Self::BlockBlob { content, .. } => content.extend_from_slice(chunk),
+        }
+
+        Ok(())
+    }
+
+    pub async fn commit(self, path: &str) -> Result<(), FileStorageError> {
+        match self {
+            Self::LocalFile {
+                dir,
+                mut file,
+                tmp_file,
+            } => {
+                file.flush().await?;
+                file.sync_all().await?;
+                drop(file);
+
+                let file_name = dir.join(path);
+
+                if let Some(parent) = file_name.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                tokio::fs::rename(&tmp_file, file_name).await?;
+            }
+            Self::BlockBlob {
+                connection,
+                container,
+                content,
+            } => {
+                connection
+                    .create_container_if_not_exists(container.as_str())
+                    .await?;
+
+                connection
+                    .upload_block_blob(container.as_str(), path, content)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileStorage;
+
+    #[tokio::test]
+    async fn test_local_dir() {
+        let dir = std::env::temp_dir().join(format!("file-storage-{}", std::process::id()));
+        let storage = FileStorage::LocalDir(dir.clone());
+
+        assert!(storage.read_file("orders/a.bin").await.unwrap().is_none());
+
+        storage
+            .write_file("orders/a.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let content = storage.read_file("orders/a.bin").await.unwrap();
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(content.unwrap(), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_write_if_match() {
+        let dir = std::env::temp_dir().join(format!("file-storage-etag-{}", std::process::id()));
+        let storage = FileStorage::LocalDir(dir.clone());
+
+        let etag = storage
+            .write_file_if_match("lease", vec![1], None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // File exists already, so it is not created again
+        let created_again = storage
+            .write_file_if_match("lease", vec![2], None)
+            .await
+            .unwrap();
+
+        let updated = storage
+            .write_file_if_match("lease", vec![3], Some(etag.as_str()))
+            .await
+            .unwrap();
+
+        // ETag is changed by the update
+        let stale_update = storage
+            .write_file_if_match("lease", vec![4], Some(etag.as_str()))
+            .await
+            .unwrap();
+
+        let (content, current_etag) = storage.read_file_with_etag("lease").await.unwrap().unwrap();
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        assert!(created_again.is_none());
+        assert!(stale_update.is_none());
+        assert_eq!(content, vec![3]);
+        assert_eq!(updated.unwrap(), current_etag);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_if_match() {
+        let dir = std::env::temp_dir().join(format!("file-storage-race-{}", std::process::id()));
+        let storage = std::sync::Arc::new(FileStorage::LocalDir(dir.clone()));
+
+        let mut tasks = Vec::new();
+
+        for i in 0..8u8 {
+            let storage = storage.clone();
+            tasks.push(tokio::spawn(async move {
+                storage.write_file_if_match("lease", vec![i], None).await
+            }));
+        }
+
+        let mut written = 0;
+
+        for task in tasks {
+            if task.await.unwrap().unwrap().is_some() {
+                written += 1;
+            }
+        }
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        // Only one of the writers which read the same ETag wins
+        assert_eq!(written, 1);
+    }
+
+    #[tokio::test]
+    async fn test_local_dir_by_chunks() {
+        let dir = std::env::temp_dir().join(format!("file-storage-chunks-{}", std::process::id()));
+        let storage = FileStorage::LocalDir(dir.clone());
+
+        let mut file = storage.create_file("orders/a.bin.tmp").await.unwrap();
+        file.append(&[1, 2]).await.unwrap();
+        file.append(&[3]).await.unwrap();
+
+        assert!(storage.read_file("orders/a.bin").await.unwrap().is_none());
+
+        file.commit("orders/a.bin").await.unwrap();
+
+        let content = storage.read_file("orders/a.bin").await.unwrap();
+        let tmp_content = storage.read_file("orders/a.bin.tmp").await.unwrap();
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(content.unwrap(), vec![1, 2, 3]);
+        assert!(tmp_content.is_none());
+    }
+}
@@ -0,0 +1,4 @@
+mod blob_account;
+pub use blob_account::*;
+mod file_storage;
+pub use file_storage::*;
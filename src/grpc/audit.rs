@@ -0,0 +1,6 @@
+pub fn get_audit_initiator<T>(request: &tonic::Request<T>) -> String {
+    match request.remote_addr() {
+        Some(addr) => format!("gRPC {}", addr),
+        None => "gRPC unknown".to_string(),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::app::AppContext;
+use crate::{app::AppContext, json_path::JsonPathFilter};
 
 use my_service_bus::shared::protobuf_models::MessageProtobufModel;
 
@@ -18,6 +18,10 @@ impl NewMessagesProtobufContract {
 }
 
 pub fn check_flags(app: &AppContext) -> Result<(), tonic::Status> {
+    if app.is_passive() {
+        return Err(tonic::Status::unavailable("Instance is passive"));
+    }
+
     if !app.app_states.is_initialized() {
         return Err(tonic::Status::cancelled(
             "Application is not initialized yet",
@@ -28,5 +32,37 @@ pub fn check_flags(app: &AppContext) -> Result<(), tonic::Status> {
         return Err(tonic::Status::cancelled("Shutting down"));
     }
 
+    if app.is_replica_out_of_sync() {
+        return Err(tonic::Status::unavailable(
+            "Secondary replica is not in sync with the primary",
+        ));
+    }
+
     Ok(())
 }
+
+// Secondary replica serves reads only. Writes come from the primary through replication
+pub fn check_write_flags(app: &AppContext) -> Result<(), tonic::Status> {
+    check_flags(app)?;
+
+    if app.is_secondary() {
+        return Err(tonic::Status::unavailable(
+            "Instance is a secondary replica. Writes are served by the primary",
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn parse_json_path(src: Option<&str>) -> Result<Option<JsonPathFilter>, tonic::Status> {
+    match src {
+        Some(src) => match JsonPathFilter::parse(src) {
+            Ok(result) => Ok(Some(result)),
+            Err(err) => Err(tonic::Status::invalid_argument(format!(
+                "Invalid json path filter. {}",
+                err
+            ))),
+        },
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,29 @@
+impl From<crate::operations::OperationError> for tonic::Status {
+    fn from(src: crate::operations::OperationError) -> Self {
+        match &src {
+            crate::operations::OperationError::TopicNotFound(topic_id) => {
+                tonic::Status::not_found(format!("Topic {} not found", topic_id))
+            }
+            crate::operations::OperationError::SnapshotVersionNotFound(version) => {
+                tonic::Status::not_found(format!("Snapshot version {} not found", version))
+            }
+            crate::operations::OperationError::InvalidSnapshot(anomalies) => {
+                let details: Vec<String> = anomalies.iter().map(|itm| itm.get_details()).collect();
+                tonic::Status::invalid_argument(details.join("; "))
+            }
+            crate::operations::OperationError::HeaderIndexNotFound(topic_id) => {
+                tonic::Status::failed_precondition(format!(
+                    "Topic {} has no header index",
+                    topic_id
+                ))
+            }
+            crate::operations::OperationError::NotLeader => {
+                tonic::Status::unavailable("Instance is passive. Writes are served by the leader")
+            }
+            crate::operations::OperationError::SecondaryReplica => tonic::Status::unavailable(
+                "Instance is a secondary replica. Writes are served by the primary",
+            ),
+            _ => tonic::Status::internal(format!("{:?}", src)),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::pin::Pin;
+
+use futures_core::Stream;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio_util::sync::CancellationToken;
+use tonic::Status;
+use tracing::Instrument;
+
+use crate::operations::SearchMessagesFilter;
+use crate::persistence_grpc::my_service_bus_history_reader_grpc_service_server::MyServiceBusHistoryReaderGrpcService;
+use crate::persistence_grpc::*;
+
+use super::contracts;
+use super::server::MyServicePersistenceGrpc;
+
+const CHANNEL_SIZE: usize = 100;
+
+#[tonic::async_trait]
+impl MyServiceBusHistoryReaderGrpcService for MyServicePersistenceGrpc {
+    type GetByDateStream = Pin<
+        Box<dyn Stream<Item = Result<MessageContentGrpcModel, Status>> + Send + Sync + 'static>,
+    >;
+
+    // Messages from the date are scanned the same way as search does, so the amount of them
+    // is capped by MaxResponseRecordsAmount
+    async fn get_by_date(
+        &self,
+        request: tonic::Request<GetHistoryByDateGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetByDateStream>, tonic::Status> {
+        let timer = self.app.metrics_keeper.start_grpc_call_timer("GetByDate");
+
+        contracts::check_flags(self.app.as_ref())?;
+
+        let req = request.into_inner();
+
+        let filter = SearchMessagesFilter {
+            from_message_id: None,
+            to_message_id: None,
+            from_date: Some(DateTimeAsMicroseconds::new(req.from_date_time)),
+            to_date: None,
+            predicates: vec![],
+            json_path: contracts::parse_json_path(req.json_path.as_deref())?,
+            limit: 0,
+        };
+
+        let app = self.app.clone();
+
+        let topic_id = req.topic_id;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(CHANNEL_SIZE);
+
+        tokio::spawn(
+            async move {
+                let cancellation_token = CancellationToken::new();
+                let (found_tx, mut found_rx) = tokio::sync::mpsc::channel(CHANNEL_SIZE);
+
+                let search = async {
+                    let found_tx = found_tx;
+
+                    crate::operations::search_messages(
+                        app.as_ref(),
+                        topic_id.as_str(),
+                        &filter,
+                        &cancellation_token,
+                        &found_tx,
+                    )
+                    .await
+                };
+
+                let forward = async {
+                    while let Some(msg) = found_rx.recv().await {
+                        app.metrics_keeper
+                            .bytes_read(topic_id.as_str(), msg.data.len());
+
+                        if tx.send(Ok(msg.as_ref().into())).await.is_err() {
+                            cancellation_token.cancel();
+                            found_rx.close();
+                            break;
+                        }
+                    }
+                };
+
+                let (result, _) = tokio::join!(search, forward);
+
+                if let Err(err) = result {
+                    let _ = tx.send(Err(err.into())).await;
+                }
+
+                timer.observe_duration();
+            }
+            .in_current_span(),
+        );
+
+        Ok(tonic::Response::new(Box::pin(
+            tokio_stream::wrappers::ReceiverStream::new(rx),
+        )))
+    }
+}
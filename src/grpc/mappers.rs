@@ -1,6 +1,16 @@
 use my_service_bus::shared::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
 
-use crate::persistence_grpc::{MessageContentGrpcModel, MessageContentMetaDataItem};
+use my_service_bus::abstractions::MessageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    archive_storage::{ArchiveFileStats, TopicArchiveStats},
+    operations::{HeaderPredicate, SearchMessagesFilter},
+    persistence_grpc::{
+        ArchiveFileStatsGrpcModel, HeaderPredicateGrpcModel, MessageContentGrpcModel,
+        MessageContentMetaDataItem, SearchMessagesGrpcRequest, TopicArchiveStatsGrpcResponse,
+    },
+};
 
 impl<'s> Into<MessageContentGrpcModel> for &'s MessageProtobufModel {
     fn into(self) -> MessageContentGrpcModel {
@@ -41,3 +51,67 @@ impl<'s> Into<MessageContentMetaDataItem> for &'s MessageMetaDataProtobufModel {
         }
     }
 }
+
+impl<'s> Into<ArchiveFileStatsGrpcModel> for &'s ArchiveFileStats {
+    fn into(self) -> ArchiveFileStatsGrpcModel {
+        ArchiveFileStatsGrpcModel {
+            file_no: self.archive_file_no,
+            blob_size: self.blob_size as i64,
+            populated_toc_slots: self.populated_toc_slots as i64,
+            compressed_size: self.compressed_size as i64,
+        }
+    }
+}
+
+impl<'s> Into<TopicArchiveStatsGrpcResponse> for &'s TopicArchiveStats {
+    fn into(self) -> TopicArchiveStatsGrpcResponse {
+        TopicArchiveStatsGrpcResponse {
+            files: self.files.values().map(|itm| itm.into()).collect(),
+            first_message_id: self.first_message.map(|itm| itm.message_id.get_value()),
+            last_message_id: self.last_message.map(|itm| itm.message_id.get_value()),
+            first_message_created: self.first_message.map(|itm| itm.created.unix_microseconds),
+            last_message_created: self.last_message.map(|itm| itm.created.unix_microseconds),
+            compression_ratio: self.get_compression_ratio(),
+        }
+    }
+}
+
+impl<'s> Into<HeaderPredicate> for &'s HeaderPredicateGrpcModel {
+    fn into(self) -> HeaderPredicate {
+        if let Some(value) = &self.equals {
+            return HeaderPredicate::Equals {
+                key: self.key.to_string(),
+                value: value.to_string(),
+            };
+        }
+
+        if let Some(prefix) = &self.prefix {
+            return HeaderPredicate::Prefix {
+                key: self.key.to_string(),
+                prefix: prefix.to_string(),
+            };
+        }
+
+        HeaderPredicate::Exists {
+            key: self.key.to_string(),
+        }
+    }
+}
+
+impl<'s> TryInto<SearchMessagesFilter> for &'s SearchMessagesGrpcRequest {
+    type Error = tonic::Status;
+
+    fn try_into(self) -> Result<SearchMessagesFilter, Self::Error> {
+        let result = SearchMessagesFilter {
+            from_message_id: self.from_message_id.map(MessageId::new),
+            to_message_id: self.to_message_id.map(MessageId::new),
+            from_date: self.from_date.map(DateTimeAsMicroseconds::new),
+            to_date: self.to_date.map(DateTimeAsMicroseconds::new),
+            predicates: self.predicates.iter().map(|itm| itm.into()).collect(),
+            json_path: super::contracts::parse_json_path(self.json_path.as_deref())?,
+            limit: self.limit.max(0) as usize,
+        };
+
+        Ok(result)
+    }
+}
@@ -5,10 +5,15 @@ use futures_core::Stream;
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::page_id::PageId;
 use my_service_bus::shared::sub_page::SubPageId;
+use prometheus::HistogramTimer;
 
 use std::pin::Pin;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tonic::Status;
+use tracing::Instrument;
+
+use crate::operations::SearchMessagesFilter;
 
 use super::contracts;
 
@@ -34,10 +39,20 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         Box<dyn Stream<Item = Result<MessageContentGrpcModel, Status>> + Send + Sync + 'static>,
     >;
 
+    type SearchMessagesStream = Pin<
+        Box<dyn Stream<Item = Result<MessageContentGrpcModel, Status>> + Send + Sync + 'static>,
+    >;
+
+    type LookupHeaderIndexStream = Pin<
+        Box<dyn Stream<Item = Result<MessageContentGrpcModel, Status>> + Send + Sync + 'static>,
+    >;
+
     async fn get_version(
         &self,
         _request: tonic::Request<()>,
     ) -> Result<tonic::Response<MyServerBusPersistenceVersion>, tonic::Status> {
+        let _timer = self.app.metrics_keeper.start_grpc_call_timer("GetVersion");
+
         let result = MyServerBusPersistenceVersion {
             version: crate::app::APP_VERSION.to_string(),
         };
@@ -49,6 +64,8 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         request: tonic::Request<GetMessageGrpcRequest>,
     ) -> Result<tonic::Response<MessageContentGrpcModel>, tonic::Status> {
+        let _timer = self.app.metrics_keeper.start_grpc_call_timer("GetMessage");
+
         contracts::check_flags(self.app.as_ref())?;
 
         let req = request.into_inner();
@@ -62,7 +79,12 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         .unwrap();
 
         let result = match message {
-            Some(msg) => msg.as_ref().into(),
+            Some(msg) => {
+                self.app
+                    .metrics_keeper
+                    .bytes_read(req.topic_id.as_str(), msg.data.len());
+                msg.as_ref().into()
+            }
             None => MessageContentGrpcModel {
                 created: 0,
                 data: Vec::new(),
@@ -78,6 +100,11 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         request: tonic::Request<crate::persistence_grpc::GetMessagesPageGrpcRequest>,
     ) -> Result<tonic::Response<Self::GetPageCompressedStream>, tonic::Status> {
+        let timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("GetPageCompressed");
+
         contracts::check_flags(self.app.as_ref())?;
 
         let req = request.into_inner();
@@ -104,16 +131,20 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         )
         .await;
 
-        my_grpc_extensions::grpc_server::send_vec_to_stream(compressed.into_iter(), |chunk| {
-            CompressedMessageChunkModel { chunk }
-        })
-        .await
+        let chunks: Vec<_> = compressed
+            .into_iter()
+            .map(|chunk| CompressedMessageChunkModel { chunk })
+            .collect();
+
+        Ok(tonic::Response::new(send_vec_to_stream(chunks, timer)))
     }
 
     async fn get_page(
         &self,
         request: tonic::Request<crate::persistence_grpc::GetMessagesPageGrpcRequest>,
     ) -> Result<tonic::Response<Self::GetPageStream>, tonic::Status> {
+        let timer = self.app.metrics_keeper.start_grpc_call_timer("GetPage");
+
         contracts::check_flags(self.app.as_ref())?;
 
         let req = request.into_inner();
@@ -132,19 +163,27 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
             to_message_id = MessageId::new(req.to_message_id);
         }
 
+        let json_path = contracts::parse_json_path(req.json_path.as_deref())?;
+
         let topic_id = req.topic_id;
 
-        tokio::spawn(async move {
-            crate::operations::send_messages_to_channel(
-                app,
-                topic_id,
-                from_message_id,
-                to_message_id,
-                tx,
-                GRPC_TIMEOUT,
-            )
-            .await;
-        });
+        tokio::spawn(
+            async move {
+                crate::operations::send_messages_to_channel(
+                    app,
+                    topic_id,
+                    from_message_id,
+                    to_message_id,
+                    json_path,
+                    tx,
+                    GRPC_TIMEOUT,
+                )
+                .await;
+
+                timer.observe_duration();
+            }
+            .in_current_span(),
+        );
 
         Ok(tonic::Response::new(Box::pin(
             tokio_stream::wrappers::ReceiverStream::new(rx),
@@ -155,6 +194,8 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         request: tonic::Request<crate::persistence_grpc::GetSubPageRequest>,
     ) -> Result<tonic::Response<Self::GetSubPageStream>, tonic::Status> {
+        let timer = self.app.metrics_keeper.start_grpc_call_timer("GetSubPage");
+
         contracts::check_flags(self.app.as_ref())?;
 
         let req = request.into_inner();
@@ -166,21 +207,29 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         let from_message_id = sub_page_id.get_first_message_id();
         let to_message_id = sub_page_id.get_last_message_id();
 
+        let json_path = contracts::parse_json_path(req.json_path.as_deref())?;
+
         let topic_id = req.topic_id;
 
         let (tx, rx) = tokio::sync::mpsc::channel(CHANNEL_SIZE);
 
-        tokio::spawn(async move {
-            crate::operations::send_messages_to_channel(
-                app,
-                topic_id,
-                from_message_id,
-                to_message_id,
-                tx,
-                GRPC_TIMEOUT,
-            )
-            .await;
-        });
+        tokio::spawn(
+            async move {
+                crate::operations::send_messages_to_channel(
+                    app,
+                    topic_id,
+                    from_message_id,
+                    to_message_id,
+                    json_path,
+                    tx,
+                    GRPC_TIMEOUT,
+                )
+                .await;
+
+                timer.observe_duration();
+            }
+            .in_current_span(),
+        );
 
         Ok(tonic::Response::new(Box::pin(
             tokio_stream::wrappers::ReceiverStream::new(rx),
@@ -192,7 +241,12 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
             tonic::Streaming<crate::persistence_grpc::CompressedMessageChunkModel>,
         >,
     ) -> Result<tonic::Response<()>, tonic::Status> {
-        contracts::check_flags(self.app.as_ref())?;
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("SaveMessages");
+
+        contracts::check_write_flags(self.app.as_ref())?;
 
         let grpc_contract =
             super::messages_mappers::unzip_and_deserialize(&mut request.into_inner(), GRPC_TIMEOUT)
@@ -214,7 +268,12 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
             tonic::Streaming<crate::persistence_grpc::UnCompressedMessageChunkModel>,
         >,
     ) -> Result<tonic::Response<()>, tonic::Status> {
-        contracts::check_flags(self.app.as_ref())?;
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("SaveMessagesUncompressed");
+
+        contracts::check_write_flags(self.app.as_ref())?;
 
         let grpc_contract = super::messages_mappers::deserialize_uncompressed(
             &mut request.into_inner(),
@@ -235,14 +294,20 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         request: tonic::Request<DeleteTopicGrpcRequest>,
     ) -> Result<tonic::Response<()>, tonic::Status> {
+        let _timer = self.app.metrics_keeper.start_grpc_call_timer("DeleteTopic");
+
+        let initiator = super::audit::get_audit_initiator(&request);
         let request = request.into_inner();
 
         crate::operations::delete_topic(
             self.app.as_ref(),
             &request.topic_id,
             request.delete_after.into(),
+            request.dry_run,
+            initiator.as_str(),
         )
-        .await;
+        .await?;
+
         return Ok(tonic::Response::new(()));
     }
 
@@ -250,10 +315,20 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         request: tonic::Request<RestoreTopicRequest>,
     ) -> Result<tonic::Response<RestoreTopicResponse>, tonic::Status> {
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("RestoreTopic");
+
+        let initiator = super::audit::get_audit_initiator(&request);
         let request = request.into_inner();
 
-        let response = if let Some(restored) =
-            crate::operations::restore_topic(self.app.as_ref(), &request.topic_id).await
+        let response = if let Ok(restored) = crate::operations::restore_topic(
+            self.app.as_ref(),
+            &request.topic_id,
+            initiator.as_str(),
+        )
+        .await
         {
             RestoreTopicResponse {
                 result: true,
@@ -268,4 +343,182 @@ impl MyServiceBusMessagesPersistenceGrpcService for MyServicePersistenceGrpc {
 
         return Ok(tonic::Response::new(response));
     }
+
+    async fn get_topic_archive_stats(
+        &self,
+        request: tonic::Request<GetTopicArchiveStatsGrpcRequest>,
+    ) -> Result<tonic::Response<TopicArchiveStatsGrpcResponse>, tonic::Status> {
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("GetTopicArchiveStats");
+
+        contracts::check_flags(self.app.as_ref())?;
+
+        let request = request.into_inner();
+
+        let stats =
+            crate::operations::get_archive_stats(self.app.as_ref(), &request.topic_id).await?;
+
+        return Ok(tonic::Response::new((&stats).into()));
+    }
+
+    async fn search_messages(
+        &self,
+        request: tonic::Request<SearchMessagesGrpcRequest>,
+    ) -> Result<tonic::Response<Self::SearchMessagesStream>, tonic::Status> {
+        let timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("SearchMessages");
+
+        contracts::check_flags(self.app.as_ref())?;
+
+        let req = request.into_inner();
+
+        let filter: SearchMessagesFilter = (&req).try_into()?;
+
+        let app = self.app.clone();
+
+        let topic_id = req.topic_id;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(CHANNEL_SIZE);
+
+        tokio::spawn(
+            async move {
+                // Search is cancelled as soon as client stops reading the stream
+                let cancellation_token = CancellationToken::new();
+                let (found_tx, mut found_rx) = tokio::sync::mpsc::channel(CHANNEL_SIZE);
+
+                let search = async {
+                    let found_tx = found_tx;
+
+                    crate::operations::search_messages(
+                        app.as_ref(),
+                        topic_id.as_str(),
+                        &filter,
+                        &cancellation_token,
+                        &found_tx,
+                    )
+                    .await
+                };
+
+                let forward = async {
+                    while let Some(msg) = found_rx.recv().await {
+                        app.metrics_keeper
+                            .bytes_read(topic_id.as_str(), msg.data.len());
+
+                        if tx.send(Ok(msg.as_ref().into())).await.is_err() {
+                            cancellation_token.cancel();
+                            found_rx.close();
+                            break;
+                        }
+                    }
+                };
+
+                let (result, _) = tokio::join!(search, forward);
+
+                if let Err(err) = result {
+                    let _ = tx.send(Err(err.into())).await;
+                }
+
+                timer.observe_duration();
+            }
+            .in_current_span(),
+        );
+
+        Ok(tonic::Response::new(Box::pin(
+            tokio_stream::wrappers::ReceiverStream::new(rx),
+        )))
+    }
+
+    async fn lookup_header_index(
+        &self,
+        request: tonic::Request<LookupHeaderIndexGrpcRequest>,
+    ) -> Result<tonic::Response<Self::LookupHeaderIndexStream>, tonic::Status> {
+        let timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("LookupHeaderIndex");
+
+        contracts::check_flags(self.app.as_ref())?;
+
+        let req = request.into_inner();
+
+        let message_ids = crate::operations::lookup_header_index(
+            self.app.as_ref(),
+            req.topic_id.as_str(),
+            req.value.as_str(),
+        )
+        .await?;
+
+        let max_amount = self.app.settings.max_response_records_amount;
+
+        let limit = if req.limit > 0 {
+            (req.limit as usize).min(max_amount)
+        } else {
+            max_amount
+        };
+
+        // Index is built on the configured header key only
+        let header_key = self
+            .app
+            .header_indexes
+            .get_header_key(req.topic_id.as_str())
+            .unwrap_or_default();
+
+        let mut result: Vec<MessageContentGrpcModel> = Vec::new();
+
+        for message_id in message_ids {
+            if result.len() >= limit {
+                break;
+            }
+
+            let message = crate::operations::get_message_by_id(
+                self.app.as_ref(),
+                req.topic_id.as_str(),
+                message_id,
+            )
+            .await?;
+
+            if let Some(msg) = message {
+                let is_matching = msg
+                    .headers
+                    .iter()
+                    .any(|itm| itm.key == header_key && itm.value == req.value);
+
+                if is_matching {
+                    self.app
+                        .metrics_keeper
+                        .bytes_read(req.topic_id.as_str(), msg.data.len());
+                    result.push(msg.as_ref().into());
+                }
+            }
+        }
+
+        Ok(tonic::Response::new(send_vec_to_stream(result, timer)))
+    }
+}
+
+// Call duration is observed when the last item is sent, not when the stream is returned
+fn send_vec_to_stream<T: Send + Sync + 'static>(
+    items: Vec<T>,
+    timer: HistogramTimer,
+) -> Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + Sync + 'static>> {
+    let (tx, rx) = tokio::sync::mpsc::channel(CHANNEL_SIZE);
+
+    tokio::spawn(
+        async move {
+            for item in items {
+                if tx.send(Ok(item)).await.is_err() {
+                    break;
+                }
+            }
+
+            timer.observe_duration();
+        }
+        .in_current_span(),
+    );
+
+    Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx))
 }
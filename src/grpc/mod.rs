@@ -1,7 +1,13 @@
+mod audit;
 mod contracts;
+mod error_converters;
+mod history_reader_grpc;
 mod mappers;
 mod messages_mappers;
 mod messages_persistence_grpc;
+pub mod replication_client;
+mod replication_grpc;
+mod replication_mappers;
 pub mod server;
 mod topic_snapshot_grpc;
 mod topic_snapshot_mappers;
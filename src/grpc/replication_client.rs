@@ -0,0 +1,224 @@
+use std::{sync::Arc, time::Duration};
+
+use my_service_bus::{abstractions::MessageId, shared::sub_page::SubPageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    persistence_grpc::{
+        my_service_bus_messages_persistence_grpc_service_client::MyServiceBusMessagesPersistenceGrpcServiceClient,
+        my_service_bus_replication_grpc_service_client::MyServiceBusReplicationGrpcServiceClient,
+        GetSubPageRequest, ReplicationSubscribeGrpcRequest,
+    },
+    replication::{ReplicaState, ReplicationEventData},
+    topics_snapshot::TopicSnapshotProtobufModel,
+};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+// Secondary applies events of the primary into its own storage until it is promoted.
+// It reconnects from the last applied sequence, so the primary replays events from its backlog.
+// If the events are not there anymore, the primary sends the topics snapshot and the secondary
+// backfills the messages it missed from the persisted pages of the primary
+pub async fn start(app: Arc<AppContext>) {
+    let replica = match &app.replica {
+        Some(replica) => replica,
+        None => return,
+    };
+
+    while !app.app_states.is_initialized() {
+        if app.app_states.is_shutting_down() {
+            return;
+        }
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    app.logs.add_info(
+        None,
+        "Replication",
+        format!(
+            "Instance is a secondary replica of {}",
+            replica.get_primary_url()
+        ),
+    );
+
+    while !replica.is_promoted() && !app.app_states.is_shutting_down() {
+        if let Err(err) = replicate(app.as_ref(), replica).await {
+            app.logs.add_warning(
+                None,
+                "Replication",
+                format!(
+                    "Replication from {} is interrupted. Err: {}",
+                    replica.get_primary_url(),
+                    err
+                ),
+            );
+        }
+
+        replica.set_connected(false);
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn replicate(app: &AppContext, replica: &ReplicaState) -> Result<(), String> {
+    let mut client =
+        MyServiceBusReplicationGrpcServiceClient::connect(replica.get_primary_url().to_string())
+            .await
+            .map_err(|err| format!("Can not connect. {:?}", err))?;
+
+    let (mut session_id, mut applied_sequence) = replica.get_position();
+
+    let mut stream = client
+        .subscribe(ReplicationSubscribeGrpcRequest {
+            session_id: session_id.to_string(),
+            from_sequence: applied_sequence,
+        })
+        .await
+        .map_err(|err| format!("Can not subscribe. {:?}", err))?
+        .into_inner();
+
+    replica.set_connected(true);
+
+    while let Some(event) = stream.message().await.map_err(|err| format!("{:?}", err))? {
+        if replica.is_promoted() || app.app_states.is_shutting_down() {
+            return Ok(());
+        }
+
+        let created = DateTimeAsMicroseconds::new(event.created);
+
+        replica.update_primary(event.last_sequence, created);
+
+        let event_session_id = event.session_id.to_string();
+        let sequence = event.sequence;
+
+        if let Some(data) = super::replication_mappers::to_domain_event(event) {
+            // Primary is restarted, the events are not in its backlog anymore or it is the first subscription
+            let is_gap = event_session_id != session_id || sequence > applied_sequence + 1;
+
+            if is_gap {
+                replica.set_in_sync(false);
+
+                let snapshot = match &data {
+                    ReplicationEventData::TopicsSnapshot(snapshot) => snapshot,
+                    _ => {
+                        return Err(format!(
+                            "Sequence {} of session {} follows sequence {} of session {} without topics snapshot",
+                            sequence, event_session_id, applied_sequence, session_id
+                        ))
+                    }
+                };
+
+                let backfilled = backfill(app, replica, snapshot).await?;
+
+                app.logs.add_info(
+                    None,
+                    "Replication",
+                    format!(
+                        "Replication is continued from sequence {} of session {} after sequence {} of session {}. Backfilled {} messages",
+                        sequence, event_session_id, applied_sequence, session_id, backfilled
+                    ),
+                );
+            }
+
+            let event_type = data.as_str();
+
+            // Sequence is not advanced past the failed event, so it is applied again after reconnect.
+            // Secondary is not in sync until then
+            if let Err(err) = crate::operations::apply_replication_event(app, data).await {
+                replica.set_in_sync(false);
+
+                return Err(format!(
+                    "Can not apply {} event {} of session {}. Err: {:?}",
+                    event_type, sequence, event_session_id, err
+                ));
+            }
+
+            replica.event_applied(event_session_id.as_str(), sequence, created);
+            replica.set_in_sync(true);
+
+            session_id = event_session_id;
+            applied_sequence = sequence;
+        }
+
+        let lag = replica.get_lag();
+        app.metrics_keeper
+            .update_replication_lag(lag.events, lag.duration_ms);
+    }
+
+    Ok(())
+}
+
+// Messages between the local and the primary message id of each topic are read from the primary
+// before its topics snapshot is applied. Snapshot is applied only after the backfill, so an interrupted
+// backfill is started again from the same local message ids after reconnect
+async fn backfill(
+    app: &AppContext,
+    replica: &ReplicaState,
+    snapshot: &[TopicSnapshotProtobufModel],
+) -> Result<usize, String> {
+    let mut client = MyServiceBusMessagesPersistenceGrpcServiceClient::connect(
+        replica.get_primary_url().to_string(),
+    )
+    .await
+    .map_err(|err| format!("Can not connect to backfill. {:?}", err))?;
+
+    let mut result = 0;
+
+    for topic in snapshot {
+        let from_message_id = app
+            .topics_snapshot
+            .get_current_message_id(topic.topic_id.as_str())
+            .await
+            .map(|itm| itm.get_value())
+            .unwrap_or(0);
+
+        let to_message_id = topic.get_message_id().get_value() - 1;
+
+        if from_message_id > to_message_id {
+            continue;
+        }
+
+        let from_sub_page_id: SubPageId = MessageId::new(from_message_id).into();
+        let to_sub_page_id: SubPageId = MessageId::new(to_message_id).into();
+
+        for sub_page_no in from_sub_page_id.get_value()..=to_sub_page_id.get_value() {
+            let mut stream = client
+                .get_sub_page(GetSubPageRequest {
+                    topic_id: topic.topic_id.to_string(),
+                    sub_page_no,
+                    json_path: None,
+                })
+                .await
+                .map_err(|err| format!("Can not backfill. {:?}", err))?
+                .into_inner();
+
+            let mut messages = Vec::new();
+
+            while let Some(msg) = stream.message().await.map_err(|err| format!("{:?}", err))? {
+                if msg.message_id >= from_message_id && msg.message_id <= to_message_id {
+                    messages.push(super::replication_mappers::to_message(msg));
+                }
+            }
+
+            if messages.is_empty() {
+                continue;
+            }
+
+            result += messages.len();
+
+            crate::operations::apply_replication_event(
+                app,
+                ReplicationEventData::NewMessages {
+                    topic_id: topic.topic_id.to_string(),
+                    messages,
+                },
+            )
+            .await
+            .map_err(|err| format!("Can not apply backfilled messages. {:?}", err))?;
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,152 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_core::Stream;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tonic::Status;
+
+use crate::persistence_grpc::my_service_bus_replication_grpc_service_server::MyServiceBusReplicationGrpcService;
+use crate::persistence_grpc::*;
+use crate::replication::ReplicationEventData;
+
+use super::contracts;
+use super::server::MyServicePersistenceGrpc;
+
+const CHANNEL_SIZE: usize = 100;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[tonic::async_trait]
+impl MyServiceBusReplicationGrpcService for MyServicePersistenceGrpc {
+    type SubscribeStream = Pin<
+        Box<dyn Stream<Item = Result<ReplicationEventGrpcModel, Status>> + Send + Sync + 'static>,
+    >;
+
+    async fn subscribe(
+        &self,
+        request: tonic::Request<ReplicationSubscribeGrpcRequest>,
+    ) -> Result<tonic::Response<Self::SubscribeStream>, tonic::Status> {
+        // Subscription lasts while the secondary is connected, so only its setup is measured
+        let _timer = self.app.metrics_keeper.start_grpc_call_timer("Subscribe");
+
+        contracts::check_flags(self.app.as_ref())?;
+
+        if self.app.replication_hub.is_none() {
+            return Err(tonic::Status::failed_precondition(
+                "Replication is not configured",
+            ));
+        }
+
+        let request = request.into_inner();
+
+        let app = self.app.clone();
+
+        let (tx, rx) = mpsc::channel(CHANNEL_SIZE);
+
+        tokio::spawn(async move {
+            let replication_hub = app.replication_hub.as_ref().unwrap();
+            let session_id = replication_hub.get_session_id();
+
+            let mut subscription =
+                replication_hub.subscribe(request.session_id.as_str(), request.from_sequence);
+
+            app.logs.add_info(
+                None,
+                "Replication",
+                format!(
+                    "Secondary subscribed from sequence {} of session {}. Resumed: {}",
+                    request.from_sequence, request.session_id, subscription.resumed
+                ),
+            );
+
+            // Secondary which can not be resumed starts from the whole topics snapshot.
+            // Messages published while it was not connected are backfilled by the secondary from persisted pages
+            if !subscription.resumed {
+                let snapshot = app.topics_snapshot.get().await;
+
+                let data = ReplicationEventData::TopicsSnapshot(
+                    snapshot
+                        .topics
+                        .values()
+                        .map(|itm| itm.as_ref().clone())
+                        .collect(),
+                );
+
+                let event = super::replication_mappers::to_grpc_event(
+                    session_id,
+                    subscription.last_sequence,
+                    DateTimeAsMicroseconds::now(),
+                    subscription.last_sequence,
+                    Some(&data),
+                );
+
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+
+            for event in subscription.backlog.drain(..) {
+                let event = super::replication_mappers::to_grpc_event(
+                    session_id,
+                    event.sequence,
+                    event.created,
+                    subscription.last_sequence,
+                    Some(&event.data),
+                );
+
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+
+            let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+            loop {
+                let event = tokio::select! {
+                    event = subscription.receiver.recv() => {
+                        match event {
+                            Ok(event) => super::replication_mappers::to_grpc_event(
+                                session_id,
+                                event.sequence,
+                                event.created,
+                                replication_hub.get_last_sequence(),
+                                Some(&event.data),
+                            ),
+                            // Secondary reconnects and resumes from the backlog
+                            Err(RecvError::Lagged(skipped)) => {
+                                let _ = tx
+                                    .send(Err(tonic::Status::resource_exhausted(format!(
+                                        "Secondary is behind for {} events",
+                                        skipped
+                                    ))))
+                                    .await;
+                                return;
+                            }
+                            Err(RecvError::Closed) => return,
+                        }
+                    }
+                    _ = heartbeat.tick() => super::replication_mappers::to_grpc_event(
+                        session_id,
+                        0,
+                        DateTimeAsMicroseconds::now(),
+                        replication_hub.get_last_sequence(),
+                        None,
+                    ),
+                };
+
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+
+                if app.app_states.is_shutting_down() {
+                    return;
+                }
+            }
+        });
+
+        Ok(tonic::Response::new(Box::pin(
+            tokio_stream::wrappers::ReceiverStream::new(rx),
+        )))
+    }
+}
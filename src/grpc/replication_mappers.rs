@@ -0,0 +1,267 @@
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    persistence_grpc::{
+        DeleteQueueSnapshotGrpcRequest, MessageContentGrpcModel, ReplicatedDeletedTopicGrpcModel,
+        ReplicatedMessagesGrpcModel, ReplicationEventGrpcModel, RestoreTopicRequest,
+        SaveQueueSnapshotGrpcRequest,
+    },
+    replication::ReplicationEventData,
+};
+
+use super::topic_snapshot_mappers::to_domain;
+
+// Heartbeat has no data. It keeps the position of the primary known to the secondary
+pub fn to_grpc_event(
+    session_id: &str,
+    sequence: i64,
+    created: DateTimeAsMicroseconds,
+    last_sequence: i64,
+    data: Option<&ReplicationEventData>,
+) -> ReplicationEventGrpcModel {
+    let mut result = ReplicationEventGrpcModel {
+        session_id: session_id.to_string(),
+        sequence,
+        created: created.unix_microseconds,
+        last_sequence,
+        new_messages: None,
+        topics_snapshot: None,
+        upsert_topic_snapshot: None,
+        delete_queue_snapshot: None,
+        delete_topic: None,
+        restore_topic: None,
+        rollback_topics_snapshot: None,
+    };
+
+    match data {
+        Some(ReplicationEventData::NewMessages { topic_id, messages }) => {
+            result.new_messages = Some(ReplicatedMessagesGrpcModel {
+                topic_id: topic_id.to_string(),
+                messages: messages.iter().map(|itm| itm.into()).collect(),
+            });
+        }
+        Some(ReplicationEventData::TopicsSnapshot(snapshot)) => {
+            result.topics_snapshot = Some(SaveQueueSnapshotGrpcRequest {
+                queue_snapshot: snapshot.iter().map(|itm| itm.into()).collect(),
+            });
+        }
+        Some(ReplicationEventData::UpsertTopicSnapshot(topic)) => {
+            result.upsert_topic_snapshot = Some(topic.into());
+        }
+        Some(ReplicationEventData::DeleteQueueSnapshot { topic_id, queue_id }) => {
+            result.delete_queue_snapshot = Some(DeleteQueueSnapshotGrpcRequest {
+                topic_id: topic_id.to_string(),
+                queue_id: queue_id.to_string(),
+            });
+        }
+        Some(ReplicationEventData::DeleteTopic {
+            topic_id,
+            message_id,
+            gc_after,
+        }) => {
+            result.delete_topic = Some(ReplicatedDeletedTopicGrpcModel {
+                topic_id: topic_id.to_string(),
+                message_id: *message_id,
+                gc_after: *gc_after,
+            });
+        }
+        Some(ReplicationEventData::RestoreTopic { topic_id }) => {
+            result.restore_topic = Some(RestoreTopicRequest {
+                topic_id: topic_id.to_string(),
+            });
+        }
+        Some(ReplicationEventData::RollbackTopicsSnapshot(snapshot)) => {
+            result.rollback_topics_snapshot = Some(prost::Message::encode_to_vec(snapshot));
+        }
+        None => {}
+    }
+
+    result
+}
+
+pub fn to_domain_event(src: ReplicationEventGrpcModel) -> Option<ReplicationEventData> {
+    if let Some(new_messages) = src.new_messages {
+        return Some(ReplicationEventData::NewMessages {
+            topic_id: new_messages.topic_id,
+            messages: new_messages.messages.into_iter().map(to_message).collect(),
+        });
+    }
+
+    if let Some(topics_snapshot) = &src.topics_snapshot {
+        return Some(ReplicationEventData::TopicsSnapshot(
+            to_domain::to_topics_data(topics_snapshot),
+        ));
+    }
+
+    if let Some(topic) = &src.upsert_topic_snapshot {
+        return Some(ReplicationEventData::UpsertTopicSnapshot(
+            to_domain::to_topic_data(topic),
+        ));
+    }
+
+    if let Some(delete_queue) = src.delete_queue_snapshot {
+        return Some(ReplicationEventData::DeleteQueueSnapshot {
+            topic_id: delete_queue.topic_id,
+            queue_id: delete_queue.queue_id,
+        });
+    }
+
+    if let Some(delete_topic) = src.delete_topic {
+        return Some(ReplicationEventData::DeleteTopic {
+            topic_id: delete_topic.topic_id,
+            message_id: delete_topic.message_id,
+            gc_after: delete_topic.gc_after,
+        });
+    }
+
+    if let Some(restore_topic) = src.restore_topic {
+        return Some(ReplicationEventData::RestoreTopic {
+            topic_id: restore_topic.topic_id,
+        });
+    }
+
+    if let Some(snapshot) = src.rollback_topics_snapshot {
+        return Some(ReplicationEventData::RollbackTopicsSnapshot(
+            prost::Message::decode(snapshot.as_slice()).ok()?,
+        ));
+    }
+
+    None
+}
+
+pub fn to_message(src: MessageContentGrpcModel) -> MessageProtobufModel {
+    MessageProtobufModel::new(
+        MessageId::new(src.message_id),
+        DateTimeAsMicroseconds::new(src.created),
+        src.data,
+        src.meta_data
+            .into_iter()
+            .map(|itm| MessageMetaDataProtobufModel {
+                key: itm.key,
+                value: itm.value,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::MessageId;
+    use my_service_bus::shared::protobuf_models::{
+        MessageMetaDataProtobufModel, MessageProtobufModel,
+    };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::{
+        replication::ReplicationEventData,
+        topics_snapshot::{DeletedTopicProtobufModel, TopicsSnapshotProtobufModelV2},
+    };
+
+    #[test]
+    fn test_new_messages_round_trip() {
+        let data = ReplicationEventData::NewMessages {
+            topic_id: "orders".to_string(),
+            messages: vec![MessageProtobufModel::new(
+                MessageId::new(15),
+                DateTimeAsMicroseconds::new(1_000),
+                b"payload".to_vec(),
+                vec![MessageMetaDataProtobufModel {
+                    key: "source".to_string(),
+                    value: "api".to_string(),
+                }],
+            )],
+        };
+
+        let event = super::to_grpc_event(
+            "session",
+            3,
+            DateTimeAsMicroseconds::new(2_000),
+            5,
+            Some(&data),
+        );
+
+        assert_eq!(event.sequence, 3);
+        assert_eq!(event.last_sequence, 5);
+
+        match super::to_domain_event(event) {
+            Some(ReplicationEventData::NewMessages { topic_id, messages }) => {
+                assert_eq!(topic_id, "orders");
+                assert_eq!(messages.len(), 1);
+                assert_eq!(messages[0].get_message_id().get_value(), 15);
+                assert_eq!(messages[0].data, b"payload".to_vec());
+                assert_eq!(messages[0].headers[0].key, "source");
+            }
+            other => panic!("Unexpected event {:?}", other),
+        }
+    }
+
+    fn round_trip(data: ReplicationEventData) -> Option<ReplicationEventData> {
+        let event =
+            super::to_grpc_event("session", 1, DateTimeAsMicroseconds::new(0), 1, Some(&data));
+
+        super::to_domain_event(event)
+    }
+
+    #[test]
+    fn test_delete_and_restore_topic_round_trip() {
+        let data = ReplicationEventData::DeleteTopic {
+            topic_id: "orders".to_string(),
+            message_id: 15,
+            gc_after: 1_000,
+        };
+
+        match round_trip(data) {
+            Some(ReplicationEventData::DeleteTopic {
+                topic_id,
+                message_id,
+                gc_after,
+            }) => {
+                assert_eq!(topic_id, "orders");
+                assert_eq!(message_id, 15);
+                assert_eq!(gc_after, 1_000);
+            }
+            other => panic!("Unexpected event {:?}", other),
+        }
+
+        let data = ReplicationEventData::RestoreTopic {
+            topic_id: "orders".to_string(),
+        };
+
+        match round_trip(data) {
+            Some(ReplicationEventData::RestoreTopic { topic_id }) => {
+                assert_eq!(topic_id, "orders");
+            }
+            other => panic!("Unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rollback_topics_snapshot_round_trip() {
+        let snapshot = TopicsSnapshotProtobufModelV2 {
+            data: vec![],
+            deleted_topics: vec![DeletedTopicProtobufModel {
+                topic_id: "orders".to_string(),
+                message_id: 15,
+                gc_after: 1_000,
+            }],
+        };
+
+        match round_trip(ReplicationEventData::RollbackTopicsSnapshot(
+            snapshot.clone(),
+        )) {
+            Some(ReplicationEventData::RollbackTopicsSnapshot(result)) => {
+                assert_eq!(result, snapshot);
+            }
+            other => panic!("Unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_heartbeat_has_no_data() {
+        let event = super::to_grpc_event("session", 0, DateTimeAsMicroseconds::new(0), 5, None);
+
+        assert!(super::to_domain_event(event).is_none());
+    }
+}
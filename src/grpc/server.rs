@@ -1,6 +1,8 @@
 use crate::app::AppContext;
+use crate::persistence_grpc::my_service_bus_history_reader_grpc_service_server::MyServiceBusHistoryReaderGrpcServiceServer;
 use crate::persistence_grpc::my_service_bus_messages_persistence_grpc_service_server::MyServiceBusMessagesPersistenceGrpcServiceServer;
 use crate::persistence_grpc::my_service_bus_queue_persistence_grpc_service_server::MyServiceBusQueuePersistenceGrpcServiceServer;
+use crate::persistence_grpc::my_service_bus_replication_grpc_service_server::MyServiceBusReplicationGrpcServiceServer;
 use anyhow::*;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -21,14 +23,23 @@ pub async fn start(app: Arc<AppContext>, port: u16) -> Result<()> {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     let service = MyServicePersistenceGrpc::new(app);
 
-    println!("Listening to {:?} as grpc endpoint", addr);
+    crate::app::LOGS.add_info(
+        None,
+        "GrpcServer",
+        format!("Listening to {:?} as grpc endpoint", addr),
+    );
     Server::builder()
+        .trace_fn(crate::telemetry::create_grpc_span)
         .add_service(MyServiceBusQueuePersistenceGrpcServiceServer::new(
             service.clone(),
         ))
         .add_service(MyServiceBusMessagesPersistenceGrpcServiceServer::new(
-            service,
+            service.clone(),
+        ))
+        .add_service(MyServiceBusHistoryReaderGrpcServiceServer::new(
+            service.clone(),
         ))
+        .add_service(MyServiceBusReplicationGrpcServiceServer::new(service))
         .serve(addr)
         .await
         .context("Server error")
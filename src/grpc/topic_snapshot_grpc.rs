@@ -23,6 +23,8 @@ impl MyServiceBusQueuePersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         _: tonic::Request<()>,
     ) -> Result<tonic::Response<Self::GetSnapshotStream>, tonic::Status> {
+        let timer = self.app.metrics_keeper.start_grpc_call_timer("GetSnapshot");
+
         contracts::check_flags(self.app.as_ref())?;
 
         let app = self.app.clone();
@@ -32,9 +34,13 @@ impl MyServiceBusQueuePersistenceGrpcService for MyServicePersistenceGrpc {
         tokio::spawn(async move {
             let result = app.topics_snapshot.get().await;
 
-            for topic_snapshot in &result.snapshot.data {
-                tx.send(Ok(topic_snapshot.into())).await.unwrap();
+            for topic_snapshot in result.topics.values() {
+                if tx.send(Ok(topic_snapshot.as_ref().into())).await.is_err() {
+                    break;
+                }
             }
+
+            timer.observe_duration();
         });
 
         Ok(Response::new(Box::pin(
@@ -46,12 +52,87 @@ impl MyServiceBusQueuePersistenceGrpcService for MyServicePersistenceGrpc {
         &self,
         request: tonic::Request<SaveQueueSnapshotGrpcRequest>,
     ) -> Result<tonic::Response<()>, tonic::Status> {
-        contracts::check_flags(self.app.as_ref())?;
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("SaveSnapshot");
+
+        contracts::check_write_flags(self.app.as_ref())?;
 
         let grpc_contract = request.into_inner();
         let snapshot = super::topic_snapshot_mappers::to_domain::to_topics_data(&grpc_contract);
 
-        self.app.topics_snapshot.update(snapshot).await;
+        crate::operations::save_topics_snapshot(self.app.as_ref(), snapshot).await?;
+
+        Ok(tonic::Response::new(()))
+    }
+
+    async fn get_topic_snapshot(
+        &self,
+        request: tonic::Request<GetTopicSnapshotGrpcRequest>,
+    ) -> Result<tonic::Response<TopicAndQueuesSnapshotGrpcModel>, tonic::Status> {
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("GetTopicSnapshot");
+
+        contracts::check_flags(self.app.as_ref())?;
+
+        let request = request.into_inner();
+
+        let topic_snapshot = self
+            .app
+            .topics_snapshot
+            .get_topic(request.topic_id.as_str())
+            .await;
+
+        match topic_snapshot {
+            Some(topic_snapshot) => Ok(tonic::Response::new(topic_snapshot.as_ref().into())),
+            None => Err(tonic::Status::not_found(format!(
+                "Topic {} not found",
+                request.topic_id
+            ))),
+        }
+    }
+
+    async fn upsert_topic_snapshot(
+        &self,
+        request: tonic::Request<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<tonic::Response<()>, tonic::Status> {
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("UpsertTopicSnapshot");
+
+        contracts::check_write_flags(self.app.as_ref())?;
+
+        let grpc_contract = request.into_inner();
+        let topic = super::topic_snapshot_mappers::to_domain::to_topic_data(&grpc_contract);
+
+        crate::operations::upsert_topic_snapshot(self.app.as_ref(), topic).await?;
+
+        Ok(tonic::Response::new(()))
+    }
+
+    async fn delete_queue_snapshot(
+        &self,
+        request: tonic::Request<DeleteQueueSnapshotGrpcRequest>,
+    ) -> Result<tonic::Response<()>, tonic::Status> {
+        let _timer = self
+            .app
+            .metrics_keeper
+            .start_grpc_call_timer("DeleteQueueSnapshot");
+
+        contracts::check_write_flags(self.app.as_ref())?;
+
+        let request = request.into_inner();
+
+        crate::operations::delete_queue_snapshot(
+            self.app.as_ref(),
+            request.topic_id.as_str(),
+            request.queue_id.as_str(),
+        )
+        .await?;
 
         Ok(tonic::Response::new(()))
     }
@@ -11,17 +11,19 @@ pub fn to_topics_data(src: &SaveQueueSnapshotGrpcRequest) -> Vec<TopicSnapshotPr
     src.queue_snapshot
         .as_slice()
         .iter()
-        .map(|itm| {
-            TopicSnapshotProtobufModel::new(
-                itm.topic_id.to_string(),
-                itm.message_id.as_message_id(),
-                to_queue_snapshot_vec(itm.queue_snapshots.as_slice()),
-                itm.persist,
-            )
-        })
+        .map(|itm| to_topic_data(itm))
         .collect()
 }
 
+pub fn to_topic_data(src: &TopicAndQueuesSnapshotGrpcModel) -> TopicSnapshotProtobufModel {
+    TopicSnapshotProtobufModel::new(
+        src.topic_id.to_string(),
+        src.message_id.as_message_id(),
+        to_queue_snapshot_vec(src.queue_snapshots.as_slice()),
+        src.persist,
+    )
+}
+
 pub fn to_queue_range(src: &QueueIndexRangeGrpcModel) -> QueueRangeProtobufModel {
     QueueRangeProtobufModel::new(src.from_id.as_message_id(), src.to_id.as_message_id())
 }
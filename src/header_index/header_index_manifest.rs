@@ -0,0 +1,117 @@
+use super::HeaderIndexManifestProtobufModel;
+
+// Runs of the same tier differ in size less than TIER_FACTOR times
+const TIER_FACTOR: i64 = 8;
+
+impl HeaderIndexManifestProtobufModel {
+    fn get_run_size(&self, index: usize) -> i64 {
+        self.run_sizes.get(index).copied().unwrap_or_default()
+    }
+
+    pub fn add_run(&mut self, run_id: i64, size: usize) {
+        self.run_sizes.resize(self.run_ids.len(), 0);
+        self.run_ids.push(run_id);
+        self.run_sizes.push(size as i64);
+    }
+
+    pub fn clear_runs(&mut self) -> Vec<i64> {
+        self.run_sizes.clear();
+        std::mem::take(&mut self.run_ids)
+    }
+
+    // Runs of the smallest tier which has at least min_runs runs. Merging runs of similar size
+    // keeps the amount of rewritten entries logarithmic instead of rewriting the whole index each time
+    pub fn get_runs_to_merge(&self, min_runs: usize) -> Option<Vec<i64>> {
+        let mut tiers: Vec<(u32, Vec<i64>)> = Vec::new();
+
+        for (index, run_id) in self.run_ids.iter().enumerate() {
+            let tier = get_tier(self.get_run_size(index));
+
+            match tiers.iter_mut().find(|(itm, _)| *itm == tier) {
+                Some((_, run_ids)) => run_ids.push(*run_id),
+                None => tiers.push((tier, vec![*run_id])),
+            }
+        }
+
+        tiers.sort_by_key(|(tier, _)| *tier);
+
+        tiers
+            .into_iter()
+            .map(|(_, run_ids)| run_ids)
+            .find(|run_ids| run_ids.len() >= min_runs)
+    }
+
+    // Merged runs are replaced with the run they are merged into
+    pub fn replace_runs(&mut self, merged_run_ids: &[i64], run_id: i64, size: usize) {
+        self.run_sizes.resize(self.run_ids.len(), 0);
+
+        let mut index = 0;
+
+        while index < self.run_ids.len() {
+            if merged_run_ids.contains(&self.run_ids[index]) {
+                self.run_ids.remove(index);
+                self.run_sizes.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        self.add_run(run_id, size);
+    }
+}
+
+fn get_tier(size: i64) -> u32 {
+    let mut tier = 0;
+    let mut size = size;
+
+    while size >= TIER_FACTOR {
+        size /= TIER_FACTOR;
+        tier += 1;
+    }
+
+    tier
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header_index::HeaderIndexManifestProtobufModel;
+
+    #[test]
+    fn test_runs_are_merged_by_tiers() {
+        let mut manifest = HeaderIndexManifestProtobufModel::default();
+
+        manifest.add_run(1, 1000);
+
+        for run_id in 2..5 {
+            manifest.add_run(run_id, 10);
+        }
+
+        assert!(manifest.get_runs_to_merge(4).is_none());
+
+        manifest.add_run(5, 12);
+
+        // Big run is not rewritten with the small ones
+        let to_merge = manifest.get_runs_to_merge(4).unwrap();
+        assert_eq!(to_merge, vec![2, 3, 4, 5]);
+
+        manifest.replace_runs(to_merge.as_slice(), 6, 42);
+
+        assert_eq!(manifest.run_ids, vec![1, 6]);
+        assert_eq!(manifest.run_sizes, vec![1000, 42]);
+    }
+
+    #[test]
+    fn test_runs_without_sizes() {
+        let mut manifest = HeaderIndexManifestProtobufModel {
+            header_key: "OrderId".to_string(),
+            run_ids: vec![1, 2],
+            next_run_id: 3,
+            run_sizes: vec![],
+        };
+
+        manifest.add_run(3, 1000);
+
+        assert_eq!(manifest.run_sizes, vec![0, 0, 1000]);
+        assert_eq!(manifest.get_runs_to_merge(2).unwrap(), vec![1, 2]);
+    }
+}
@@ -0,0 +1,116 @@
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+
+use super::{HeaderIndexEntryProtobufModel, HeaderIndexRunProtobufModel};
+
+// Hash must be stable between versions, since it is persisted
+pub fn hash_header_value(value: &str) -> u64 {
+    let digest = md5::compute(value.as_bytes());
+
+    let mut result = [0u8; 8];
+    result.copy_from_slice(&digest.0[..8]);
+    u64::from_le_bytes(result)
+}
+
+impl HeaderIndexRunProtobufModel {
+    pub fn from_messages<'s>(
+        header_key: &str,
+        messages: impl Iterator<Item = &'s MessageProtobufModel>,
+    ) -> Self {
+        let mut entries = Vec::new();
+
+        for msg in messages {
+            for header in &msg.headers {
+                if header.key == header_key {
+                    entries.push(HeaderIndexEntryProtobufModel {
+                        hash: hash_header_value(header.value.as_str()),
+                        message_id: msg.get_message_id().get_value(),
+                    });
+                }
+            }
+        }
+
+        sort_and_dedup(&mut entries);
+
+        Self { entries }
+    }
+
+    pub fn merge(runs: impl Iterator<Item = Self>) -> Self {
+        let mut entries = Vec::new();
+
+        for run in runs {
+            entries.extend(run.entries);
+        }
+
+        sort_and_dedup(&mut entries);
+
+        Self { entries }
+    }
+
+    // Message ids are candidates. Different values can have the same hash
+    pub fn find(&self, hash: u64) -> impl Iterator<Item = i64> + '_ {
+        let from = self.entries.partition_point(|itm| itm.hash < hash);
+
+        self.entries[from..]
+            .iter()
+            .take_while(move |itm| itm.hash == hash)
+            .map(|itm| itm.message_id)
+    }
+}
+
+fn sort_and_dedup(entries: &mut Vec<HeaderIndexEntryProtobufModel>) {
+    entries.sort_by_key(|itm| (itm.hash, itm.message_id));
+    entries.dedup();
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::{
+        abstractions::MessageId,
+        shared::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel},
+    };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::header_index::HeaderIndexRunProtobufModel;
+
+    fn message(message_id: i64, order_id: &str) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            MessageId::new(message_id),
+            DateTimeAsMicroseconds::new(0),
+            vec![],
+            vec![MessageMetaDataProtobufModel {
+                key: "OrderId".to_string(),
+                value: order_id.to_string(),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_build_merge_and_find() {
+        let first = vec![message(1, "a"), message(2, "b"), message(3, "a")];
+        let second = vec![message(3, "a"), message(4, "c"), message(5, "a")];
+
+        let first = HeaderIndexRunProtobufModel::from_messages("OrderId", first.iter());
+        let second = HeaderIndexRunProtobufModel::from_messages("OrderId", second.iter());
+
+        let merged = HeaderIndexRunProtobufModel::merge(vec![first, second].into_iter());
+
+        assert_eq!(merged.entries.len(), 5);
+
+        let found: Vec<i64> = merged.find(super::hash_header_value("a")).collect();
+        assert_eq!(found, vec![1, 3, 5]);
+
+        let found: Vec<i64> = merged.find(super::hash_header_value("c")).collect();
+        assert_eq!(found, vec![4]);
+
+        assert_eq!(merged.find(super::hash_header_value("d")).count(), 0);
+    }
+
+    #[test]
+    fn test_other_headers_are_not_indexed() {
+        let messages = vec![message(1, "a")];
+
+        let run = HeaderIndexRunProtobufModel::from_messages("CorrelationId", messages.iter());
+
+        assert!(run.entries.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::Mutex;
+
+pub struct HeaderIndexes {
+    header_keys: HashMap<String, String>,
+    // Index of the topic is appended and merged one operation at a time
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl HeaderIndexes {
+    pub fn new() -> Self {
+        Self {
+            header_keys: HashMap::new(),
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_header_key(&mut self, topic_id: &str, header_key: &str) {
+        self.header_keys
+            .insert(topic_id.to_string(), header_key.to_string());
+    }
+
+    pub fn get_header_key(&self, topic_id: &str) -> Option<&str> {
+        self.header_keys.get(topic_id).map(|itm| itm.as_str())
+    }
+
+    pub fn get_topics(&self) -> Vec<String> {
+        self.header_keys.keys().cloned().collect()
+    }
+
+    pub async fn get_lock(&self, topic_id: &str) -> Arc<Mutex<()>> {
+        let mut write_access = self.locks.lock().await;
+
+        write_access
+            .entry(topic_id.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone()
+    }
+}
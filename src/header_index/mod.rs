@@ -0,0 +1,8 @@
+mod header_index_manifest;
+mod header_index_run;
+mod header_indexes;
+mod protobuf_model;
+pub use header_index_manifest::*;
+pub use header_index_run::*;
+pub use header_indexes::*;
+pub use protobuf_model::*;
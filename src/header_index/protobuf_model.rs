@@ -0,0 +1,28 @@
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeaderIndexEntryProtobufModel {
+    #[prost(uint64, tag = "1")]
+    pub hash: u64,
+    #[prost(int64, tag = "2")]
+    pub message_id: i64,
+}
+
+// Entries are sorted by hash and message id
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeaderIndexRunProtobufModel {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<HeaderIndexEntryProtobufModel>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeaderIndexManifestProtobufModel {
+    #[prost(string, tag = "1")]
+    pub header_key: String,
+    #[prost(int64, repeated, tag = "2")]
+    pub run_ids: Vec<i64>,
+    #[prost(int64, tag = "3")]
+    pub next_run_id: i64,
+    // Amount of entries of each run in the order of run_ids. Runs of manifests written before
+    // sizes were added are treated as empty, so they are merged first
+    #[prost(int64, repeated, tag = "4")]
+    pub run_sizes: Vec<i64>,
+}
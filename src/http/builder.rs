@@ -14,15 +14,14 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         super::controllers::api_controller::GetStatusAction::new(app.clone()),
     ));
 
-    /*
-       result.register_get_action(Arc::new(
-           super::controllers::logs_controller::ActionLogs::new(app.clone()),
-       ));
-
-       result.register_get_action(Arc::new(
-           super::controllers::logs_controller::ActionLogsByTopic::new(app.clone()),
-       ));
-    */
+    result.register_get_action(Arc::new(
+        super::controllers::logs_controller::ActionLogs::new(app.clone()),
+    ));
+
+    result.register_get_action(Arc::new(
+        super::controllers::logs_controller::ActionLogsByTopic::new(app.clone()),
+    ));
+
     //Controller Topic
     result.register_delete_action(Arc::new(
         super::controllers::topic_controller::DeleteTopicAction::new(app.clone()),
@@ -32,6 +31,59 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         super::controllers::topic_controller::GetDeletedTopicsAction::new(app.clone()),
     ));
 
+    result.register_post_action(Arc::new(
+        super::controllers::topic_controller::RestoreTopicAction::new(app.clone()),
+    ));
+
+    result.register_get_action(Arc::new(
+        super::controllers::topic_controller::GetTopicStatsAction::new(app.clone()),
+    ));
+
+    //Controller Audit
+    result.register_get_action(Arc::new(
+        super::controllers::audit_controller::GetAuditLogAction::new(app.clone()),
+    ));
+
+    //Controller Backup
+    result.register_get_action(Arc::new(
+        super::controllers::backup_controller::GetBackupsAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::backup_controller::StartBackupAction::new(app.clone()),
+    ));
+
+    //Controller Export
+    result.register_get_action(Arc::new(
+        super::controllers::export_controller::GetParquetExportsAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::export_controller::StartParquetExportAction::new(app.clone()),
+    ));
+
+    //Controller Replication
+    result.register_get_action(Arc::new(
+        super::controllers::replication_controller::GetReplicationStatusAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::replication_controller::PromoteReplicaAction::new(app.clone()),
+    ));
+
+    //Controller Snapshot
+    result.register_get_action(Arc::new(
+        super::controllers::snapshot_controller::GetSnapshotHistoryAction::new(app.clone()),
+    ));
+
+    result.register_get_action(Arc::new(
+        super::controllers::snapshot_controller::GetSnapshotDiffAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::snapshot_controller::RollbackSnapshotAction::new(app.clone()),
+    ));
+
     result.register_get_action(Arc::new(
         super::controllers::prometheus_controller::MetricsAction::new(app.clone()),
     ));
@@ -48,5 +100,17 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         super::controllers::read_controller::ListFromDateAction::new(app.clone()),
     ));
 
+    result.register_get_action(Arc::new(
+        super::controllers::read_controller::SearchAction::new(app.clone()),
+    ));
+
+    result.register_get_action(Arc::new(
+        super::controllers::read_controller::RangeAction::new(app.clone()),
+    ));
+
+    result.register_get_action(Arc::new(
+        super::controllers::read_controller::DownloadAction::new(app.clone()),
+    ));
+
     result
 }
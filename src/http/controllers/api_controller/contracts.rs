@@ -135,6 +135,21 @@ impl LoadedPageModel {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct DeletedTopicStatusModel {
+    #[serde(rename = "topicId")]
+    topic_id: String,
+
+    #[serde(rename = "messageId")]
+    message_id: i64,
+
+    #[serde(rename = "gcAfter")]
+    gc_after: String,
+
+    #[serde(rename = "purgeIn")]
+    purge_in: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct StatusModel {
     #[serde(rename = "queuesSnapshotId")]
@@ -145,9 +160,14 @@ pub struct StatusModel {
     awaiting_operations: Vec<String>,
     #[serde(rename = "topics")]
     topics: Vec<TopicInfo>,
+    #[serde(rename = "deletedTopics")]
+    deleted_topics: Vec<DeletedTopicStatusModel>,
     system: SystemStatusModel,
     #[serde(skip_serializing_if = "Option::is_none")]
     initialing: Option<bool>,
+    // leader, passive or secondary. Shown only if leader lease or replica is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
 }
 
 impl StatusModel {
@@ -157,33 +177,65 @@ impl StatusModel {
         let mut topics = Vec::new();
         let now = DateTimeAsMicroseconds::now();
 
-        for snapshot in &topics_snapshot.snapshot.data {
-            let topic_data = app.topics_list.get(snapshot.topic_id.as_str()).await;
+        for snapshot in topics_snapshot.topics.values() {
+            let topic_data = if topics_snapshot.is_deleted(snapshot.topic_id.as_str()) {
+                None
+            } else {
+                app.topics_list.get(snapshot.topic_id.as_str()).await
+            };
 
             let topic_info_model = get_topics_model(snapshot, topic_data.as_ref(), now).await;
 
             topics.push(topic_info_model)
         }
 
+        let deleted_topics = topics_snapshot
+            .deleted_topics
+            .iter()
+            .map(|itm| {
+                let gc_after = DateTimeAsMicroseconds::new(itm.gc_after);
+
+                DeletedTopicStatusModel {
+                    topic_id: itm.topic_id.to_string(),
+                    message_id: itm.message_id,
+                    gc_after: gc_after.to_rfc3339(),
+                    purge_in: duration_to_string(
+                        gc_after.duration_since(now).as_positive_or_zero(),
+                    ),
+                }
+            })
+            .collect();
+
         let mut sys_info = sysinfo::System::new_all();
 
         // First we update all information of our system struct.
         sys_info.refresh_all();
 
-        let model = StatusModel {
-            initialing: match app.app_states.is_initialized() {
-                true => None,
-                false => Some(true),
-            },
-            queues_snapshot_id: topics_snapshot.snapshot_id,
-            active_operations: Vec::new(),
-            awaiting_operations: Vec::new(),
-            topics,
-            system: SystemStatusModel {
-                totalmem: sys_info.total_memory(),
-                usedmem: sys_info.used_memory(),
-            },
-        };
+        let model =
+            StatusModel {
+                initialing: match app.app_states.is_initialized() {
+                    true => None,
+                    false => Some(true),
+                },
+                role: match app.is_secondary() {
+                    true => Some("secondary".to_string()),
+                    false => app.leader_lease.as_ref().map(|leader_lease| {
+                        match leader_lease.is_leader() {
+                            true => "leader".to_string(),
+                            false => "passive".to_string(),
+                        }
+                    }),
+                },
+                queues_snapshot_id: topics_snapshot.snapshot_id,
+                active_operations: Vec::new(),
+                awaiting_operations: Vec::new(),
+                topics,
+                deleted_topics,
+                system: SystemStatusModel {
+                    totalmem: sys_info.total_memory(),
+                    usedmem: sys_info.used_memory(),
+                },
+            };
 
         return model;
     }
@@ -0,0 +1,11 @@
+use my_http_server::HttpContext;
+
+use crate::settings::SettingsModel;
+
+pub fn get_audit_initiator(settings: &SettingsModel, ctx: &HttpContext, api_key: &str) -> String {
+    format!(
+        "HTTP {} {}",
+        ctx.request.get_ip().get_real_ip(),
+        settings.get_api_key_identity(api_key)
+    )
+}
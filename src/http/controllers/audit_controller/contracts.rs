@@ -0,0 +1,42 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::*;
+
+use crate::audit_log::AuditLogItemProtobufModel;
+
+#[derive(MyHttpInput)]
+pub struct GetAuditLogHttpInput {
+    #[http_query(name = "topicId"; description = "Only items of the topic")]
+    pub topic_id: Option<String>,
+
+    #[http_query(name = "from"; description = "Items created since. ISO date time")]
+    pub from: Option<String>,
+
+    #[http_query(name = "to"; description = "Items created till. ISO date time")]
+    pub to: Option<String>,
+
+    #[http_query(name = "apiKey"; description = "Api Key")]
+    pub api_key: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct AuditLogItemHttpModel {
+    pub created: String,
+    pub action: String,
+    pub initiator: String,
+    #[serde(rename = "topicId")]
+    pub topic_id: Option<String>,
+    pub parameters: String,
+}
+
+impl AuditLogItemHttpModel {
+    pub fn new(src: &AuditLogItemProtobufModel) -> Self {
+        Self {
+            created: DateTimeAsMicroseconds::new(src.created).to_rfc3339(),
+            action: src.action.to_string(),
+            initiator: src.initiator.to_string(),
+            topic_id: src.topic_id.clone(),
+            parameters: src.parameters.to_string(),
+        }
+    }
+}
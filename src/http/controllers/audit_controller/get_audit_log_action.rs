@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Audit",
+    input_data: "GetAuditLogHttpInput",
+    description: "Get audit log of administrative operations",
+    summary: "Get audit log of administrative operations",
+    controller: "Audit",
+    result:[
+        {status_code: 200, description: "Audit log items. Newest go first", model:"Vec<AuditLogItemHttpModel>"},
+        {status_code: 400, description: "Invalid date time"},
+        {status_code: 401, description: "Invalid Secret Key or audit log is not enabled"},
+    ]
+)]
+pub struct GetAuditLogAction {
+    app: Arc<AppContext>,
+}
+
+impl GetAuditLogAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetAuditLogAction,
+    input_data: GetAuditLogHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let secret_key = match action
+        .app
+        .settings
+        .audit
+        .as_ref()
+        .and_then(|itm| itm.read_secret_key.as_ref())
+    {
+        Some(secret_key) => secret_key,
+        None => {
+            return Err(HttpFailResult::as_unauthorized(
+                "Audit log is not enabled".to_string().into(),
+            ));
+        }
+    };
+
+    if secret_key.as_str() != input_data.api_key {
+        return Err(HttpFailResult::as_unauthorized(
+            "Invalid Secret Key".to_string().into(),
+        ));
+    }
+
+    let from = parse_date_time(input_data.from.as_deref())?;
+    let to = parse_date_time(input_data.to.as_deref())?;
+
+    let result: Vec<AuditLogItemHttpModel> = action
+        .app
+        .audit_log
+        .get(input_data.topic_id.as_deref(), from, to)
+        .await
+        .iter()
+        .map(|itm| AuditLogItemHttpModel::new(itm.as_ref()))
+        .collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
+
+fn parse_date_time(src: Option<&str>) -> Result<Option<DateTimeAsMicroseconds>, HttpFailResult> {
+    let src = match src {
+        Some(src) => src,
+        None => return Ok(None),
+    };
+
+    match DateTimeAsMicroseconds::parse_iso_string(src) {
+        Some(result) => Ok(Some(result)),
+        None => Err(HttpFailResult::as_validation_error(format!(
+            "Invalid date time {}",
+            src
+        ))),
+    }
+}
@@ -0,0 +1,3 @@
+mod contracts;
+mod get_audit_log_action;
+pub use get_audit_log_action::*;
@@ -0,0 +1,72 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use serde::*;
+
+use crate::backup::{BackupJob, BackupJobState};
+
+#[derive(MyHttpInput)]
+pub struct StartBackupHttpInput {
+    #[http_query(name = "target"; description="local or blob. Directory and container are configured in settings")]
+    pub target: String,
+
+    #[http_query(name = "name"; description="Name of the backup. Current date time is used if empty")]
+    pub name: Option<String>,
+
+    #[http_query(name = "apiKey"; description="Api Key")]
+    pub api_key: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct BackupJobHttpModel {
+    pub name: String,
+    pub target: String,
+    pub started: String,
+    pub finished: Option<String>,
+    pub state: String,
+    pub topics: usize,
+    #[serde(rename = "subPages")]
+    pub sub_pages: usize,
+    pub files: usize,
+    pub size: usize,
+    pub error: Option<String>,
+}
+
+impl BackupJobHttpModel {
+    pub fn new(src: &BackupJob) -> Self {
+        let mut result = Self {
+            name: src.name.to_string(),
+            target: src.target.to_string(),
+            started: src.started.to_rfc3339(),
+            finished: src.finished.map(|itm| itm.to_rfc3339()),
+            state: String::new(),
+            topics: 0,
+            sub_pages: 0,
+            files: 0,
+            size: 0,
+            error: None,
+        };
+
+        match &src.state {
+            BackupJobState::Running => {
+                result.state = "running".to_string();
+            }
+            BackupJobState::Finished {
+                topics,
+                sub_pages,
+                files,
+                size,
+            } => {
+                result.state = "finished".to_string();
+                result.topics = *topics;
+                result.sub_pages = *sub_pages;
+                result.files = *files;
+                result.size = *size;
+            }
+            BackupJobState::Failed(err) => {
+                result.state = "failed".to_string();
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Backup",
+    description: "Shows backups made since the start of the service",
+    summary: "Backup jobs",
+    controller: "Backup",
+    result:[
+        {status_code: 200, description: "Backup jobs", model:"Vec<BackupJobHttpModel>"},
+    ]
+)]
+pub struct GetBackupsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetBackupsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetBackupsAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result: Vec<BackupJobHttpModel> = action
+        .app
+        .backup_jobs
+        .get_all()
+        .await
+        .iter()
+        .map(BackupJobHttpModel::new)
+        .collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
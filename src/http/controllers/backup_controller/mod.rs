@@ -0,0 +1,5 @@
+mod contracts;
+mod get_backups_action;
+pub use get_backups_action::*;
+mod start_backup_action;
+pub use start_backup_action::*;
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Backup",
+    input_data: "StartBackupHttpInput",
+    description: "Starts backup of topics snapshot, messages and indexes in background",
+    summary: "Start backup",
+    controller: "Backup",
+    result:[
+        {status_code: 200, description: "Backup is started. State is shown by GET /api/Backup"},
+        {status_code: 400, description: "Backup is already running or target is not configured"},
+    ]
+)]
+pub struct StartBackupAction {
+    app: Arc<AppContext>,
+}
+
+impl StartBackupAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &StartBackupAction,
+    input_data: StartBackupHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    if action.app.settings.delete_topic_secret_key.as_str() != input_data.api_key {
+        return Err(HttpFailResult::as_unauthorized(
+            "Invalid Secret Key".to_string().into(),
+        ));
+    }
+
+    let name = match input_data.name {
+        Some(name) if !name.is_empty() => name,
+        _ => DateTimeAsMicroseconds::now()
+            .to_chrono_utc()
+            .format("%Y%m%d-%H%M%S")
+            .to_string(),
+    };
+
+    let initiator = super::super::audit::get_audit_initiator(
+        &action.app.settings,
+        ctx,
+        input_data.api_key.as_str(),
+    );
+
+    crate::operations::start_backup(
+        action.app.clone(),
+        name,
+        input_data.target.as_str(),
+        initiator.as_str(),
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
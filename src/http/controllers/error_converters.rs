@@ -6,6 +6,29 @@ impl From<crate::operations::OperationError> for HttpFailResult {
             crate::operations::OperationError::TopicNotFound(msg) => {
                 HttpFailResult::as_not_found(format!("Topic {} not found", msg), false)
             }
+            crate::operations::OperationError::SnapshotVersionNotFound(version) => {
+                HttpFailResult::as_not_found(
+                    format!("Snapshot version {} not found", version),
+                    false,
+                )
+            }
+            crate::operations::OperationError::ParquetExportError(msg) => {
+                HttpFailResult::as_validation_error(msg.to_string())
+            }
+            crate::operations::OperationError::BackupError(msg) => {
+                HttpFailResult::as_validation_error(msg.to_string())
+            }
+            crate::operations::OperationError::NotLeader => HttpFailResult::as_validation_error(
+                "Instance is passive. Writes are served by the leader".to_string(),
+            ),
+            crate::operations::OperationError::SecondaryReplica => {
+                HttpFailResult::as_validation_error(
+                    "Instance is a secondary replica. Writes are served by the primary".to_string(),
+                )
+            }
+            crate::operations::OperationError::ReplicationError(msg) => {
+                HttpFailResult::as_validation_error(msg.to_string())
+            }
             _ => HttpFailResult::as_fatal_error(format!("{:?}", src)),
         }
     }
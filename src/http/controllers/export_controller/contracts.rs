@@ -0,0 +1,84 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use serde::*;
+
+use crate::parquet_export::{ParquetExportJob, ParquetExportJobState};
+
+#[derive(MyHttpInput)]
+pub struct StartParquetExportHttpInput {
+    #[http_query(name = "topicId"; description="Id of topic")]
+    pub topic_id: String,
+
+    #[http_query(name = "target"; description="local or blob. Directory and container are configured in settings")]
+    pub target: String,
+
+    #[http_query(name = "fromMessageId"; description="From message id")]
+    pub from_message_id: Option<i64>,
+
+    #[http_query(name = "toMessageId"; description="To message id including")]
+    pub to_message_id: Option<i64>,
+
+    #[http_query(name = "fromDate"; description="From date. ISO date time")]
+    pub from_date: Option<String>,
+
+    #[http_query(name = "toDate"; description="To date. ISO date time")]
+    pub to_date: Option<String>,
+
+    #[http_query(name = "incremental"; description="Continue from the last exported message id")]
+    pub incremental: Option<bool>,
+
+    #[http_query(name = "apiKey"; description="Api Key")]
+    pub api_key: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct ParquetExportJobHttpModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    pub target: String,
+    pub started: String,
+    pub finished: Option<String>,
+    pub state: String,
+    pub files: Vec<String>,
+    pub exported: usize,
+    #[serde(rename = "lastMessageId")]
+    pub last_message_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl ParquetExportJobHttpModel {
+    pub fn new(src: &ParquetExportJob) -> Self {
+        let mut result = Self {
+            topic_id: src.topic_id.to_string(),
+            target: src.target.to_string(),
+            started: src.started.to_rfc3339(),
+            finished: src.finished.map(|itm| itm.to_rfc3339()),
+            state: String::new(),
+            files: vec![],
+            exported: 0,
+            last_message_id: None,
+            error: None,
+        };
+
+        match &src.state {
+            ParquetExportJobState::Running => {
+                result.state = "running".to_string();
+            }
+            ParquetExportJobState::Finished {
+                files,
+                exported,
+                last_message_id,
+            } => {
+                result.state = "finished".to_string();
+                result.files = files.clone();
+                result.exported = *exported;
+                result.last_message_id = *last_message_id;
+            }
+            ParquetExportJobState::Failed(err) => {
+                result.state = "failed".to_string();
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Export/Parquet",
+    description: "Shows last parquet export job of each topic",
+    summary: "Parquet export jobs",
+    controller: "Export",
+    result:[
+        {status_code: 200, description: "Export jobs", model:"Vec<ParquetExportJobHttpModel>"},
+    ]
+)]
+pub struct GetParquetExportsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetParquetExportsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetParquetExportsAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result: Vec<ParquetExportJobHttpModel> = action
+        .app
+        .parquet_export_jobs
+        .get_all()
+        .await
+        .iter()
+        .map(ParquetExportJobHttpModel::new)
+        .collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
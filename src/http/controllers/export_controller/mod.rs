@@ -0,0 +1,5 @@
+mod contracts;
+mod get_parquet_exports_action;
+pub use get_parquet_exports_action::*;
+mod start_parquet_export_action;
+pub use start_parquet_export_action::*;
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use my_service_bus::abstractions::MessageId;
+
+use crate::{app::AppContext, operations::ParquetExportRequest};
+
+use super::{super::read_controller::parse_date_time, contracts::*};
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Export/Parquet",
+    input_data: "StartParquetExportHttpInput",
+    description: "Starts export of topic messages to parquet files in background",
+    summary: "Start parquet export",
+    controller: "Export",
+    result:[
+        {status_code: 200, description: "Export is started. State is shown by GET /api/Export/Parquet"},
+        {status_code: 400, description: "Invalid parameters, target is not configured or export is already running"},
+        {status_code: 404, description: "Topic not found"},
+    ]
+)]
+pub struct StartParquetExportAction {
+    app: Arc<AppContext>,
+}
+
+impl StartParquetExportAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &StartParquetExportAction,
+    input_data: StartParquetExportHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    if action.app.settings.delete_topic_secret_key.as_str() != input_data.api_key {
+        return Err(HttpFailResult::as_unauthorized(
+            "Invalid Secret Key".to_string().into(),
+        ));
+    }
+
+    let request = ParquetExportRequest {
+        topic_id: input_data.topic_id.to_string(),
+        from_message_id: input_data.from_message_id.map(MessageId::new),
+        to_message_id: input_data.to_message_id.map(MessageId::new),
+        from_date: parse_date_time(input_data.from_date.as_deref())?,
+        to_date: parse_date_time(input_data.to_date.as_deref())?,
+        incremental: input_data.incremental.unwrap_or(false),
+    };
+
+    let initiator = super::super::audit::get_audit_initiator(
+        &action.app.settings,
+        ctx,
+        input_data.api_key.as_str(),
+    );
+
+    crate::operations::start_parquet_export(
+        action.app.clone(),
+        request,
+        input_data.target.as_str(),
+        initiator.as_str(),
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
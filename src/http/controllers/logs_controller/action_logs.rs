@@ -22,7 +22,7 @@ async fn handle_request(
 ) -> Result<HttpOkResult, HttpFailResult> {
     let mut sw = StopWatch::new();
     sw.start();
-    let logs = action.app.logs.get().await;
+    let logs = action.app.logs.get();
 
     let mut sb = StringBuilder::new();
 
@@ -29,11 +29,7 @@ async fn handle_request(
 ) -> Result<HttpOkResult, HttpFailResult> {
     let mut sw = StopWatch::new();
     sw.start();
-    let logs = action
-        .app
-        .logs
-        .get_by_topic(http_input.topic_id.as_str())
-        .await;
+    let logs = action.app.logs.get_by_topic(http_input.topic_id.as_str());
 
     if logs.is_none() {
         return Ok("---".to_string().into());
@@ -1,7 +1,13 @@
 pub mod api_controller;
+mod audit;
+pub mod audit_controller;
+pub mod backup_controller;
 mod error_converters;
+pub mod export_controller;
 pub mod home_controller;
-//pub mod logs_controller;
+pub mod logs_controller;
 pub mod prometheus_controller;
 pub mod read_controller;
+pub mod replication_controller;
+pub mod snapshot_controller;
 pub mod topic_controller;
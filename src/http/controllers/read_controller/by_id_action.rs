@@ -5,7 +5,7 @@ use my_service_bus::abstractions::AsMessageId;
 
 use crate::app::AppContext;
 
-use super::contracts::*;
+use super::{contracts::*, MessageContentFormat};
 
 #[my_http_server::macros::http_route(
     method:"GET",
@@ -16,6 +16,7 @@ summary:"Read message by Id",
 input_data:"GetMessageByIdInputContract",
 result:[
     {status_code: 202, description: "Found message"},
+    {status_code: 400, description: "Invalid format"},
     {status_code: 404, description: "Topic not found"},
 ]
 )]
@@ -35,6 +36,8 @@ async fn handle_request(
     input_data: GetMessageByIdInputContract,
     _ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
+    let format = MessageContentFormat::from_query(input_data.format.as_deref())?;
+
     let message = crate::operations::get_message_by_id(
         action.app.as_ref(),
         input_data.topic_id.as_str(),
@@ -44,7 +47,7 @@ async fn handle_request(
 
     match message {
         Some(msg) => {
-            let model = GetMessageResponseModel::create(msg.as_ref());
+            let model = GetMessageResponseModel::create(msg.as_ref(), format);
             return HttpOutput::as_json(model).into_ok_result(true).into();
         }
         None => {
@@ -2,9 +2,10 @@ use std::sync::Arc;
 
 use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
 use my_service_bus::shared::protobuf_models::MessageProtobufModel;
-use rust_extensions::base64::IntoBase64;
 use serde::{Deserialize, Serialize};
 
+use super::MessageContentFormat;
+
 #[derive(MyHttpInput)]
 pub struct GetMessageByIdInputContract {
     #[http_query(name = "topicId"; description="Id of topic")]
@@ -12,6 +13,9 @@ pub struct GetMessageByIdInputContract {
 
     #[http_query(name = "messageId"; description="Id of message")]
     pub message_id: i64,
+
+    #[http_query(name = "format"; description="Content format: base64 (default), utf8, json or hex")]
+    pub format: Option<String>,
 }
 
 #[derive(MyHttpInput)]
@@ -24,6 +28,144 @@ pub struct GetMessagesByIdInputContract {
 
     #[http_query(name = "fromDate"; description="From date")]
     pub from_date: String,
+
+    #[http_query(name = "jsonPath"; description="Json path filter on message content. Messages which are not json are skipped")]
+    pub json_path: Option<String>,
+
+    #[http_query(name = "format"; description="Content format: base64 (default), utf8, json or hex")]
+    pub format: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct SearchMessagesInputContract {
+    #[http_query(name = "topicId"; description="Id of topic")]
+    pub topic_id: String,
+
+    #[http_query(name = "fromMessageId"; description="Search from message id")]
+    pub from_message_id: Option<i64>,
+
+    #[http_query(name = "toMessageId"; description="Search till message id")]
+    pub to_message_id: Option<i64>,
+
+    #[http_query(name = "fromDate"; description="Search from date. ISO date time")]
+    pub from_date: Option<String>,
+
+    #[http_query(name = "toDate"; description="Search till date. ISO date time")]
+    pub to_date: Option<String>,
+
+    #[http_query(name = "headers"; description="Header predicates separated by ';'. key=value - equals, key^=prefix - starts with, key - exists")]
+    pub headers: String,
+
+    #[http_query(name = "jsonPath"; description="Json path filter on message content. For instance $.accountId == \"123\"")]
+    pub json_path: Option<String>,
+
+    #[http_query(name = "limit"; description="Maximum amount of messages to find"; default: 100)]
+    pub limit: usize,
+
+    #[http_query(name = "timeoutSec"; description="Search is stopped after timeout"; default: 30)]
+    pub timeout_sec: u64,
+
+    #[http_query(name = "format"; description="Content format: base64 (default), utf8, json or hex")]
+    pub format: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct GetMessagesRangeInputContract {
+    #[http_query(name = "topicId"; description="Id of topic")]
+    pub topic_id: String,
+
+    #[http_query(name = "from"; description="From message id")]
+    pub from: i64,
+
+    #[http_query(name = "to"; description="To message id including")]
+    pub to: i64,
+
+    #[http_query(name = "limit"; description="Page size. Capped by MaxResponseRecordsAmount"; default: 100)]
+    pub limit: usize,
+
+    #[http_query(name = "cursor"; description="Cursor of the next page returned by the previous call")]
+    pub cursor: Option<String>,
+
+    #[http_query(name = "jsonPath"; description="Json path filter on message content. Messages which are not json are skipped")]
+    pub json_path: Option<String>,
+
+    #[http_query(name = "format"; description="Content format: base64 (default), utf8, json or hex")]
+    pub format: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct DownloadMessagesInputContract {
+    #[http_query(name = "topicId"; description="Id of topic")]
+    pub topic_id: String,
+
+    #[http_query(name = "fromMessageId"; description="From message id")]
+    pub from_message_id: Option<i64>,
+
+    #[http_query(name = "toMessageId"; description="To message id including")]
+    pub to_message_id: Option<i64>,
+
+    #[http_query(name = "fromDate"; description="From date. ISO date time")]
+    pub from_date: Option<String>,
+
+    #[http_query(name = "toDate"; description="To date. ISO date time")]
+    pub to_date: Option<String>,
+
+    #[http_query(name = "jsonPath"; description="Json path filter on message content. Messages which are not json are skipped")]
+    pub json_path: Option<String>,
+
+    #[http_query(name = "format"; description="File format: ndjson (default), protobuf or pages")]
+    pub format: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct SearchMessagesResponseModel {
+    result: i32,
+    data: Vec<MessageJsonModel>,
+    #[serde(rename = "scannedSubPages")]
+    scanned_sub_pages: usize,
+    cancelled: bool,
+}
+
+impl SearchMessagesResponseModel {
+    pub fn create<'s>(
+        messages: impl Iterator<Item = &'s Arc<MessageProtobufModel>>,
+        format: MessageContentFormat,
+        scanned_sub_pages: usize,
+        cancelled: bool,
+    ) -> Self {
+        Self {
+            result: 0,
+            data: messages
+                .map(|msg| MessageJsonModel::new(msg, format))
+                .collect(),
+            scanned_sub_pages,
+            cancelled,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct GetMessagesRangeResponseModel {
+    result: i32,
+    data: Vec<MessageJsonModel>,
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+}
+
+impl GetMessagesRangeResponseModel {
+    pub fn create<'s>(
+        messages: impl Iterator<Item = &'s Arc<MessageProtobufModel>>,
+        format: MessageContentFormat,
+        next_cursor: Option<String>,
+    ) -> Self {
+        Self {
+            result: 0,
+            data: messages
+                .map(|msg| MessageJsonModel::new(msg, format))
+                .collect(),
+            next_cursor,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
@@ -33,11 +175,14 @@ pub struct GetMessagesResponseModel {
 }
 
 impl GetMessagesResponseModel {
-    pub fn create<'s>(messages: impl Iterator<Item = &'s Arc<MessageProtobufModel>>) -> Self {
+    pub fn create<'s>(
+        messages: impl Iterator<Item = &'s Arc<MessageProtobufModel>>,
+        format: MessageContentFormat,
+    ) -> Self {
         let mut data = Vec::new();
 
         for msg in messages {
-            data.push(MessageJsonModel::new(msg))
+            data.push(MessageJsonModel::new(msg, format))
         }
 
         GetMessagesResponseModel { result: 0, data }
@@ -51,27 +196,45 @@ pub struct GetMessageResponseModel {
 }
 
 impl GetMessageResponseModel {
-    pub fn create(message: &MessageProtobufModel) -> GetMessageResponseModel {
+    pub fn create(
+        message: &MessageProtobufModel,
+        format: MessageContentFormat,
+    ) -> GetMessageResponseModel {
         GetMessageResponseModel {
             result: 0,
-            data: MessageJsonModel::new(message),
+            data: MessageJsonModel::new(message, format),
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct MessageHeaderJsonModel {
+    key: String,
+    value: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
 pub struct MessageJsonModel {
     id: i64,
     created: String,
+    headers: Vec<MessageHeaderJsonModel>,
     content: String,
 }
 
 impl MessageJsonModel {
-    pub fn new(src: &MessageProtobufModel) -> Self {
+    pub fn new(src: &MessageProtobufModel, format: MessageContentFormat) -> Self {
         let result = Self {
             id: src.get_message_id().into(),
-            content: src.data.into_base64(),
+            content: format.render(src.data.as_slice()),
             created: src.get_created().to_rfc3339(),
+            headers: src
+                .headers
+                .iter()
+                .map(|itm| MessageHeaderJsonModel {
+                    key: itm.key.to_string(),
+                    value: itm.value.to_string(),
+                })
+                .collect(),
         };
 
         result
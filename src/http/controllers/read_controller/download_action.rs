@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use my_service_bus::abstractions::MessageId;
+use tokio_util::sync::CancellationToken;
+
+use crate::{app::AppContext, operations::SearchMessagesFilter};
+
+use super::{contracts::*, parse_date_time, parse_json_path, ExportFormat, ExportWriter};
+
+const EXPORT_CHANNEL_SIZE: usize = 1000;
+
+// Encoded bytes are sent to the client by chunks of this size
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+#[my_http_server::macros::http_route(
+    method:"GET",
+    route:"/Read/Download",
+    controller:"Read",
+    description:"Downloads messages within the range of ids or dates as a file",
+    summary:"Download messages as a file",
+    input_data:"DownloadMessagesInputContract",
+    result:[
+        {status_code: 200, description: "File with messages"},
+        {status_code: 400, description: "Invalid range, date time, format or json path filter"},
+        {status_code: 404, description: "Topic not found"},
+    ]
+)]
+pub struct DownloadAction {
+    app: Arc<AppContext>,
+}
+
+impl DownloadAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &DownloadAction,
+    input_data: DownloadMessagesInputContract,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let format = ExportFormat::from_query(input_data.format.as_deref())?;
+
+    let filter = SearchMessagesFilter {
+        from_message_id: input_data.from_message_id.map(MessageId::new),
+        to_message_id: input_data.to_message_id.map(MessageId::new),
+        from_date: parse_date_time(input_data.from_date.as_deref())?,
+        to_date: parse_date_time(input_data.to_date.as_deref())?,
+        predicates: vec![],
+        json_path: parse_json_path(input_data.json_path.as_deref())?,
+        limit: 0,
+    };
+
+    if let (Some(from), Some(to)) = (input_data.from_message_id, input_data.to_message_id) {
+        if from > to {
+            return Err(HttpFailResult::as_validation_error(format!(
+                "Invalid range {}-{}",
+                from, to
+            )));
+        }
+    }
+
+    // Status code is sent before the first chunk, so missing topic is checked in advance
+    if action
+        .app
+        .topics_list
+        .get(input_data.topic_id.as_str())
+        .await
+        .is_none()
+    {
+        return Err(HttpFailResult::as_not_found(
+            format!("Topic {} not found", input_data.topic_id),
+            false,
+        ));
+    }
+
+    let (body_sender, body) = hyper::Body::channel();
+
+    tokio::spawn(export_to_body(
+        action.app.clone(),
+        input_data.topic_id.to_string(),
+        filter,
+        format,
+        body_sender,
+    ));
+
+    let response = hyper::Response::builder()
+        .header("Content-Type", format.get_content_type())
+        .header(
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}.{}\"",
+                input_data.topic_id,
+                format.get_file_extension()
+            ),
+        )
+        .body(body)
+        .unwrap();
+
+    HttpOutput::Raw(response).into_ok_result(false).into()
+}
+
+// Export is cancelled as soon as client disconnects
+async fn export_to_body(
+    app: Arc<AppContext>,
+    topic_id: String,
+    filter: SearchMessagesFilter,
+    format: ExportFormat,
+    mut body_sender: hyper::body::Sender,
+) {
+    let cancellation_token = CancellationToken::new();
+    let (found_tx, mut found_rx) = tokio::sync::mpsc::channel(EXPORT_CHANNEL_SIZE);
+
+    let export = async {
+        let found_tx = found_tx;
+
+        crate::operations::export_messages(
+            app.as_ref(),
+            topic_id.as_str(),
+            &filter,
+            &cancellation_token,
+            &found_tx,
+        )
+        .await
+    };
+
+    let write = async {
+        let mut writer = ExportWriter::new(format);
+
+        while let Some(msg) = found_rx.recv().await {
+            app.metrics_keeper
+                .bytes_read(topic_id.as_str(), msg.data.len());
+
+            if let Err(err) = writer.write(msg.as_ref()) {
+                app.logs.add_error(
+                    Some(topic_id.as_str()),
+                    "Download messages",
+                    format!(
+                        "Can not encode message {}",
+                        msg.get_message_id().get_value()
+                    ),
+                    Some(format!("{:?}", err)),
+                );
+
+                cancellation_token.cancel();
+                found_rx.close();
+                return None;
+            }
+
+            if writer.get_pending_size() < EXPORT_CHUNK_SIZE {
+                continue;
+            }
+
+            let chunk = writer.take_chunk();
+
+            if body_sender.send_data(chunk.into()).await.is_err() {
+                cancellation_token.cancel();
+                found_rx.close();
+                return None;
+            }
+        }
+
+        Some(writer)
+    };
+
+    let (result, writer) = tokio::join!(export, write);
+
+    let writer = match writer {
+        Some(writer) => writer,
+        None => {
+            body_sender.abort();
+            return;
+        }
+    };
+
+    if let Err(err) = result {
+        app.logs.add_error(
+            Some(topic_id.as_str()),
+            "Download messages",
+            "Export is interrupted".to_string(),
+            Some(format!("{:?}", err)),
+        );
+
+        body_sender.abort();
+        return;
+    }
+
+    match writer.finish() {
+        Ok(chunk) => {
+            let _ = body_sender.send_data(chunk.into()).await;
+        }
+        Err(err) => {
+            app.logs.add_error(
+                Some(topic_id.as_str()),
+                "Download messages",
+                "Can not finish the file".to_string(),
+                Some(format!("{:?}", err)),
+            );
+
+            body_sender.abort();
+        }
+    }
+}
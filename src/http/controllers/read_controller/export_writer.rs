@@ -0,0 +1,264 @@
+use my_http_server::HttpFailResult;
+use my_service_bus::shared::{
+    page_compressor::CompressedPageBuilder, protobuf_models::MessageProtobufModel,
+};
+
+use super::{contracts::MessageJsonModel, MessageContentFormat};
+
+// Pages export is a sequence of compressed pages of up to this amount of messages each
+const MESSAGES_PER_COMPRESSED_PAGE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Ndjson,
+    Protobuf,
+    Pages,
+}
+
+impl ExportFormat {
+    // Ndjson is the default
+    pub fn from_query(src: Option<&str>) -> Result<Self, HttpFailResult> {
+        match src {
+            None | Some("ndjson") => Ok(Self::Ndjson),
+            Some("protobuf") => Ok(Self::Protobuf),
+            Some("pages") => Ok(Self::Pages),
+            Some(src) => Err(HttpFailResult::as_validation_error(format!(
+                "Invalid format {}. Supported formats: ndjson, protobuf, pages",
+                src
+            ))),
+        }
+    }
+
+    pub fn get_content_type(&self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson",
+            Self::Protobuf => "application/octet-stream",
+            Self::Pages => "application/octet-stream",
+        }
+    }
+
+    pub fn get_file_extension(&self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Protobuf => "pb",
+            Self::Pages => "bin",
+        }
+    }
+}
+
+// Encodes messages one by one. Encoded bytes are taken by chunks, so the whole export is never kept in memory
+pub enum ExportWriter {
+    // One json per line. Content is base64
+    Ndjson(Vec<u8>),
+    // Length delimited MessageProtobufModel
+    Protobuf(Vec<u8>),
+    // Compressed pages in the format sub pages are archived in. Each page is prefixed by its length: u32 LE.
+    // File itself is not a zip archive, only its pages are
+    Pages {
+        page: CompressedPageBuilder,
+        messages_in_page: usize,
+        buffer: Vec<u8>,
+    },
+}
+
+impl ExportWriter {
+    pub fn new(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Ndjson => Self::Ndjson(Vec::new()),
+            ExportFormat::Protobuf => Self::Protobuf(Vec::new()),
+            ExportFormat::Pages => Self::Pages {
+                page: CompressedPageBuilder::new_as_single_file(),
+                messages_in_page: 0,
+                buffer: Vec::new(),
+            },
+        }
+    }
+
+    pub fn write(&mut self, msg: &MessageProtobufModel) -> std::io::Result<()> {
+        match self {
+            Self::Ndjson(buffer) => {
+                let model = MessageJsonModel::new(msg, MessageContentFormat::Base64);
+                serde_json::to_writer(&mut *buffer, &model)?;
+                buffer.push(b'\n');
+            }
+            Self::Protobuf(buffer) => {
+                prost::Message::encode_length_delimited(msg, buffer)?;
+            }
+            Self::Pages {
+                page,
+                messages_in_page,
+                buffer,
+            } => {
+                page.add_message(msg).map_err(to_io_error)?;
+                *messages_in_page += 1;
+
+                if *messages_in_page >= MESSAGES_PER_COMPRESSED_PAGE {
+                    let page = std::mem::replace(page, CompressedPageBuilder::new_as_single_file());
+                    write_compressed_page(page, buffer)?;
+                    *messages_in_page = 0;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_pending_size(&self) -> usize {
+        match self {
+            Self::Ndjson(buffer) => buffer.len(),
+            Self::Protobuf(buffer) => buffer.len(),
+            Self::Pages { buffer, .. } => buffer.len(),
+        }
+    }
+
+    pub fn take_chunk(&mut self) -> Vec<u8> {
+        match self {
+            Self::Ndjson(buffer) => std::mem::take(buffer),
+            Self::Protobuf(buffer) => std::mem::take(buffer),
+            Self::Pages { buffer, .. } => std::mem::take(buffer),
+        }
+    }
+
+    // Returns the rest of the file
+    pub fn finish(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Ndjson(buffer) => Ok(buffer),
+            Self::Protobuf(buffer) => Ok(buffer),
+            Self::Pages {
+                page,
+                messages_in_page,
+                mut buffer,
+            } => {
+                if messages_in_page > 0 {
+                    write_compressed_page(page, &mut buffer)?;
+                }
+
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+fn write_compressed_page(
+    mut page: CompressedPageBuilder,
+    dest: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    let payload = page.get_payload().map_err(to_io_error)?;
+
+    dest.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    dest.extend_from_slice(payload.as_slice());
+
+    Ok(())
+}
+
+fn to_io_error(err: impl std::fmt::Debug) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::{
+        abstractions::MessageId,
+        shared::{
+            page_compressor::CompressedPageReader,
+            protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel},
+        },
+    };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{ExportFormat, ExportWriter};
+
+    fn message(id: i64) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            MessageId::new(id),
+            DateTimeAsMicroseconds::new(0),
+            format!("payload-{}", id).into_bytes(),
+            vec![MessageMetaDataProtobufModel {
+                key: "source".to_string(),
+                value: "api".to_string(),
+            }],
+        )
+    }
+
+    fn export(format: ExportFormat) -> Vec<u8> {
+        let mut writer = ExportWriter::new(format);
+
+        let mut result = Vec::new();
+
+        for id in 1..=3 {
+            writer.write(&message(id)).unwrap();
+            result.extend(writer.take_chunk());
+        }
+
+        result.extend(writer.finish().unwrap());
+        result
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let result = String::from_utf8(export(ExportFormat::Ndjson)).unwrap();
+
+        let lines: Vec<serde_json::Value> = result
+            .lines()
+            .map(|itm| serde_json::from_str(itm).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2]["id"], 3);
+        assert_eq!(lines[0]["headers"][0]["key"], "source");
+    }
+
+    #[test]
+    fn test_protobuf() {
+        let result = export(ExportFormat::Protobuf);
+
+        let mut src = result.as_slice();
+
+        let mut ids = Vec::new();
+
+        while !src.is_empty() {
+            let msg: MessageProtobufModel =
+                prost::Message::decode_length_delimited(&mut src).unwrap();
+            ids.push(msg.get_message_id().get_value());
+        }
+
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pages() {
+        let mut writer = ExportWriter::new(ExportFormat::Pages);
+
+        let mut result = Vec::new();
+
+        for id in 1..=super::MESSAGES_PER_COMPRESSED_PAGE as i64 + 2 {
+            writer.write(&message(id)).unwrap();
+            result.extend(writer.take_chunk());
+        }
+
+        result.extend(writer.finish().unwrap());
+
+        let mut src = result.as_slice();
+        let mut pages = Vec::new();
+
+        while !src.is_empty() {
+            let len = u32::from_le_bytes(src[..4].try_into().unwrap()) as usize;
+
+            let mut reader = CompressedPageReader::new(&src[4..4 + len]).unwrap();
+
+            let mut messages = Vec::new();
+
+            while let Some(msg) = reader.get_next_message().unwrap() {
+                messages.push(msg);
+            }
+
+            pages.push(messages);
+            src = &src[4 + len..];
+        }
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].len(), super::MESSAGES_PER_COMPRESSED_PAGE);
+        assert_eq!(pages[1].len(), 2);
+        assert_eq!(pages[1][1].data, b"payload-1002".to_vec());
+    }
+}
@@ -1,8 +1,10 @@
-use super::contracts::*;
-use crate::app::AppContext;
+use super::{contracts::*, parse_json_path, MessageContentFormat};
+use crate::{app::AppContext, operations::SearchMessagesFilter};
 use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+
+const JSON_PATH_SCAN_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[my_http_server::macros::http_route(method:"GET",
 route:"/Read/ListFromDate",
@@ -12,6 +14,7 @@ summary:"Read message by Id",
 input_data:"GetMessagesByIdInputContract",
 result:[
     {status_code: 200, description: "Found messages"},
+    {status_code: 400, description: "Invalid date time, json path filter or format"},
     {status_code: 404, description: "Topic not found"},
 ]
 )]
@@ -30,15 +33,55 @@ async fn handle_request(
     input_data: GetMessagesByIdInputContract,
     _ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
+    let from_date = match DateTimeAsMicroseconds::parse_iso_string(input_data.from_date.as_str()) {
+        Some(result) => result,
+        None => {
+            return Err(HttpFailResult::as_validation_error(format!(
+                "Invalid date time {}",
+                input_data.from_date
+            )));
+        }
+    };
+
+    let format = MessageContentFormat::from_query(input_data.format.as_deref())?;
+
+    let max_amount = input_data
+        .max_amount
+        .min(action.app.settings.max_response_records_amount);
+
+    if let Some(json_path) = parse_json_path(input_data.json_path.as_deref())? {
+        let filter = SearchMessagesFilter {
+            from_message_id: None,
+            to_message_id: None,
+            from_date: Some(from_date),
+            to_date: None,
+            predicates: vec![],
+            json_path: Some(json_path),
+            limit: max_amount,
+        };
+
+        let (messages, _) = crate::operations::find_messages(
+            action.app.as_ref(),
+            input_data.topic_id.as_str(),
+            &filter,
+            JSON_PATH_SCAN_TIMEOUT,
+        )
+        .await?;
+
+        let model = GetMessagesResponseModel::create(messages.iter(), format);
+
+        return HttpOutput::as_json(model).into_ok_result(true).into();
+    }
+
     let messages = crate::operations::get_messages_from_date(
         action.app.as_ref(),
         input_data.topic_id.as_str(),
-        DateTimeAsMicroseconds::parse_iso_string(input_data.from_date.as_str()).unwrap(),
-        input_data.max_amount,
+        from_date,
+        max_amount,
     )
     .await?;
 
-    let model = GetMessagesResponseModel::create(messages.iter());
+    let model = GetMessagesResponseModel::create(messages.iter(), format);
 
     HttpOutput::as_json(model).into_ok_result(true).into()
 }
@@ -0,0 +1,85 @@
+use my_http_server::HttpFailResult;
+use rust_extensions::base64::IntoBase64;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageContentFormat {
+    Base64,
+    Utf8,
+    Json,
+    Hex,
+}
+
+impl MessageContentFormat {
+    pub fn parse(src: &str) -> Result<Self, HttpFailResult> {
+        match src {
+            "base64" => Ok(Self::Base64),
+            "utf8" => Ok(Self::Utf8),
+            "json" => Ok(Self::Json),
+            "hex" => Ok(Self::Hex),
+            _ => Err(HttpFailResult::as_validation_error(format!(
+                "Invalid format {}. Supported formats: base64, utf8, json, hex",
+                src
+            ))),
+        }
+    }
+
+    // Format is optional in query. Base64 is the default
+    pub fn from_query(src: Option<&str>) -> Result<Self, HttpFailResult> {
+        match src {
+            Some(src) => Self::parse(src),
+            None => Ok(Self::Base64),
+        }
+    }
+
+    // Json content is validated and rendered as compact json text.
+    // Content which is not valid json is rendered as utf8 string
+    pub fn render(&self, data: &[u8]) -> String {
+        match self {
+            Self::Base64 => data.into_base64(),
+            Self::Utf8 => String::from_utf8_lossy(data).to_string(),
+            Self::Json => match serde_json::from_slice::<Value>(data) {
+                Ok(value) => value.to_string(),
+                Err(_) => String::from_utf8_lossy(data).to_string(),
+            },
+            Self::Hex => {
+                let mut result = String::with_capacity(data.len() * 2);
+
+                for b in data {
+                    result.push_str(format!("{:02x}", b).as_str());
+                }
+
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MessageContentFormat;
+
+    #[test]
+    fn test_render() {
+        let data = br#"{"accountId":"123"}"#;
+
+        assert_eq!(
+            MessageContentFormat::Utf8.render(data),
+            r#"{"accountId":"123"}"#
+        );
+
+        assert_eq!(
+            MessageContentFormat::Json.render(br#"{ "accountId": "123" }"#),
+            r#"{"accountId":"123"}"#
+        );
+
+        assert_eq!(MessageContentFormat::Json.render(b"not json"), "not json");
+
+        assert_eq!(
+            MessageContentFormat::Hex.render(&[0x00, 0xab, 0x10]),
+            "00ab10"
+        );
+
+        assert!(MessageContentFormat::parse("xml").is_err());
+    }
+}
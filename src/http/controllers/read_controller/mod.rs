@@ -1,5 +1,15 @@
 mod by_id_action;
 mod contracts;
+mod download_action;
+mod export_writer;
 mod list_from_date_action;
+mod message_content_format;
+mod range_action;
+mod search_action;
 pub use by_id_action::*;
+pub use download_action::DownloadAction;
+pub use export_writer::*;
 pub use list_from_date_action::ListFromDateAction;
+pub use message_content_format::*;
+pub use range_action::RangeAction;
+pub use search_action::{parse_date_time, parse_json_path, SearchAction};
@@ -0,0 +1,107 @@
+use std::{sync::Arc, time::Duration};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use my_service_bus::abstractions::MessageId;
+
+use crate::{app::AppContext, operations::SearchMessagesFilter};
+
+use super::{contracts::*, parse_json_path, MessageContentFormat};
+
+const RANGE_SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[my_http_server::macros::http_route(
+    method:"GET",
+    route:"/Read/Range",
+    controller:"Read",
+    description:"Reads messages within the range of message ids page by page",
+    summary:"Read range of messages",
+    input_data:"GetMessagesRangeInputContract",
+    result:[
+        {status_code: 200, description: "Page of messages and cursor of the next page", model:"GetMessagesRangeResponseModel"},
+        {status_code: 400, description: "Invalid range, cursor, format or json path filter"},
+        {status_code: 404, description: "Topic not found"},
+    ]
+)]
+pub struct RangeAction {
+    app: Arc<AppContext>,
+}
+
+impl RangeAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &RangeAction,
+    input_data: GetMessagesRangeInputContract,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    if input_data.from < 0 || input_data.from > input_data.to {
+        return Err(HttpFailResult::as_validation_error(format!(
+            "Invalid range {}-{}",
+            input_data.from, input_data.to
+        )));
+    }
+
+    let format = MessageContentFormat::from_query(input_data.format.as_deref())?;
+
+    let from = match input_data.cursor.as_deref() {
+        Some(cursor) => parse_cursor(cursor, input_data.from, input_data.to)?,
+        None => input_data.from,
+    };
+
+    let limit = input_data
+        .limit
+        .max(1)
+        .min(action.app.settings.max_response_records_amount);
+
+    let filter = SearchMessagesFilter {
+        from_message_id: Some(MessageId::new(from)),
+        to_message_id: Some(MessageId::new(input_data.to)),
+        from_date: None,
+        to_date: None,
+        predicates: vec![],
+        json_path: parse_json_path(input_data.json_path.as_deref())?,
+        limit,
+    };
+
+    let (messages, result) = crate::operations::find_messages(
+        action.app.as_ref(),
+        input_data.topic_id.as_str(),
+        &filter,
+        RANGE_SCAN_TIMEOUT,
+    )
+    .await?;
+
+    // Page is full or scan is timed out - the rest is read from the message after the last scanned one.
+    // Scan can time out before anything matches, so the cursor moves forward anyway
+    let next_cursor = if messages.len() >= limit || result.cancelled {
+        let next_message_id = result
+            .last_scanned_message_id
+            .map(|itm| itm.get_value() + 1)
+            .unwrap_or(from);
+
+        if next_message_id <= input_data.to {
+            Some(next_message_id.to_string())
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let model = GetMessagesRangeResponseModel::create(messages.iter(), format, next_cursor);
+
+    HttpOutput::as_json(model).into_ok_result(true).into()
+}
+
+fn parse_cursor(cursor: &str, from: i64, to: i64) -> Result<i64, HttpFailResult> {
+    match cursor.parse::<i64>() {
+        Ok(result) if result >= from && result <= to => Ok(result),
+        _ => Err(HttpFailResult::as_validation_error(format!(
+            "Invalid cursor {}",
+            cursor
+        ))),
+    }
+}
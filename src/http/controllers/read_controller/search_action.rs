@@ -0,0 +1,125 @@
+use std::{sync::Arc, time::Duration};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use my_service_bus::abstractions::MessageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    json_path::JsonPathFilter,
+    operations::{HeaderPredicate, SearchMessagesFilter},
+};
+
+use super::{contracts::*, MessageContentFormat};
+
+#[my_http_server::macros::http_route(
+    method:"GET",
+    route:"/Read/Search",
+    controller:"Read",
+    description:"Searches messages by headers within the range",
+    summary:"Search messages by headers",
+    input_data:"SearchMessagesInputContract",
+    result:[
+        {status_code: 200, description: "Found messages", model:"SearchMessagesResponseModel"},
+        {status_code: 400, description: "Invalid date time, header predicate, json path filter or format"},
+        {status_code: 404, description: "Topic not found"},
+    ]
+)]
+pub struct SearchAction {
+    app: Arc<AppContext>,
+}
+
+impl SearchAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SearchAction,
+    input_data: SearchMessagesInputContract,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let format = MessageContentFormat::from_query(input_data.format.as_deref())?;
+
+    let filter = SearchMessagesFilter {
+        from_message_id: input_data.from_message_id.map(MessageId::new),
+        to_message_id: input_data.to_message_id.map(MessageId::new),
+        from_date: parse_date_time(input_data.from_date.as_deref())?,
+        to_date: parse_date_time(input_data.to_date.as_deref())?,
+        predicates: parse_predicates(input_data.headers.as_str())?,
+        json_path: parse_json_path(input_data.json_path.as_deref())?,
+        limit: input_data.limit,
+    };
+
+    let (messages, result) = crate::operations::find_messages(
+        action.app.as_ref(),
+        input_data.topic_id.as_str(),
+        &filter,
+        Duration::from_secs(input_data.timeout_sec),
+    )
+    .await?;
+
+    let model = SearchMessagesResponseModel::create(
+        messages.iter(),
+        format,
+        result.scanned_sub_pages,
+        result.cancelled,
+    );
+
+    HttpOutput::as_json(model).into_ok_result(true).into()
+}
+
+fn parse_predicates(src: &str) -> Result<Vec<HeaderPredicate>, HttpFailResult> {
+    let mut result = Vec::new();
+
+    for predicate in src.split(';') {
+        if predicate.trim().is_empty() {
+            continue;
+        }
+
+        match HeaderPredicate::parse(predicate) {
+            Some(predicate) => result.push(predicate),
+            None => {
+                return Err(HttpFailResult::as_validation_error(format!(
+                    "Invalid header predicate {}",
+                    predicate
+                )))
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn parse_json_path(src: Option<&str>) -> Result<Option<JsonPathFilter>, HttpFailResult> {
+    let src = match src {
+        Some(src) => src,
+        None => return Ok(None),
+    };
+
+    match JsonPathFilter::parse(src) {
+        Ok(result) => Ok(Some(result)),
+        Err(err) => Err(HttpFailResult::as_validation_error(format!(
+            "Invalid json path filter. {}",
+            err
+        ))),
+    }
+}
+
+pub fn parse_date_time(
+    src: Option<&str>,
+) -> Result<Option<DateTimeAsMicroseconds>, HttpFailResult> {
+    let src = match src {
+        Some(src) => src,
+        None => return Ok(None),
+    };
+
+    match DateTimeAsMicroseconds::parse_iso_string(src) {
+        Some(result) => Ok(Some(result)),
+        None => Err(HttpFailResult::as_validation_error(format!(
+            "Invalid date time {}",
+            src
+        ))),
+    }
+}
@@ -0,0 +1,73 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use serde::*;
+
+use crate::app::AppContext;
+
+#[derive(MyHttpInput)]
+pub struct PromoteReplicaHttpInput {
+    #[http_query(name = "apiKey"; description="Api Key")]
+    pub api_key: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct ReplicationStatusHttpModel {
+    // primary, secondary or promoted
+    pub role: String,
+    #[serde(rename = "sessionId")]
+    pub session_id: Option<String>,
+    #[serde(rename = "lastSequence")]
+    pub last_sequence: Option<i64>,
+    pub subscribers: Option<usize>,
+    #[serde(rename = "primaryUrl")]
+    pub primary_url: Option<String>,
+    pub connected: Option<bool>,
+    #[serde(rename = "inSync")]
+    pub in_sync: Option<bool>,
+    #[serde(rename = "appliedSequence")]
+    pub applied_sequence: Option<i64>,
+    #[serde(rename = "lagEvents")]
+    pub lag_events: Option<i64>,
+    #[serde(rename = "lagMs")]
+    pub lag_ms: Option<i64>,
+}
+
+impl ReplicationStatusHttpModel {
+    pub fn new(app: &AppContext) -> Self {
+        let mut result = Self {
+            role: "primary".to_string(),
+            session_id: None,
+            last_sequence: None,
+            subscribers: None,
+            primary_url: None,
+            connected: None,
+            in_sync: None,
+            applied_sequence: None,
+            lag_events: None,
+            lag_ms: None,
+        };
+
+        if let Some(replication_hub) = &app.replication_hub {
+            result.session_id = Some(replication_hub.get_session_id().to_string());
+            result.last_sequence = Some(replication_hub.get_last_sequence());
+            result.subscribers = Some(replication_hub.get_subscribers_amount());
+        }
+
+        if let Some(replica) = &app.replica {
+            let (_, applied_sequence) = replica.get_position();
+            let lag = replica.get_lag();
+
+            result.role = match replica.is_promoted() {
+                true => "promoted".to_string(),
+                false => "secondary".to_string(),
+            };
+            result.primary_url = Some(replica.get_primary_url().to_string());
+            result.connected = Some(replica.is_connected());
+            result.in_sync = Some(replica.is_in_sync());
+            result.applied_sequence = Some(applied_sequence);
+            result.lag_events = Some(lag.events);
+            result.lag_ms = Some(lag.duration_ms);
+        }
+
+        result
+    }
+}
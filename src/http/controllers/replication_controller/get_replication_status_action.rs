@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Replication",
+    description: "Shows replication role, position and lag of the instance",
+    summary: "Replication status",
+    controller: "Replication",
+    result:[
+        {status_code: 200, description: "Replication status", model:"ReplicationStatusHttpModel"},
+    ]
+)]
+pub struct GetReplicationStatusAction {
+    app: Arc<AppContext>,
+}
+
+impl GetReplicationStatusAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetReplicationStatusAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result = ReplicationStatusHttpModel::new(action.app.as_ref());
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
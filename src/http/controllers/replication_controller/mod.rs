@@ -0,0 +1,5 @@
+mod contracts;
+mod get_replication_status_action;
+pub use get_replication_status_action::*;
+mod promote_replica_action;
+pub use promote_replica_action::*;
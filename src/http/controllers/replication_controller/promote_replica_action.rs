@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Replication/Promote",
+    input_data: "PromoteReplicaHttpInput",
+    description: "Stops replication from the primary and makes the secondary accept writes",
+    summary: "Promote secondary",
+    controller: "Replication",
+    result:[
+        {status_code: 200, description: "Secondary is promoted"},
+        {status_code: 400, description: "Instance is not a secondary or is already promoted"},
+    ]
+)]
+pub struct PromoteReplicaAction {
+    app: Arc<AppContext>,
+}
+
+impl PromoteReplicaAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &PromoteReplicaAction,
+    input_data: PromoteReplicaHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    if action.app.settings.delete_topic_secret_key.as_str() != input_data.api_key {
+        return Err(HttpFailResult::as_unauthorized(
+            "Invalid Secret Key".to_string().into(),
+        ));
+    }
+
+    let initiator = super::super::audit::get_audit_initiator(
+        &action.app.settings,
+        ctx,
+        input_data.api_key.as_str(),
+    );
+
+    crate::operations::promote_replica(action.app.as_ref(), initiator.as_str()).await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,127 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::*;
+
+use crate::topics_snapshot::{
+    QueueSnapshotDiff, TopicSnapshotDiff, TopicsSnapshotHistoryItemProtobufModel,
+};
+
+#[derive(MyHttpInput)]
+pub struct GetSnapshotDiffHttpInput {
+    #[http_path(name = "version"; description = "Version of snapshot")]
+    pub version: i64,
+
+    #[http_query(name = "compareWith"; description = "Version to compare with. Current snapshot if empty")]
+    pub compare_with: Option<i64>,
+}
+
+#[derive(MyHttpInput)]
+pub struct RollbackSnapshotHttpInput {
+    #[http_query(name = "version"; description = "Version of snapshot to rollback to")]
+    pub version: i64,
+
+    #[http_query(name = "apiKey"; description = "Api Key")]
+    pub api_key: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct SnapshotHistoryItemHttpModel {
+    pub version: i64,
+    pub created: String,
+    #[serde(rename = "topicsAmount")]
+    pub topics_amount: usize,
+    #[serde(rename = "queuesAmount")]
+    pub queues_amount: usize,
+    #[serde(rename = "deletedTopicsAmount")]
+    pub deleted_topics_amount: usize,
+}
+
+impl SnapshotHistoryItemHttpModel {
+    pub fn new(src: &TopicsSnapshotHistoryItemProtobufModel) -> Self {
+        let (topics_amount, queues_amount, deleted_topics_amount) = match &src.snapshot {
+            Some(snapshot) => (
+                snapshot.data.len(),
+                snapshot.data.iter().map(|itm| itm.queues.len()).sum(),
+                snapshot.deleted_topics.len(),
+            ),
+            None => (0, 0, 0),
+        };
+
+        Self {
+            version: src.version,
+            created: DateTimeAsMicroseconds::new(src.created).to_rfc3339(),
+            topics_amount,
+            queues_amount,
+            deleted_topics_amount,
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct QueueRangeHttpModel {
+    #[serde(rename = "fromId")]
+    pub from_id: i64,
+    #[serde(rename = "toId")]
+    pub to_id: i64,
+}
+
+fn to_ranges(src: &Option<Vec<(i64, i64)>>) -> Option<Vec<QueueRangeHttpModel>> {
+    let src = src.as_ref()?;
+
+    Some(
+        src.iter()
+            .map(|(from_id, to_id)| QueueRangeHttpModel {
+                from_id: *from_id,
+                to_id: *to_id,
+            })
+            .collect(),
+    )
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct QueueDiffHttpModel {
+    #[serde(rename = "queueId")]
+    pub queue_id: String,
+    #[serde(rename = "rangesBefore")]
+    pub ranges_before: Option<Vec<QueueRangeHttpModel>>,
+    #[serde(rename = "rangesAfter")]
+    pub ranges_after: Option<Vec<QueueRangeHttpModel>>,
+}
+
+impl QueueDiffHttpModel {
+    pub fn new(src: &QueueSnapshotDiff) -> Self {
+        Self {
+            queue_id: src.queue_id.to_string(),
+            ranges_before: to_ranges(&src.ranges_before),
+            ranges_after: to_ranges(&src.ranges_after),
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct TopicDiffHttpModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "messageIdBefore")]
+    pub message_id_before: Option<i64>,
+    #[serde(rename = "messageIdAfter")]
+    pub message_id_after: Option<i64>,
+    pub queues: Vec<QueueDiffHttpModel>,
+}
+
+impl TopicDiffHttpModel {
+    pub fn new(src: &TopicSnapshotDiff) -> Self {
+        Self {
+            topic_id: src.topic_id.to_string(),
+            message_id_before: src.message_id_before,
+            message_id_after: src.message_id_after,
+            queues: src.queues.iter().map(QueueDiffHttpModel::new).collect(),
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct RollbackSnapshotHttpResponse {
+    #[serde(rename = "backupVersion")]
+    pub backup_version: i64,
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Snapshot/History/{version}/Diff",
+    input_data: "GetSnapshotDiffHttpInput",
+    description: "Get topics which are changed between snapshot version and current snapshot or other version",
+    summary: "Get diff of topics snapshot versions",
+    controller: "Snapshot",
+    result:[
+        {status_code: 200, description: "Changed topics", model:"Vec<TopicDiffHttpModel>"},
+        {status_code: 404, description: "Snapshot version not found"},
+    ]
+)]
+pub struct GetSnapshotDiffAction {
+    app: Arc<AppContext>,
+}
+
+impl GetSnapshotDiffAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetSnapshotDiffAction,
+    input_data: GetSnapshotDiffHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let diff = crate::operations::get_topics_snapshot_diff(
+        action.app.as_ref(),
+        input_data.version,
+        input_data.compare_with,
+    )
+    .await?;
+
+    let result: Vec<TopicDiffHttpModel> = diff.iter().map(TopicDiffHttpModel::new).collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Snapshot/History",
+    description: "Get saved versions of topics snapshot",
+    summary: "Get saved versions of topics snapshot",
+    controller: "Snapshot",
+    result:[
+        {status_code: 200, description: "Snapshot versions", model:"Vec<SnapshotHistoryItemHttpModel>"},
+    ]
+)]
+pub struct GetSnapshotHistoryAction {
+    app: Arc<AppContext>,
+}
+
+impl GetSnapshotHistoryAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetSnapshotHistoryAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result: Vec<SnapshotHistoryItemHttpModel> = action
+        .app
+        .topics_snapshot
+        .history
+        .get_list()
+        .await
+        .iter()
+        .map(|itm| SnapshotHistoryItemHttpModel::new(itm.as_ref()))
+        .collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,7 @@
+mod contracts;
+mod get_history_action;
+pub use get_history_action::*;
+mod get_diff_action;
+pub use get_diff_action::*;
+mod rollback_action;
+pub use rollback_action::*;
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Snapshot/Rollback",
+    input_data: "RollbackSnapshotHttpInput",
+    description: "Rollback topics snapshot to saved version. Current snapshot is saved as a new version",
+    summary: "Rollback topics snapshot",
+    controller: "Snapshot",
+    result:[
+        {status_code: 200, description: "Snapshot is rolled back", model:"RollbackSnapshotHttpResponse"},
+        {status_code: 401, description: "Invalid secret key or RollbackSnapshotSecretKey is not configured"},
+        {status_code: 404, description: "Snapshot version not found"},
+    ]
+)]
+pub struct RollbackSnapshotAction {
+    app: Arc<AppContext>,
+}
+
+impl RollbackSnapshotAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &RollbackSnapshotAction,
+    input_data: RollbackSnapshotHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let secret_key = match &action.app.settings.rollback_snapshot_secret_key {
+        Some(secret_key) => secret_key,
+        None => {
+            return Err(HttpFailResult::as_unauthorized(
+                "Rollback is not enabled".to_string().into(),
+            ));
+        }
+    };
+
+    if secret_key.as_str() != input_data.api_key {
+        return Err(HttpFailResult::as_unauthorized(
+            "Invalid Secret Key".to_string().into(),
+        ));
+    }
+
+    let initiator = super::super::audit::get_audit_initiator(
+        &action.app.settings,
+        ctx,
+        input_data.api_key.as_str(),
+    );
+
+    let backup_version = crate::operations::rollback_topics_snapshot(
+        action.app.as_ref(),
+        input_data.version,
+        initiator.as_str(),
+    )
+    .await?;
+
+    HttpOutput::as_json(RollbackSnapshotHttpResponse { backup_version })
+        .into_ok_result(true)
+        .into()
+}
@@ -1,4 +1,7 @@
-use my_http_server::macros::MyHttpInput;
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use serde::*;
+
+use crate::archive_storage::{ArchiveFileStats, TopicArchiveStats};
 
 #[derive(MyHttpInput)]
 pub struct DeleteTopicHttpContract {
@@ -7,4 +10,104 @@ pub struct DeleteTopicHttpContract {
 
     #[http_query(name = "apiKey"; description="Api Key")]
     pub api_key: String,
+
+    #[http_query(name = "gracePeriodSec"; description="Topic is purged after grace period. Default is DeletedTopicGracePeriodSec setting")]
+    pub grace_period_sec: Option<u64>,
+
+    #[http_query(name = "dryRun"; description="Only shows what would be deleted")]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct DeletedTopicHttpModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "messageId")]
+    pub message_id: i64,
+    #[serde(rename = "gcAfter")]
+    pub gc_after: String,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+}
+
+#[derive(MyHttpInput)]
+pub struct RestoreTopicHttpInput {
+    #[http_query(name = "topicId"; description="Id of topic")]
+    pub topic_id: String,
+
+    #[http_query(name = "apiKey"; description="Api Key")]
+    pub api_key: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct RestoredTopicHttpModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "messageId")]
+    pub message_id: i64,
+}
+
+#[derive(MyHttpInput)]
+pub struct GetTopicStatsHttpInput {
+    #[http_path(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct ArchiveFileStatsHttpModel {
+    #[serde(rename = "fileNo")]
+    pub file_no: i64,
+    #[serde(rename = "blobSize")]
+    pub blob_size: usize,
+    #[serde(rename = "populatedTocSlots")]
+    pub populated_toc_slots: usize,
+    #[serde(rename = "compressedSize")]
+    pub compressed_size: u64,
+}
+
+impl ArchiveFileStatsHttpModel {
+    pub fn new(src: &ArchiveFileStats) -> Self {
+        Self {
+            file_no: src.archive_file_no,
+            blob_size: src.blob_size,
+            populated_toc_slots: src.populated_toc_slots,
+            compressed_size: src.compressed_size,
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct TopicStatsHttpModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "archiveFiles")]
+    pub archive_files: Vec<ArchiveFileStatsHttpModel>,
+    #[serde(rename = "firstMessageId")]
+    pub first_message_id: Option<i64>,
+    #[serde(rename = "lastMessageId")]
+    pub last_message_id: Option<i64>,
+    #[serde(rename = "firstMessageCreated")]
+    pub first_message_created: Option<String>,
+    #[serde(rename = "lastMessageCreated")]
+    pub last_message_created: Option<String>,
+    #[serde(rename = "compressionRatio")]
+    pub compression_ratio: Option<f64>,
+}
+
+impl TopicStatsHttpModel {
+    pub fn new(topic_id: String, src: &TopicArchiveStats) -> Self {
+        Self {
+            topic_id,
+            archive_files: src
+                .files
+                .values()
+                .map(ArchiveFileStatsHttpModel::new)
+                .collect(),
+            first_message_id: src.first_message.map(|itm| itm.message_id.get_value()),
+            last_message_id: src.last_message.map(|itm| itm.message_id.get_value()),
+            first_message_created: src.first_message.map(|itm| itm.created.to_rfc3339()),
+            last_message_created: src.last_message.map(|itm| itm.created.to_rfc3339()),
+            compression_ratio: src.get_compression_ratio(),
+        }
+    }
 }
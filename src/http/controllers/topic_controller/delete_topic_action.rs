@@ -1,6 +1,7 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::app::AppContext;
 
@@ -10,11 +11,12 @@ use super::contracts::*;
     method: "DELETE",
     route: "/api/Topic",
     input_data: "DeleteTopicHttpContract",
-    description: "Deletes Topic",
+    description: "Deletes Topic. Topic is purged after grace period and can be restored until then",
     summary: "Delete Topic",
     controller: "Topic",
     result:[
-        {status_code: 202, description: "Topic is deleted"},
+        {status_code: 200, description: "Topic is deleted", model:"DeletedTopicHttpModel"},
+        {status_code: 400, description: "Grace period is too long"},
         {status_code: 404, description: "Topic not found"},
     ]
 )]
@@ -31,7 +33,7 @@ impl DeleteTopicAction {
 async fn handle_request(
     action: &DeleteTopicAction,
     input_data: DeleteTopicHttpContract,
-    _ctx: &HttpContext,
+    ctx: &HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
     if action.app.settings.delete_topic_secret_key.as_str() != input_data.api_key {
         return Err(HttpFailResult::as_unauthorized(
@@ -39,30 +41,46 @@ async fn handle_request(
         ));
     }
 
-    let mut topics_snapshot = action.app.topics_snapshot.get().await;
+    let initiator = super::super::audit::get_audit_initiator(
+        &action.app.settings,
+        ctx,
+        input_data.api_key.as_str(),
+    );
 
-    let index = topics_snapshot
-        .snapshot
-        .data
-        .iter()
-        .position(|topic| topic.topic_id.as_str() == input_data.topic_id);
+    let grace_period = match input_data.grace_period_sec {
+        Some(grace_period_sec) => Duration::from_secs(grace_period_sec),
+        None => action.app.settings.get_deleted_topic_grace_period(),
+    };
+    let gc_after = i64::try_from(grace_period.as_micros())
+        .ok()
+        .and_then(|micros| {
+            DateTimeAsMicroseconds::now()
+                .unix_microseconds
+                .checked_add(micros)
+        })
+        .map(DateTimeAsMicroseconds::new)
+        .ok_or_else(|| {
+            HttpFailResult::as_validation_error(format!(
+                "Grace period {}s is too long",
+                grace_period.as_secs()
+            ))
+        })?;
 
-    if index.is_none() {
-        return Err(HttpFailResult::as_not_found(
-            format!("Topic {} not found", input_data.topic_id),
-            true,
-        ));
-    }
-
-    let index = index.unwrap();
-
-    topics_snapshot.snapshot.data.remove(index);
+    let result = crate::operations::delete_topic(
+        action.app.as_ref(),
+        input_data.topic_id.as_str(),
+        gc_after,
+        input_data.dry_run.unwrap_or(false),
+        initiator.as_str(),
+    )
+    .await?;
 
-    action
-        .app
-        .topics_snapshot
-        .update(topics_snapshot.snapshot.data)
-        .await;
+    let model = DeletedTopicHttpModel {
+        topic_id: result.topic_id,
+        message_id: result.message_id.get_value(),
+        gc_after: result.gc_after.to_rfc3339(),
+        dry_run: result.dry_run,
+    };
 
-    return HttpOutput::Empty.into_ok_result(true).into();
+    HttpOutput::as_json(model).into_ok_result(true).into()
 }
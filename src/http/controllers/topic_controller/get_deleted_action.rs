@@ -37,7 +37,7 @@ async fn handle_request(
 
     {
         let topics_snapshot = action.app.topics_snapshot.get().await;
-        for deleted in &topics_snapshot.snapshot.deleted_topics {
+        for deleted in &topics_snapshot.deleted_topics {
             result.push(DeletedTopic {
                 topic_id: deleted.topic_id.to_string(),
                 gc_after: DateTimeAsMicroseconds::new(deleted.gc_after).to_rfc3339(),
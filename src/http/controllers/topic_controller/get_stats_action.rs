@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Topic/{topicId}/stats",
+    input_data: "GetTopicStatsHttpInput",
+    description: "Get archive statistics of topic",
+    summary: "Get archive statistics of topic",
+    controller: "Topic",
+    result:[
+        {status_code: 200, description: "Archive statistics", model:"TopicStatsHttpModel"},
+        {status_code: 404, description: "Topic not found"},
+    ]
+)]
+pub struct GetTopicStatsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetTopicStatsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetTopicStatsAction,
+    input_data: GetTopicStatsHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let stats =
+        crate::operations::get_archive_stats(action.app.as_ref(), input_data.topic_id.as_str())
+            .await?;
+
+    let model = TopicStatsHttpModel::new(input_data.topic_id, &stats);
+
+    HttpOutput::as_json(model).into_ok_result(true).into()
+}
@@ -3,3 +3,7 @@ mod delete_topic_action;
 pub use delete_topic_action::*;
 mod get_deleted_action;
 pub use get_deleted_action::*;
+mod get_stats_action;
+pub use get_stats_action::*;
+mod restore_topic_action;
+pub use restore_topic_action::*;
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topic/Restore",
+    input_data: "RestoreTopicHttpInput",
+    description: "Restores deleted topic",
+    summary: "Restore deleted topic",
+    controller: "Topic",
+    result:[
+        {status_code: 200, description: "Topic is restored", model:"RestoredTopicHttpModel"},
+        {status_code: 404, description: "Deleted topic not found"},
+    ]
+)]
+pub struct RestoreTopicAction {
+    app: Arc<AppContext>,
+}
+
+impl RestoreTopicAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &RestoreTopicAction,
+    input_data: RestoreTopicHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    if action.app.settings.delete_topic_secret_key.as_str() != input_data.api_key {
+        return Err(HttpFailResult::as_unauthorized(
+            "Invalid Secret Key".to_string().into(),
+        ));
+    }
+
+    let initiator = super::super::audit::get_audit_initiator(
+        &action.app.settings,
+        ctx,
+        input_data.api_key.as_str(),
+    );
+
+    let restored = crate::operations::restore_topic(
+        action.app.as_ref(),
+        input_data.topic_id.as_str(),
+        initiator.as_str(),
+    )
+    .await?;
+
+    let model = RestoredTopicHttpModel {
+        topic_id: restored.topic_id,
+        message_id: restored.message_id,
+    };
+
+    HttpOutput::as_json(model).into_ok_result(true).into()
+}
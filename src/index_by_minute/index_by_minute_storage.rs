@@ -1,17 +1,30 @@
-use std::time::Duration;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
 
 use my_azure_page_blob_ext::MyAzurePageBlobStorageWithRetries;
 use my_azure_page_blob_random_access::PageBlobRandomAccess;
-use my_azure_storage_sdk::{page_blob::AzurePageBlobStorage, AzureStorageError};
+use my_azure_storage_sdk::{
+    page_blob::{consts::BLOB_PAGE_SIZE, AzurePageBlobStorage},
+    AzureStorageError,
+};
 use my_service_bus::abstractions::MessageId;
+use tokio::sync::Mutex;
 
 use super::{
-    utils::{MINUTE_INDEX_FILE_SIZE, MINUTE_INDEX_PAGES_AMOUNT},
+    utils::{INDEX_STEP, MINUTE_INDEX_FILE_SIZE, MINUTE_INDEX_PAGES_AMOUNT},
     MinuteWithinYear,
 };
 
+// Amount of minutes read at once when the index is searched for the next written minute
+const MINUTES_PER_READ: usize = 4096;
+
 pub struct IndexByMinutePageBlob {
     page_blob: PageBlobRandomAccess<MyAzurePageBlobStorageWithRetries>,
+    // Local copy of the last written 512-byte pages. Minutes grow sequentially,
+    // so the next flush usually lands on the same page and we do not have to read it again.
+    cached_pages: Mutex<BTreeMap<usize, Vec<u8>>>,
 }
 
 impl IndexByMinutePageBlob {
@@ -20,6 +33,7 @@ impl IndexByMinutePageBlob {
             MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(3));
         Self {
             page_blob: PageBlobRandomAccess::new(page_blob, true, MINUTE_INDEX_PAGES_AMOUNT),
+            cached_pages: Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -69,30 +83,125 @@ impl IndexByMinutePageBlob {
                 .unwrap();
         }
     }
+
+    // Overwrites the minute even if it is already written
     pub async fn write_message_id_to_minute_index(
         &self,
         minute: MinuteWithinYear,
         message_id: MessageId,
     ) {
-        let message_id = message_id.get_value() as i64;
+        self.write_to_minute_index(&[(minute, message_id)], true)
+            .await;
+    }
 
-        let payload = message_id.to_le_bytes();
-        let position_in_file = minute.get_position_in_file();
-        self.page_blob
-            .write(position_in_file, payload.as_slice())
-            .await
-            .unwrap();
+    // Writes only minutes which are not written yet, so the first message of the minute is kept
+    #[tracing::instrument(skip_all, fields(items = items.len()))]
+    pub async fn write_missing_message_ids_to_minute_index(
+        &self,
+        items: &[(MinuteWithinYear, MessageId)],
+    ) {
+        self.write_to_minute_index(items, false).await;
     }
 
+    // Dirty pages are uploaded as contiguous ranges
+    async fn write_to_minute_index(
+        &self,
+        items: &[(MinuteWithinYear, MessageId)],
+        overwrite: bool,
+    ) {
+        if items.is_empty() {
+            return;
+        }
+
+        let mut cached_pages = self.cached_pages.lock().await;
+
+        let pages_to_load: BTreeSet<usize> = items
+            .iter()
+            .map(|(minute, _)| get_page_no(*minute))
+            .filter(|page_no| !cached_pages.contains_key(page_no))
+            .collect();
+
+        for (first_page_no, pages_amount) in group_contiguous_pages(pages_to_load.into_iter()) {
+            let payload = self
+                .page_blob
+                .read(
+                    first_page_no * BLOB_PAGE_SIZE,
+                    pages_amount * BLOB_PAGE_SIZE,
+                )
+                .await
+                .unwrap();
+
+            for (i, page) in payload.as_slice().chunks(BLOB_PAGE_SIZE).enumerate() {
+                cached_pages.insert(first_page_no + i, page.to_vec());
+            }
+        }
+
+        let mut dirty_pages = BTreeSet::new();
+
+        for (minute, message_id) in items {
+            let page_no = get_page_no(*minute);
+            let page = cached_pages.get_mut(&page_no).unwrap();
+
+            let offset = minute.get_position_in_file() - page_no * BLOB_PAGE_SIZE;
+
+            if !overwrite && read_i64(&page[offset..offset + INDEX_STEP]) != 0 {
+                continue;
+            }
+
+            let message_id = message_id.get_value() as i64;
+
+            page[offset..offset + INDEX_STEP].copy_from_slice(message_id.to_le_bytes().as_slice());
+
+            dirty_pages.insert(page_no);
+        }
+
+        let last_page_no = dirty_pages.iter().last().copied();
+
+        for (first_page_no, pages_amount) in group_contiguous_pages(dirty_pages.into_iter()) {
+            let mut payload = Vec::with_capacity(pages_amount * BLOB_PAGE_SIZE);
+
+            for page_no in first_page_no..first_page_no + pages_amount {
+                payload.extend_from_slice(cached_pages.get(&page_no).unwrap());
+            }
+
+            self.page_blob
+                .write(first_page_no * BLOB_PAGE_SIZE, payload.as_slice())
+                .await
+                .unwrap();
+        }
+
+        if let Some(last_page_no) = last_page_no {
+            cached_pages.retain(|page_no, _| *page_no == last_page_no);
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(minute = minute.get_value()))]
     pub async fn read_message_id_from_minute_index(
         &self,
         minute: MinuteWithinYear,
     ) -> Option<MessageId> {
         let position_in_file = minute.get_position_in_file();
 
-        let mut payload = self.page_blob.read(position_in_file, 8).await.unwrap();
+        let result = {
+            let cached_pages = self.cached_pages.lock().await;
+            let page_no = get_page_no(minute);
+
+            match cached_pages.get(&page_no) {
+                Some(page) => {
+                    let offset = position_in_file - page_no * BLOB_PAGE_SIZE;
+                    Some(read_i64(&page[offset..offset + INDEX_STEP]))
+                }
+                None => None,
+            }
+        };
 
-        let result = payload.read_i64();
+        let result = match result {
+            Some(result) => result,
+            None => {
+                let mut payload = self.page_blob.read(position_in_file, 8).await.unwrap();
+                payload.read_i64()
+            }
+        };
 
         if result == 0 {
             return None;
@@ -100,6 +209,59 @@ impl IndexByMinutePageBlob {
 
         Some(MessageId::new(result))
     }
+
+    // Message id of the first written minute starting from the minute. Pages are written through,
+    // so the blob has everything the cached pages have
+    #[tracing::instrument(skip_all, fields(minute = minute.get_value()))]
+    pub async fn find_first_message_id_from(&self, minute: MinuteWithinYear) -> Option<MessageId> {
+        let mut position = minute.get_position_in_file();
+
+        while position < MINUTE_INDEX_FILE_SIZE {
+            let len = (MINUTES_PER_READ * INDEX_STEP).min(MINUTE_INDEX_FILE_SIZE - position);
+
+            let payload = self.page_blob.read(position, len).await.unwrap();
+
+            for item in payload.as_slice().chunks(INDEX_STEP) {
+                let message_id = read_i64(item);
+
+                if message_id != 0 {
+                    return Some(MessageId::new(message_id));
+                }
+            }
+
+            position += len;
+        }
+
+        None
+    }
+}
+
+fn get_page_no(minute: MinuteWithinYear) -> usize {
+    minute.get_position_in_file() / BLOB_PAGE_SIZE
+}
+
+fn read_i64(src: &[u8]) -> i64 {
+    let mut result = [0u8; 8];
+    result.copy_from_slice(src);
+    i64::from_le_bytes(result)
+}
+
+// Returns (first_page_no, pages_amount) for each contiguous range of sorted page numbers
+fn group_contiguous_pages(pages: impl Iterator<Item = usize>) -> Vec<(usize, usize)> {
+    let mut result: Vec<(usize, usize)> = Vec::new();
+
+    for page_no in pages {
+        if let Some((first_page_no, pages_amount)) = result.last_mut() {
+            if *first_page_no + *pages_amount == page_no {
+                *pages_amount += 1;
+                continue;
+            }
+        }
+
+        result.push((page_no, 1));
+    }
+
+    result
 }
 
 #[cfg(test)]
@@ -108,7 +270,7 @@ mod tests {
 
     use crate::index_by_minute::MinuteWithinYear;
 
-    use super::IndexByMinutePageBlob;
+    use super::{group_contiguous_pages, IndexByMinutePageBlob};
     use my_azure_storage_sdk::{page_blob::AzurePageBlobStorage, AzureStorageConnection};
     use my_service_bus::abstractions::MessageId;
 
@@ -184,4 +346,124 @@ mod tests {
             [123u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8]
         )
     }
+
+    #[tokio::test]
+    async fn test_find_first_message_id_from() {
+        let connection = AzureStorageConnection::new_in_memory();
+        let page_blob =
+            AzurePageBlobStorage::new(Arc::new(connection), "test".to_string(), "test".to_string())
+                .await;
+
+        page_blob.create_container_if_not_exists().await.unwrap();
+
+        let page_blob = IndexByMinutePageBlob::new(page_blob);
+
+        page_blob.init_index_by_minute().await;
+
+        page_blob
+            .write_missing_message_ids_to_minute_index(&[
+                (MinuteWithinYear::new(15), MessageId::new(10)),
+                (MinuteWithinYear::new(10_000), MessageId::new(20)),
+            ])
+            .await;
+
+        let result = page_blob
+            .find_first_message_id_from(MinuteWithinYear::new(15))
+            .await;
+        assert_eq!(result.unwrap(), MessageId::new(10));
+
+        // Minutes without messages are skipped, even if they are in other reads
+        let result = page_blob
+            .find_first_message_id_from(MinuteWithinYear::new(16))
+            .await;
+        assert_eq!(result.unwrap(), MessageId::new(20));
+
+        let result = page_blob
+            .find_first_message_id_from(MinuteWithinYear::new(10_001))
+            .await;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_group_contiguous_pages() {
+        let result = group_contiguous_pages(vec![1, 2, 3, 5, 7, 8].into_iter());
+
+        assert_eq!(result, vec![(1, 3), (5, 1), (7, 2)]);
+
+        let result = group_contiguous_pages(vec![].into_iter());
+
+        assert_eq!(result.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_write_across_pages_skips_already_written() {
+        let connection = AzureStorageConnection::new_in_memory();
+        let page_blob =
+            AzurePageBlobStorage::new(Arc::new(connection), "test".to_string(), "test".to_string())
+                .await;
+
+        page_blob.create_container_if_not_exists().await.unwrap();
+
+        let page_blob = IndexByMinutePageBlob::new(page_blob);
+
+        page_blob.init_index_by_minute().await;
+
+        page_blob
+            .write_message_id_to_minute_index(MinuteWithinYear::new(63), MessageId::new(10))
+            .await;
+
+        page_blob
+            .write_missing_message_ids_to_minute_index(&[
+                (MinuteWithinYear::new(63), MessageId::new(20)),
+                (MinuteWithinYear::new(64), MessageId::new(30)),
+                (MinuteWithinYear::new(200), MessageId::new(40)),
+            ])
+            .await;
+
+        let blob_payload = page_blob.get_page_blob().download().await.unwrap();
+
+        assert_eq!(&blob_payload[63 * 8..63 * 8 + 8], 10i64.to_le_bytes());
+        assert_eq!(&blob_payload[64 * 8..64 * 8 + 8], 30i64.to_le_bytes());
+        assert_eq!(&blob_payload[200 * 8..200 * 8 + 8], 40i64.to_le_bytes());
+
+        let result = page_blob
+            .read_message_id_from_minute_index(MinuteWithinYear::new(63))
+            .await;
+
+        assert_eq!(result.unwrap(), MessageId::new(10));
+
+        let result = page_blob
+            .read_message_id_from_minute_index(MinuteWithinYear::new(64))
+            .await;
+
+        assert_eq!(result.unwrap(), MessageId::new(30));
+    }
+
+    #[tokio::test]
+    async fn test_single_write_overwrites_minute() {
+        let connection = AzureStorageConnection::new_in_memory();
+        let page_blob =
+            AzurePageBlobStorage::new(Arc::new(connection), "test".to_string(), "test".to_string())
+                .await;
+
+        page_blob.create_container_if_not_exists().await.unwrap();
+
+        let page_blob = IndexByMinutePageBlob::new(page_blob);
+
+        page_blob.init_index_by_minute().await;
+
+        let minute = MinuteWithinYear::new(15);
+
+        page_blob
+            .write_message_id_to_minute_index(minute, MessageId::new(10))
+            .await;
+
+        page_blob
+            .write_message_id_to_minute_index(minute, MessageId::new(20))
+            .await;
+
+        let result = page_blob.read_message_id_from_minute_index(minute).await;
+
+        assert_eq!(result.unwrap(), MessageId::new(20));
+    }
 }
@@ -47,6 +47,14 @@ impl UpdateQueue {
         Some(result.message_id)
     }
 
+    pub async fn get_first_from(&self, minute_within_year: MinuteWithinYear) -> Option<MessageId> {
+        let read_access = self.data.lock().await;
+        read_access
+            .iter()
+            .find(|itm| itm.minute_within_year >= minute_within_year)
+            .map(|itm| itm.message_id)
+    }
+
     pub async fn get_items_ready_to_be_gc(&self) -> Option<Vec<MinuteWithinYear>> {
         let read_access = self.data.lock().await;
         if read_access.len() <= 1 {
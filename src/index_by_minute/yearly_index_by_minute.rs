@@ -68,6 +68,35 @@ impl YearlyIndexByMinute {
             .await
     }
 
+    // Minutes without messages are skipped. Queued minutes are not in the blob yet
+    pub async fn find_message_id_from(
+        &self,
+        minute_within_year: MinuteWithinYear,
+    ) -> Option<MessageId> {
+        if let Some(result) = self.get_message_id(minute_within_year).await {
+            return Some(result);
+        }
+
+        let queued = self.update_queue.get_first_from(minute_within_year).await;
+
+        let stored = self
+            .page_blob
+            .find_first_message_id_from(minute_within_year)
+            .await;
+
+        match (queued, stored) {
+            (Some(queued), Some(stored)) => {
+                if queued.get_value() < stored.get_value() {
+                    Some(queued)
+                } else {
+                    Some(stored)
+                }
+            }
+            (queued, None) => queued,
+            (None, stored) => stored,
+        }
+    }
+
     pub async fn flush_to_storage(&self) {
         let items_to_write = self.update_queue.get_items_ready_to_be_gc().await;
 
@@ -75,34 +104,28 @@ impl YearlyIndexByMinute {
             return;
         }
 
-        for minute in items_to_write.unwrap() {
-            let minute = (minute).into();
+        let mut to_write = Vec::new();
 
+        for minute in items_to_write.unwrap() {
             if let Some(message_id) = self.update_queue.remove(minute).await {
-                self.write_to_blob(minute, message_id).await;
+                to_write.push((minute, message_id));
             }
         }
-    }
 
-    pub async fn write_everything_before_gc(&self) {
-        while let Some(item) = self.update_queue.remove_first_element().await {
-            self.write_to_blob(item.minute_within_year, item.message_id)
-                .await;
-        }
+        self.page_blob
+            .write_missing_message_ids_to_minute_index(to_write.as_slice())
+            .await;
     }
 
-    async fn write_to_blob(&self, minute: MinuteWithinYear, message_id: MessageId) {
-        let value = self
-            .page_blob
-            .read_message_id_from_minute_index(minute)
-            .await;
+    pub async fn write_everything_before_gc(&self) {
+        let mut to_write = Vec::new();
 
-        if value.is_some() {
-            return;
+        while let Some(item) = self.update_queue.remove_first_element().await {
+            to_write.push((item.minute_within_year, item.message_id));
         }
 
         self.page_blob
-            .write_message_id_to_minute_index(minute, message_id)
+            .write_missing_message_ids_to_minute_index(to_write.as_slice())
             .await;
     }
 }
@@ -0,0 +1,245 @@
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOperator {
+    Equals,
+    NotEquals,
+    Greater,
+    GreaterOrEquals,
+    Less,
+    LessOrEquals,
+}
+
+// Operators with two chars go first, so ">=" is not parsed as ">"
+const OPERATORS: [(&str, CompareOperator); 6] = [
+    ("==", CompareOperator::Equals),
+    ("!=", CompareOperator::NotEquals),
+    (">=", CompareOperator::GreaterOrEquals),
+    ("<=", CompareOperator::LessOrEquals),
+    (">", CompareOperator::Greater),
+    ("<", CompareOperator::Less),
+];
+
+// Supported syntax: $.field.items[0]['other field'] <op> <json literal>
+// where op is one of == != > >= < <=. Without operator it checks that the path exists
+#[derive(Debug, Clone)]
+pub struct JsonPathFilter {
+    path: Vec<PathSegment>,
+    condition: Option<(CompareOperator, Value)>,
+}
+
+impl JsonPathFilter {
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let src = src.trim();
+
+        let (path, condition) = match find_operator(src) {
+            Some((index, operator, operator_str)) => {
+                let literal = src[index + operator_str.len()..].trim();
+
+                let value: Value = serde_json::from_str(literal)
+                    .map_err(|_| format!("Invalid literal {}", literal))?;
+
+                (&src[..index], Some((operator, value)))
+            }
+            None => (src, None),
+        };
+
+        Ok(Self {
+            path: parse_path(path.trim())?,
+            condition,
+        })
+    }
+
+    // Payloads which are not valid JSON never match
+    pub fn is_matching(&self, payload: &[u8]) -> bool {
+        let json: Value = match serde_json::from_slice(payload) {
+            Ok(json) => json,
+            Err(_) => return false,
+        };
+
+        let mut current = &json;
+
+        for segment in &self.path {
+            let next = match segment {
+                PathSegment::Field(name) => current.get(name.as_str()),
+                PathSegment::Index(index) => current.get(*index),
+            };
+
+            current = match next {
+                Some(next) => next,
+                None => return false,
+            };
+        }
+
+        match &self.condition {
+            Some((operator, value)) => compare(current, *operator, value),
+            None => true,
+        }
+    }
+}
+
+// Operator inside a quoted field name or string literal is not an operator
+fn find_operator(src: &str) -> Option<(usize, CompareOperator, &'static str)> {
+    let mut quote: Option<char> = None;
+
+    for (index, c) in src.char_indices() {
+        if let Some(quote_char) = quote {
+            if c == quote_char {
+                quote = None;
+            }
+            continue;
+        }
+
+        if c == '\'' || c == '"' {
+            quote = Some(c);
+            continue;
+        }
+
+        for (operator_str, operator) in OPERATORS {
+            if src[index..].starts_with(operator_str) {
+                return Some((index, operator, operator_str));
+            }
+        }
+    }
+
+    None
+}
+
+fn parse_path(src: &str) -> Result<Vec<PathSegment>, String> {
+    let mut rest = src
+        .strip_prefix('$')
+        .ok_or_else(|| format!("Path {} must start with $", src))?;
+
+    let mut result = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot
+                .find(|c| c == '.' || c == '[')
+                .unwrap_or(after_dot.len());
+
+            let name = &after_dot[..end];
+
+            if name.is_empty() {
+                return Err(format!("Empty field name in path {}", src));
+            }
+
+            result.push(PathSegment::Field(name.to_string()));
+            rest = &after_dot[end..];
+            continue;
+        }
+
+        if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket
+                .find(']')
+                .ok_or_else(|| format!("Unclosed bracket in path {}", src))?;
+
+            let inner = after_bracket[..end].trim();
+
+            let segment = if inner.len() >= 2
+                && (inner.starts_with('\'') && inner.ends_with('\'')
+                    || inner.starts_with('"') && inner.ends_with('"'))
+            {
+                PathSegment::Field(inner[1..inner.len() - 1].to_string())
+            } else {
+                let index = inner
+                    .parse()
+                    .map_err(|_| format!("Invalid index {} in path {}", inner, src))?;
+                PathSegment::Index(index)
+            };
+
+            result.push(segment);
+            rest = &after_bracket[end + 1..];
+            continue;
+        }
+
+        return Err(format!("Invalid path {}", src));
+    }
+
+    Ok(result)
+}
+
+fn compare(left: &Value, operator: CompareOperator, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(left), Value::Number(right)) => match (left.as_f64(), right.as_f64()) {
+            (Some(left), Some(right)) => left.partial_cmp(&right),
+            _ => None,
+        },
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => {
+            if left == right {
+                Some(Ordering::Equal)
+            } else {
+                None
+            }
+        }
+    };
+
+    match operator {
+        CompareOperator::Equals => ordering == Some(Ordering::Equal),
+        CompareOperator::NotEquals => ordering != Some(Ordering::Equal),
+        CompareOperator::Greater => ordering == Some(Ordering::Greater),
+        CompareOperator::GreaterOrEquals => {
+            ordering == Some(Ordering::Greater) || ordering == Some(Ordering::Equal)
+        }
+        CompareOperator::Less => ordering == Some(Ordering::Less),
+        CompareOperator::LessOrEquals => {
+            ordering == Some(Ordering::Less) || ordering == Some(Ordering::Equal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonPathFilter;
+
+    const PAYLOAD: &[u8] = br#"{"accountId":"123","amount":10.5,"items":[{"sku":"a"},{"sku":"b"}],"meta data":{"vip":true}}"#;
+
+    fn is_matching(filter: &str) -> bool {
+        JsonPathFilter::parse(filter).unwrap().is_matching(PAYLOAD)
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert!(is_matching(r#"$.accountId == "123""#));
+        assert!(!is_matching(r#"$.accountId == "124""#));
+        assert!(is_matching(r#"$.accountId != "124""#));
+
+        assert!(is_matching("$.amount > 10"));
+        assert!(is_matching("$.amount >= 10.5"));
+        assert!(!is_matching("$.amount < 10"));
+        assert!(is_matching("$.amount <= 10.5"));
+
+        assert!(is_matching(r#"$.items[1].sku == "b""#));
+        assert!(is_matching("$['meta data'].vip == true"));
+    }
+
+    #[test]
+    fn test_exists() {
+        assert!(is_matching("$.items[0]"));
+        assert!(!is_matching("$.items[2]"));
+        assert!(!is_matching("$.missing"));
+    }
+
+    #[test]
+    fn test_not_json_payload_is_skipped() {
+        let filter = JsonPathFilter::parse("$.accountId").unwrap();
+        assert!(!filter.is_matching(b"not a json"));
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        assert!(JsonPathFilter::parse("accountId == 1").is_err());
+        assert!(JsonPathFilter::parse("$.accountId == abc").is_err());
+        assert!(JsonPathFilter::parse("$.items[x]").is_err());
+        assert!(JsonPathFilter::parse("$..items").is_err());
+    }
+}
@@ -0,0 +1,2 @@
+mod json_path_filter;
+pub use json_path_filter::*;
@@ -0,0 +1,192 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::file_storage::{FileStorage, FileStorageError};
+
+use super::LeaseRecordModel;
+
+const LEASE_FILE_NAME: &str = ".leader-lease";
+
+// Lease is lost locally this long before it expires for other instances, so clock drift and
+// slow writes do not make two leaders at a time
+const LEASE_SAFETY_MARGIN: Duration = Duration::from_secs(5);
+
+// Leader renews the lease file every third of the lease duration. Passive instance takes over
+// only when the lease is expired. Lease file is written only if its ETag is the one which was read
+// (acquire) or written (renew) by the instance, so two instances never hold the lease at a time
+pub struct LeaderLease {
+    storage: FileStorage,
+    instance_id: String,
+    duration: Duration,
+    epoch: AtomicI64,
+    etag: Mutex<Option<String>>,
+    // Instance is the leader until this moment (unix microseconds)
+    leader_until: AtomicI64,
+    released: AtomicBool,
+}
+
+impl LeaderLease {
+    pub fn new(storage: FileStorage, instance_id: String, duration: Duration) -> Self {
+        Self {
+            storage,
+            instance_id,
+            duration: duration.max(LEASE_SAFETY_MARGIN * 2),
+            epoch: AtomicI64::new(0),
+            etag: Mutex::new(None),
+            leader_until: AtomicI64::new(0),
+            released: AtomicBool::new(false),
+        }
+    }
+
+    pub fn get_instance_id(&self) -> &str {
+        self.instance_id.as_str()
+    }
+
+    pub fn get_storage_name(&self) -> String {
+        self.storage.get_name()
+    }
+
+    pub fn get_renew_interval(&self) -> Duration {
+        self.duration / 3
+    }
+
+    pub fn is_leader(&self) -> bool {
+        DateTimeAsMicroseconds::now().unix_microseconds < self.leader_until.load(Ordering::SeqCst)
+    }
+
+    pub fn is_released(&self) -> bool {
+        self.released.load(Ordering::SeqCst)
+    }
+
+    // Returns the record and the ETag of the lease file
+    async fn read_record(
+        &self,
+    ) -> Result<(Option<LeaseRecordModel>, Option<String>), FileStorageError> {
+        match self.storage.read_file_with_etag(LEASE_FILE_NAME).await? {
+            Some((content, etag)) => Ok((LeaseRecordModel::parse(content.as_slice()), Some(etag))),
+            None => Ok((None, None)),
+        }
+    }
+
+    // Returns the new ETag or None if the lease file is changed by another instance
+    async fn write_record(
+        &self,
+        epoch: i64,
+        expires: i64,
+        etag: Option<&str>,
+    ) -> Result<Option<String>, FileStorageError> {
+        let record = LeaseRecordModel {
+            owner: self.instance_id.to_string(),
+            epoch,
+            expires,
+        };
+
+        self.storage
+            .write_file_if_match(LEASE_FILE_NAME, record.to_vec(), etag)
+            .await
+    }
+
+    fn get_etag(&self) -> Option<String> {
+        self.etag.lock().unwrap().clone()
+    }
+
+    fn set_etag(&self, etag: Option<String>) {
+        *self.etag.lock().unwrap() = etag;
+    }
+
+    fn set_leader_until(&self, written_at: DateTimeAsMicroseconds) {
+        let leader_until =
+            written_at.unix_microseconds + (self.duration - LEASE_SAFETY_MARGIN).as_micros() as i64;
+
+        self.leader_until.store(leader_until, Ordering::SeqCst);
+    }
+
+    // Returns true if the lease is acquired
+    pub async fn try_acquire(&self) -> Result<bool, FileStorageError> {
+        let (record, etag) = self.read_record().await?;
+        let now = DateTimeAsMicroseconds::now();
+
+        if !super::can_acquire_lease(
+            record.as_ref(),
+            self.instance_id.as_str(),
+            now.unix_microseconds,
+        ) {
+            return Ok(false);
+        }
+
+        let epoch = record.map(|itm| itm.epoch).unwrap_or(0) + 1;
+        let expires = now.unix_microseconds + self.duration.as_micros() as i64;
+
+        // Another instance which read the same ETag wrote the lease first
+        let new_etag = match self.write_record(epoch, expires, etag.as_deref()).await? {
+            Some(new_etag) => new_etag,
+            None => return Ok(false),
+        };
+
+        self.set_etag(Some(new_etag));
+        self.epoch.store(epoch, Ordering::SeqCst);
+        self.set_leader_until(now);
+
+        Ok(true)
+    }
+
+    // Returns false if the lease file is changed by another instance or is not held by this one anymore.
+    // Lease stays valid until it expires if it can not be renewed because of storage errors
+    pub async fn renew(&self) -> Result<bool, FileStorageError> {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let etag = self.get_etag();
+
+        let (record, current_etag) = self.read_record().await?;
+
+        let is_held = match &record {
+            Some(record) => record.is_held_by(self.instance_id.as_str(), epoch),
+            None => false,
+        };
+
+        if !is_held || etag.is_none() || current_etag != etag {
+            self.leader_until.store(0, Ordering::SeqCst);
+            return Ok(false);
+        }
+
+        let now = DateTimeAsMicroseconds::now();
+        let expires = now.unix_microseconds + self.duration.as_micros() as i64;
+
+        match self.write_record(epoch, expires, etag.as_deref()).await? {
+            Some(new_etag) => {
+                self.set_etag(Some(new_etag));
+                self.set_leader_until(now);
+                Ok(true)
+            }
+            None => {
+                self.leader_until.store(0, Ordering::SeqCst);
+                Ok(false)
+            }
+        }
+    }
+
+    // Lease is expired on shutdown, so the passive instance takes over without waiting for it
+    pub async fn release(&self) -> Result<(), FileStorageError> {
+        self.released.store(true, Ordering::SeqCst);
+
+        if !self.is_leader() {
+            return Ok(());
+        }
+
+        self.leader_until.store(0, Ordering::SeqCst);
+
+        // Lease file changed by another instance is not released
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let etag = self.get_etag();
+        self.write_record(epoch, 0, etag.as_deref()).await?;
+        self.set_etag(None);
+
+        Ok(())
+    }
+}
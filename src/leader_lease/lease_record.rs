@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+// Content of the lease file. Epoch is increased by each acquire, so the owner notices
+// that the lease was taken over even if the same instance id is reused
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeaseRecordModel {
+    #[serde(rename = "owner")]
+    pub owner: String,
+    #[serde(rename = "epoch")]
+    pub epoch: i64,
+    #[serde(rename = "expires")]
+    pub expires: i64,
+}
+
+impl LeaseRecordModel {
+    pub fn parse(content: &[u8]) -> Option<Self> {
+        serde_json::from_slice(content).ok()
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap()
+    }
+
+    pub fn is_expired(&self, now_micros: i64) -> bool {
+        self.expires <= now_micros
+    }
+
+    pub fn is_held_by(&self, instance_id: &str, epoch: i64) -> bool {
+        self.owner == instance_id && self.epoch == epoch
+    }
+}
+
+// Corrupted lease file is treated as an expired lease
+pub fn can_acquire_lease(
+    record: Option<&LeaseRecordModel>,
+    instance_id: &str,
+    now_micros: i64,
+) -> bool {
+    match record {
+        Some(record) => record.owner == instance_id || record.is_expired(now_micros),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(owner: &str, expires: i64) -> LeaseRecordModel {
+        LeaseRecordModel {
+            owner: owner.to_string(),
+            epoch: 5,
+            expires,
+        }
+    }
+
+    #[test]
+    fn test_can_acquire() {
+        assert!(can_acquire_lease(None, "a", 100));
+        assert!(can_acquire_lease(Some(&record("a", 200)), "a", 100));
+        assert!(!can_acquire_lease(Some(&record("b", 200)), "a", 100));
+        assert!(can_acquire_lease(Some(&record("b", 100)), "a", 100));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let content = record("a", 200).to_vec();
+        let result = LeaseRecordModel::parse(content.as_slice()).unwrap();
+
+        assert!(result.is_held_by("a", 5));
+        assert!(!result.is_held_by("a", 6));
+        assert!(!result.is_held_by("b", 5));
+        assert!(LeaseRecordModel::parse(b"garbage").is_none());
+    }
+}
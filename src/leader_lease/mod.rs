@@ -0,0 +1,4 @@
+mod leader_lease;
+mod lease_record;
+pub use leader_lease::*;
+pub use lease_record::*;
@@ -2,15 +2,25 @@ use std::{sync::Arc, time::Duration};
 mod app;
 
 mod archive_storage;
+mod audit_log;
+mod backup;
+mod encryption;
+mod file_storage;
 
 //mod azure_storage_with_retries;
 mod grpc;
+mod header_index;
 mod http;
 mod index_by_minute;
+mod json_path;
+mod leader_lease;
 mod message_pages;
 mod operations;
+mod parquet_export;
+mod replication;
 
 mod settings;
+mod telemetry;
 mod timers;
 mod topic_data;
 mod topics_snapshot;
@@ -23,8 +33,11 @@ use crate::{
     app::AppContext,
     settings::SettingsModel,
     timers::{
+        archive_compaction::ArchiveCompactionTimer, header_index_merger::HeaderIndexMergerTimer,
         metrics_updater::MetricsUpdater, pages_gc::PagesGcTimer, save_min_index::SaveMinIndexTimer,
         topics_snapshot_saver::TopicsSnapshotSaverTimer,
+        zstd_dictionaries_loader::ZstdDictionariesLoaderTimer,
+        zstd_dictionaries_trainer::ZstdDictionariesTrainerTimer,
     },
 };
 #[allow(non_snake_case)]
@@ -39,10 +52,24 @@ static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 async fn main() {
     let settings = SettingsModel::read().await;
 
+    telemetry::init(&settings);
+
     let app = AppContext::new(settings).await;
 
     let app = Arc::new(app);
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.first().map(|itm| itm.as_str()) == Some(parquet_export::EXPORT_PARQUET_COMMAND) {
+        parquet_export::run_export_cli(app, &args[1..]).await;
+        return;
+    }
+
+    if args.first().map(|itm| itm.as_str()) == Some(backup::RESTORE_BACKUP_COMMAND) {
+        backup::run_restore_cli(app, &args[1..]).await;
+        return;
+    }
+
     let mut timer_3s = MyTimer::new(Duration::from_secs(3));
 
     timer_3s.register_timer(
@@ -72,10 +99,34 @@ async fn main() {
     );
     timer_1s.start(app.app_states.clone(), my_logger::LOGGER.clone());
 
-    tokio::spawn(operations::data_initializer::init(app.clone()));
+    let mut timer_1m = MyTimer::new(Duration::from_secs(60));
+    timer_1m.register_timer(
+        "HeaderIndexMerger",
+        Arc::new(HeaderIndexMergerTimer::new(app.clone())),
+    );
+    timer_1m.register_timer(
+        "ZstdDictionariesLoader",
+        Arc::new(ZstdDictionariesLoaderTimer::new(app.clone())),
+    );
+    timer_1m.start(app.app_states.clone(), my_logger::LOGGER.clone());
+
+    let mut timer_1h = MyTimer::new(Duration::from_secs(60 * 60));
+    timer_1h.register_timer(
+        "ZstdDictionariesTrainer",
+        Arc::new(ZstdDictionariesTrainerTimer::new(app.clone())),
+    );
+    timer_1h.register_timer(
+        "ArchiveCompaction",
+        Arc::new(ArchiveCompactionTimer::new(app.clone())),
+    );
+    timer_1h.start(app.app_states.clone(), my_logger::LOGGER.clone());
+
+    tokio::spawn(operations::run_leader_election(app.clone()));
 
     tokio::spawn(grpc::server::start(app.clone(), 7124));
 
+    tokio::spawn(grpc::replication_client::start(app.clone()));
+
     app.app_states.wait_until_shutdown().await;
 
     crate::operations::before_shut_down::execute_before_shutdown(app).await;
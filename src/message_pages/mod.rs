@@ -2,6 +2,7 @@ mod error;
 
 mod pages_list;
 mod sub_page;
+mod sub_page_codec;
 mod sub_page_inner;
 mod sub_page_read_copy;
 
@@ -11,5 +12,6 @@ pub use error::PageOperationError;
 
 pub use pages_list::PagesList;
 pub use sub_page::*;
+pub use sub_page_codec::*;
 pub use sub_page_inner::*;
 pub use sub_page_read_copy::*;
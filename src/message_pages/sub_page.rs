@@ -8,7 +8,7 @@ use my_service_bus::shared::{
 use rust_extensions::sorted_vec::{EntityWithKey, SortedVecOfArc};
 use tokio::sync::Mutex;
 
-use super::{SubPageInner, SubPageReadCopy};
+use super::{SubPageCodec, SubPageInner, SubPageReadCopy};
 
 pub enum SubPage {
     Active(SubPageId, Mutex<SubPageInner>),
@@ -47,12 +47,12 @@ impl SubPage {
         }
     }
 
-    pub async fn new_messages(&self, messages: Vec<MessageProtobufModel>) {
+    pub async fn new_messages(&self, topic_id: &str, messages: Vec<MessageProtobufModel>) {
         match self {
             SubPage::Active(_, inner) => {
                 let mut data = inner.lock().await;
                 for message in messages {
-                    data.add_message(Arc::new(message));
+                    data.add_message(topic_id, Arc::new(message));
                 }
             }
             SubPage::FromArchive(_) => {}
@@ -60,6 +60,25 @@ impl SubPage {
         }
     }
 
+    // Archived messages are added only if they are not in memory, since the ones in memory are newer.
+    // Returns amount of added messages
+    pub async fn merge_archived_messages(&self, topic_id: &str, archived: SubPageInner) -> usize {
+        let mut result = 0;
+
+        if let SubPage::Active(_, inner) = self {
+            let mut data = inner.lock().await;
+
+            for message in archived.messages.iter() {
+                if data.get_message(message.get_message_id()).is_none() {
+                    data.add_message(topic_id, message.clone());
+                    result += 1;
+                }
+            }
+        }
+
+        result
+    }
+
     pub async fn get_message(&self, message_id: MessageId) -> Option<Arc<MessageProtobufModel>> {
         match self {
             SubPage::Active(_, inner) => {
@@ -127,21 +146,24 @@ impl SubPage {
         result
     }
 
-    pub async fn to_compressed_payload(&self) -> Option<Vec<u8>> {
+    pub async fn get_first_and_last_message(
+        &self,
+    ) -> Option<(Arc<MessageProtobufModel>, Arc<MessageProtobufModel>)> {
         match self {
-            SubPage::Active(_, sub_page_inner) => {
-                let mut page_compressor =
-                    my_service_bus::shared::page_compressor::CompressedPageBuilder::new_as_single_file();
-
-                {
-                    let data = sub_page_inner.lock().await;
-
-                    for msg in data.messages.iter() {
-                        page_compressor.add_message(msg).unwrap();
-                    }
-                }
+            SubPage::Active(_, inner) => {
+                let data = inner.lock().await;
+                data.get_first_and_last_message()
+            }
+            SubPage::FromArchive(data) => data.get_first_and_last_message(),
+            SubPage::Missing(_) => None,
+        }
+    }
 
-                let result = page_compressor.get_payload().unwrap();
+    pub async fn to_compressed_payload(&self, codec: &SubPageCodec) -> Option<Vec<u8>> {
+        match self {
+            SubPage::Active(_, sub_page_inner) => {
+                let data = sub_page_inner.lock().await;
+                let result = codec.encode_messages(data.messages.iter().map(|itm| itm.as_ref()));
                 Some(result)
             }
             SubPage::FromArchive(_) => None,
@@ -168,3 +190,53 @@ impl SubPage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use my_service_bus::{
+        abstractions::MessageId,
+        shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId},
+    };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::message_pages::SubPageInner;
+
+    use super::SubPage;
+
+    fn message(id: i64, data: &str) -> Arc<MessageProtobufModel> {
+        Arc::new(MessageProtobufModel::new(
+            MessageId::new(id),
+            DateTimeAsMicroseconds::new(0),
+            data.as_bytes().to_vec(),
+            vec![],
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_late_messages_are_merged_with_archived() {
+        let sub_page_id = SubPageId::new(0);
+
+        let mut archived = SubPageInner::new(sub_page_id);
+        archived.add_message("test", message(1, "archived-1"));
+        archived.add_message("test", message(2, "archived-2"));
+
+        // Late message of the sub page which is archived already
+        let sub_page = SubPage::create_new(SubPageInner::new(sub_page_id));
+        sub_page
+            .new_messages("test", vec![message(2, "late-2").as_ref().clone()])
+            .await;
+
+        let merged = sub_page.merge_archived_messages("test", archived).await;
+        assert_eq!(merged, 1);
+
+        let first = sub_page.get_message(MessageId::new(1)).await.unwrap();
+        assert_eq!(first.data, b"archived-1".to_vec());
+
+        let late = sub_page.get_message(MessageId::new(2)).await.unwrap();
+        assert_eq!(late.data, b"late-2".to_vec());
+
+        assert_eq!(sub_page.get_size_and_amount().await.amount, 2);
+    }
+}
@@ -0,0 +1,539 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use my_service_bus::shared::{
+    page_compressor::{CompressedPageBuilder, CompressedPageReader, CompressedPageReaderError},
+    protobuf_models::MessageProtobufModel,
+};
+
+// Zip payloads are written without header, so archives written before codecs were introduced
+// are read as they are. Other codecs start with the header:
+// magic[4] codec[1] dictionary_id[4] uncompressed_len[4]
+const CODEC_PAYLOAD_MAGIC: [u8; 4] = *b"MSBP";
+const CODEC_HEADER_SIZE: usize = 13;
+
+// Header comes from storage, so buffer is not allocated by it unless it fits a sub page
+pub const MAX_UNCOMPRESSED_SUB_PAGE_SIZE: usize = 256 * 1024 * 1024;
+
+// Enough to read the uncompressed length from the codec header or the zip local file header
+pub const PAYLOAD_PREFIX_SIZE: usize = 30;
+
+const ZIP_LOCAL_FILE_HEADER_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+
+const ZSTD_CODEC_ID: u8 = 1;
+const LZ4_CODEC_ID: u8 = 2;
+
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug)]
+pub enum SubPageCodecError {
+    ZipError(CompressedPageReaderError),
+    IoError(std::io::Error),
+    Lz4Error(lz4_flex::block::DecompressError),
+    ProtobufDecodeError(prost::DecodeError),
+    UnknownCodec(u8),
+    UnknownDictionary(u32),
+    InvalidHeader,
+    UncompressedSizeTooBig(usize),
+}
+
+impl From<CompressedPageReaderError> for SubPageCodecError {
+    fn from(src: CompressedPageReaderError) -> Self {
+        Self::ZipError(src)
+    }
+}
+
+impl From<std::io::Error> for SubPageCodecError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+impl From<lz4_flex::block::DecompressError> for SubPageCodecError {
+    fn from(src: lz4_flex::block::DecompressError) -> Self {
+        Self::Lz4Error(src)
+    }
+}
+
+impl From<prost::DecodeError> for SubPageCodecError {
+    fn from(src: prost::DecodeError) -> Self {
+        Self::ProtobufDecodeError(src)
+    }
+}
+
+pub struct ZstdDictionary {
+    pub id: u32,
+    pub data: Vec<u8>,
+}
+
+impl ZstdDictionary {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            id: crc32fast::hash(data.as_slice()),
+            data,
+        }
+    }
+}
+
+pub enum SubPageCodec {
+    Zip,
+    Zstd {
+        level: i32,
+        dictionary: Option<Arc<ZstdDictionary>>,
+    },
+    Lz4,
+}
+
+impl SubPageCodec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubPageCodec::Zip => "zip",
+            SubPageCodec::Zstd { .. } => "zstd",
+            SubPageCodec::Lz4 => "lz4",
+        }
+    }
+
+    pub fn encode_messages<'s>(
+        &self,
+        messages: impl Iterator<Item = &'s MessageProtobufModel>,
+    ) -> Vec<u8> {
+        if let SubPageCodec::Zip = self {
+            let mut page_compressor = CompressedPageBuilder::new_as_single_file();
+
+            for msg in messages {
+                page_compressor.add_message(msg).unwrap();
+            }
+
+            return page_compressor.get_payload().unwrap();
+        }
+
+        let mut raw = Vec::new();
+
+        for msg in messages {
+            prost::Message::encode_length_delimited(msg, &mut raw).unwrap();
+        }
+
+        self.compress(raw.as_slice())
+    }
+
+    fn compress(&self, raw: &[u8]) -> Vec<u8> {
+        let (codec_id, dictionary_id, compressed) = match self {
+            SubPageCodec::Zip => panic!("Zip payload is compressed by CompressedPageBuilder"),
+            SubPageCodec::Zstd { level, dictionary } => match dictionary {
+                Some(dictionary) => {
+                    let mut compressor =
+                        zstd::bulk::Compressor::with_dictionary(*level, dictionary.data.as_slice())
+                            .unwrap();
+
+                    (
+                        ZSTD_CODEC_ID,
+                        dictionary.id,
+                        compressor.compress(raw).unwrap(),
+                    )
+                }
+                None => (ZSTD_CODEC_ID, 0, zstd::bulk::compress(raw, *level).unwrap()),
+            },
+            SubPageCodec::Lz4 => (LZ4_CODEC_ID, 0, lz4_flex::block::compress(raw)),
+        };
+
+        let mut result = Vec::with_capacity(CODEC_HEADER_SIZE + compressed.len());
+        result.extend_from_slice(&CODEC_PAYLOAD_MAGIC);
+        result.push(codec_id);
+        result.extend_from_slice(&dictionary_id.to_le_bytes());
+        result.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+        result.extend_from_slice(compressed.as_slice());
+        result
+    }
+}
+
+// Uncompressed length of the sub page by the first bytes of its payload. None if it is not known:
+// the payload is encrypted or zip entry has sizes in the data descriptor
+pub fn read_uncompressed_len(prefix: &[u8]) -> Option<usize> {
+    if prefix.starts_with(&CODEC_PAYLOAD_MAGIC) {
+        if prefix.len() < CODEC_HEADER_SIZE {
+            return None;
+        }
+
+        let uncompressed_len = u32::from_le_bytes(prefix[9..13].try_into().unwrap());
+        return Some(uncompressed_len as usize);
+    }
+
+    if prefix.starts_with(&ZIP_LOCAL_FILE_HEADER_SIGNATURE) {
+        if prefix.len() < PAYLOAD_PREFIX_SIZE {
+            return None;
+        }
+
+        let uncompressed_len = u32::from_le_bytes(prefix[22..26].try_into().unwrap());
+
+        if uncompressed_len == 0 {
+            return None;
+        }
+
+        return Some(uncompressed_len as usize);
+    }
+
+    None
+}
+
+// Codec to write sub pages of each topic with. Sub pages are read by the codec in the payload header.
+// Trained dictionaries are added at runtime and replace the configured codec of the topic
+pub struct SubPageCodecs {
+    default: Arc<SubPageCodec>,
+    by_topic: HashMap<String, Arc<SubPageCodec>>,
+    topics_to_train: HashSet<String>,
+    trained: RwLock<HashMap<String, Arc<SubPageCodec>>>,
+    dictionaries: RwLock<HashMap<u32, Arc<ZstdDictionary>>>,
+    loaded_topics: Mutex<HashSet<String>>,
+}
+
+impl SubPageCodecs {
+    pub fn new(default: SubPageCodec) -> Self {
+        let result = Self {
+            default: Arc::new(default),
+            by_topic: HashMap::new(),
+            topics_to_train: HashSet::new(),
+            trained: RwLock::new(HashMap::new()),
+            dictionaries: RwLock::new(HashMap::new()),
+            loaded_topics: Mutex::new(HashSet::new()),
+        };
+
+        result.register_dictionary(result.default.as_ref());
+        result
+    }
+
+    pub fn set_topic_codec(&mut self, topic_id: &str, codec: SubPageCodec) {
+        self.register_dictionary(&codec);
+        self.by_topic.insert(topic_id.to_string(), Arc::new(codec));
+    }
+
+    pub fn enable_dictionary_training(&mut self, topic_id: &str) {
+        self.topics_to_train.insert(topic_id.to_string());
+    }
+
+    pub fn get_topics_to_train(&self) -> Vec<String> {
+        self.topics_to_train.iter().cloned().collect()
+    }
+
+    fn register_dictionary(&self, codec: &SubPageCodec) {
+        if let SubPageCodec::Zstd {
+            dictionary: Some(dictionary),
+            ..
+        } = codec
+        {
+            self.add_dictionary(dictionary.clone());
+        }
+    }
+
+    pub fn add_dictionary(&self, dictionary: Arc<ZstdDictionary>) {
+        let mut write_access = self.dictionaries.write().unwrap();
+        write_access.insert(dictionary.id, dictionary);
+    }
+
+    // Sub pages of the topic are written with the dictionary from now on
+    pub fn set_trained_dictionary(&self, topic_id: &str, dictionary: Arc<ZstdDictionary>) {
+        self.add_dictionary(dictionary.clone());
+
+        if !self.topics_to_train.contains(topic_id) {
+            return;
+        }
+
+        let level = match self.by_topic.get(topic_id).map(|itm| itm.as_ref()) {
+            Some(SubPageCodec::Zstd { level, .. }) => *level,
+            _ => DEFAULT_ZSTD_LEVEL,
+        };
+
+        let codec = SubPageCodec::Zstd {
+            level,
+            dictionary: Some(dictionary),
+        };
+
+        let mut write_access = self.trained.write().unwrap();
+        write_access.insert(topic_id.to_string(), Arc::new(codec));
+    }
+
+    pub fn is_dictionaries_loaded(&self, topic_id: &str) -> bool {
+        let read_access = self.loaded_topics.lock().unwrap();
+        read_access.contains(topic_id)
+    }
+
+    pub fn set_dictionaries_loaded(&self, topic_id: &str) {
+        let mut write_access = self.loaded_topics.lock().unwrap();
+        write_access.insert(topic_id.to_string());
+    }
+
+    pub fn get(&self, topic_id: &str) -> Arc<SubPageCodec> {
+        if let Some(codec) = self.trained.read().unwrap().get(topic_id) {
+            return codec.clone();
+        }
+
+        match self.by_topic.get(topic_id) {
+            Some(codec) => codec.clone(),
+            None => self.default.clone(),
+        }
+    }
+
+    pub fn decode_messages(
+        &self,
+        payload: &[u8],
+    ) -> Result<Vec<MessageProtobufModel>, SubPageCodecError> {
+        let mut result = Vec::new();
+
+        if !payload.starts_with(&CODEC_PAYLOAD_MAGIC) {
+            let mut reader = CompressedPageReader::new(payload)?;
+
+            while let Some(msg) = reader.get_next_message()? {
+                result.push(msg);
+            }
+
+            return Ok(result);
+        }
+
+        let raw = self.decompress(payload)?;
+
+        let mut buf = raw.as_slice();
+
+        while !buf.is_empty() {
+            let msg: MessageProtobufModel = prost::Message::decode_length_delimited(&mut buf)?;
+            result.push(msg);
+        }
+
+        Ok(result)
+    }
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, SubPageCodecError> {
+        if payload.len() < CODEC_HEADER_SIZE {
+            return Err(SubPageCodecError::InvalidHeader);
+        }
+
+        let codec_id = payload[4];
+        let dictionary_id = u32::from_le_bytes(payload[5..9].try_into().unwrap());
+        let uncompressed_len = u32::from_le_bytes(payload[9..13].try_into().unwrap()) as usize;
+
+        if uncompressed_len > MAX_UNCOMPRESSED_SUB_PAGE_SIZE {
+            return Err(SubPageCodecError::UncompressedSizeTooBig(uncompressed_len));
+        }
+
+        let compressed = &payload[CODEC_HEADER_SIZE..];
+
+        match codec_id {
+            ZSTD_CODEC_ID => {
+                if dictionary_id == 0 {
+                    return Ok(zstd::bulk::decompress(compressed, uncompressed_len)?);
+                }
+
+                let dictionary = self
+                    .dictionaries
+                    .read()
+                    .unwrap()
+                    .get(&dictionary_id)
+                    .cloned()
+                    .ok_or(SubPageCodecError::UnknownDictionary(dictionary_id))?;
+
+                let mut decompressor =
+                    zstd::bulk::Decompressor::with_dictionary(dictionary.data.as_slice())?;
+
+                Ok(decompressor.decompress(compressed, uncompressed_len)?)
+            }
+            LZ4_CODEC_ID => Ok(lz4_flex::block::decompress(compressed, uncompressed_len)?),
+            _ => Err(SubPageCodecError::UnknownCodec(codec_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use my_service_bus::abstractions::MessageId;
+    use my_service_bus::shared::protobuf_models::MessageMetaDataProtobufModel;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    fn get_messages() -> Vec<MessageProtobufModel> {
+        (0..10)
+            .map(|no| {
+                MessageProtobufModel::new(
+                    MessageId::new(1000 + no),
+                    DateTimeAsMicroseconds::new(no * 1_000_000),
+                    format!("{{\"id\":{}}}", no).into_bytes(),
+                    vec![MessageMetaDataProtobufModel {
+                        key: "source".to_string(),
+                        value: "api".to_string(),
+                    }],
+                )
+            })
+            .collect()
+    }
+
+    fn assert_messages(result: Vec<MessageProtobufModel>, expected: &[MessageProtobufModel]) {
+        assert_eq!(result.len(), expected.len());
+
+        for (result, expected) in result.iter().zip(expected) {
+            assert_eq!(
+                result.get_message_id().get_value(),
+                expected.get_message_id().get_value()
+            );
+            assert_eq!(result.data, expected.data);
+            assert_eq!(result.headers.len(), 1);
+            assert_eq!(result.headers[0].value, "api");
+        }
+    }
+
+    fn get_raw() -> Vec<u8> {
+        "{\"id\":1,\"name\":\"test\"}".repeat(100).into_bytes()
+    }
+
+    #[test]
+    fn test_zstd_and_lz4_roundtrip() {
+        let codecs = SubPageCodecs::new(SubPageCodec::Zip);
+
+        let raw = get_raw();
+
+        for codec in [
+            SubPageCodec::Zstd {
+                level: DEFAULT_ZSTD_LEVEL,
+                dictionary: None,
+            },
+            SubPageCodec::Lz4,
+        ] {
+            let payload = codec.compress(raw.as_slice());
+            assert!(payload.len() < raw.len());
+
+            let result = codecs.decompress(payload.as_slice()).unwrap();
+            assert_eq!(result, raw);
+        }
+    }
+
+    #[test]
+    fn test_zstd_with_dictionary() {
+        let dictionary = Arc::new(ZstdDictionary::new("{\"id\":,\"name\":\"\"}".into()));
+
+        let codec = SubPageCodec::Zstd {
+            level: DEFAULT_ZSTD_LEVEL,
+            dictionary: Some(dictionary.clone()),
+        };
+
+        let raw = get_raw();
+        let payload = codec.compress(raw.as_slice());
+
+        // Payload can not be read without the dictionary it was written with
+        let codecs = SubPageCodecs::new(SubPageCodec::Zip);
+        assert!(matches!(
+            codecs.decompress(payload.as_slice()),
+            Err(SubPageCodecError::UnknownDictionary(_))
+        ));
+
+        let mut codecs = SubPageCodecs::new(SubPageCodec::Zip);
+        codecs.set_topic_codec("test", codec);
+
+        assert_eq!(codecs.decompress(payload.as_slice()).unwrap(), raw);
+        assert_eq!(codecs.get("test").as_str(), "zstd");
+        assert_eq!(codecs.get("other").as_str(), "zip");
+    }
+
+    #[test]
+    fn test_read_uncompressed_len() {
+        let raw = get_raw();
+
+        let payload = SubPageCodec::Lz4.compress(raw.as_slice());
+        assert_eq!(
+            read_uncompressed_len(&payload[..PAYLOAD_PREFIX_SIZE]),
+            Some(raw.len())
+        );
+
+        assert_eq!(read_uncompressed_len(b"MSBE0000"), None);
+        assert_eq!(read_uncompressed_len(&payload[..4]), None);
+    }
+
+    #[test]
+    fn test_encode_and_decode_messages() {
+        let codecs = SubPageCodecs::new(SubPageCodec::Zip);
+        let messages = get_messages();
+
+        for codec in [
+            SubPageCodec::Zip,
+            SubPageCodec::Zstd {
+                level: DEFAULT_ZSTD_LEVEL,
+                dictionary: None,
+            },
+            SubPageCodec::Lz4,
+        ] {
+            let payload = codec.encode_messages(messages.iter());
+
+            let result = codecs.decode_messages(payload.as_slice()).unwrap();
+            assert_messages(result, messages.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_decode_legacy_zip_payload() {
+        let messages = get_messages();
+
+        // Sub pages archived before codecs were introduced
+        let mut page_compressor = CompressedPageBuilder::new_as_single_file();
+
+        for msg in &messages {
+            page_compressor.add_message(msg).unwrap();
+        }
+
+        let payload = page_compressor.get_payload().unwrap();
+
+        let codecs = SubPageCodecs::new(SubPageCodec::Lz4);
+        let result = codecs.decode_messages(payload.as_slice()).unwrap();
+
+        assert_messages(result, messages.as_slice());
+    }
+
+    #[test]
+    fn test_uncompressed_len_is_bounded() {
+        let codecs = SubPageCodecs::new(SubPageCodec::Zip);
+
+        let mut payload = SubPageCodec::Lz4.compress(get_raw().as_slice());
+        payload[9..13].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(matches!(
+            codecs.decompress(payload.as_slice()),
+            Err(SubPageCodecError::UncompressedSizeTooBig(_))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_codec() {
+        let codecs = SubPageCodecs::new(SubPageCodec::Zip);
+
+        let mut payload = SubPageCodec::Lz4.compress(get_raw().as_slice());
+        payload[4] = 100;
+
+        assert!(matches!(
+            codecs.decompress(payload.as_slice()),
+            Err(SubPageCodecError::UnknownCodec(100))
+        ));
+    }
+
+    #[test]
+    fn test_trained_dictionary_replaces_topic_codec() {
+        let mut codecs = SubPageCodecs::new(SubPageCodec::Zip);
+        codecs.set_topic_codec("trained", SubPageCodec::Lz4);
+        codecs.enable_dictionary_training("trained");
+
+        let dictionary = Arc::new(ZstdDictionary::new("{\"id\":,\"name\":\"\"}".into()));
+
+        codecs.set_trained_dictionary("trained", dictionary.clone());
+        codecs.set_trained_dictionary("not-trained", dictionary);
+
+        assert_eq!(codecs.get("trained").as_str(), "zstd");
+        assert_eq!(codecs.get("not-trained").as_str(), "zip");
+
+        let raw = get_raw();
+        let payload = codecs.get("trained").compress(raw.as_slice());
+
+        assert_eq!(codecs.decompress(payload.as_slice()).unwrap(), raw);
+
+        assert!(!codecs.is_dictionaries_loaded("trained"));
+        codecs.set_dictionaries_loaded("trained");
+        assert!(codecs.is_dictionaries_loaded("trained"));
+    }
+}
@@ -2,13 +2,14 @@ use std::sync::Arc;
 
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::{
-    page_compressor::CompressedPageReaderError,
     protobuf_models::MessageProtobufModel,
     sub_page::{SizeAndAmount, SubPageId},
 };
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_extensions::sorted_vec::SortedVecOfArc;
 
+use super::{SubPageCodecError, SubPageCodecs};
+
 pub struct SubPageInner {
     pub sub_page_id: SubPageId,
     pub messages: SortedVecOfArc<i64, MessageProtobufModel>,
@@ -47,15 +48,21 @@ impl SubPageInner {
 
     pub fn add_message(
         &mut self,
+        topic_id: &str,
         message: Arc<MessageProtobufModel>,
     ) -> Option<Arc<MessageProtobufModel>> {
         let message_id = message.get_message_id();
 
         if !self.sub_page_id.is_my_message_id(message_id) {
-            println!(
-                "Somehow we are uploading message_id {} to sub_page {}. Skipping message...",
-                message_id.get_value(),
-                self.sub_page_id.get_value()
+            crate::app::LOGS.add_error(
+                Some(topic_id),
+                "SubPageInner::add_message",
+                format!(
+                    "Somehow we are uploading message_id {} to sub_page {}. Skipping message...",
+                    message_id.get_value(),
+                    self.sub_page_id.get_value()
+                ),
+                None,
             );
             return None;
         }
@@ -80,20 +87,30 @@ impl SubPageInner {
         self.messages.clone()
     }
 
+    pub fn get_first_and_last_message(
+        &self,
+    ) -> Option<(Arc<MessageProtobufModel>, Arc<MessageProtobufModel>)> {
+        let first = self.messages.first()?;
+        let last = self.messages.last()?;
+        Some((first.clone(), last.clone()))
+    }
+
     pub fn get_size_and_amount(&self) -> &SizeAndAmount {
         &self.size_and_amount
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(sub_page_id = sub_page_id.get_value(), size = compressed_payload.len()),
+    )]
     pub fn from_compressed_payload(
         sub_page_id: SubPageId,
         compressed_payload: &[u8],
-    ) -> Result<SubPageInner, CompressedPageReaderError> {
-        let mut compressed_payload =
-            my_service_bus::shared::page_compressor::CompressedPageReader::new(compressed_payload)?;
-
+        codecs: &SubPageCodecs,
+    ) -> Result<SubPageInner, SubPageCodecError> {
         let mut messages = SortedVecOfArc::new();
 
-        while let Some(msg) = compressed_payload.get_next_message().unwrap() {
+        for msg in codecs.decode_messages(compressed_payload)? {
             messages.insert_or_replace(Arc::new(msg));
         }
 
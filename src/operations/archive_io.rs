@@ -1,10 +1,12 @@
 use my_azure_page_blob_random_access::PageBlobRandomAccessError;
-use my_service_bus::shared::{page_compressor::CompressedPageReaderError, sub_page::SubPageId};
+use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StopWatch};
 
 use crate::{
     app::AppContext,
-    message_pages::{SubPage, SubPageInner},
+    archive_storage::{toc::SubPagePosition, ArchiveStorage, ArchivedMessageInfo},
+    encryption::EncryptionError,
+    message_pages::{SubPage, SubPageCodecError, SubPageInner},
     topic_data::TopicData,
 };
 
@@ -12,12 +14,19 @@ use crate::{
 pub enum RestoreSubPageError {
     NotFound,
     PageBlobRandomAccessError(PageBlobRandomAccessError),
-    CompressedPageReaderError(CompressedPageReaderError),
+    SubPageCodecError(SubPageCodecError),
+    EncryptionError(EncryptionError),
 }
 
-impl From<CompressedPageReaderError> for RestoreSubPageError {
-    fn from(err: CompressedPageReaderError) -> Self {
-        Self::CompressedPageReaderError(err)
+impl From<EncryptionError> for RestoreSubPageError {
+    fn from(err: EncryptionError) -> Self {
+        Self::EncryptionError(err)
+    }
+}
+
+impl From<SubPageCodecError> for RestoreSubPageError {
+    fn from(err: SubPageCodecError) -> Self {
+        Self::SubPageCodecError(err)
     }
 }
 
@@ -27,54 +36,173 @@ impl From<PageBlobRandomAccessError> for RestoreSubPageError {
     }
 }
 
+#[tracing::instrument(
+    skip_all,
+    fields(topic_id = topic_data.topic_id.as_str(), sub_page_id = sub_page_id.get_value()),
+)]
 pub async fn restore_sub_page(
     app: &AppContext,
     topic_data: &TopicData,
     sub_page_id: SubPageId,
 ) -> Result<SubPage, RestoreSubPageError> {
-    let page_blob_storage = topic_data
-        .archive_pages_list
+    let page_blob_storage = app
+        .archive_storage_list
         .try_get_or_open(sub_page_id.into(), topic_data.topic_id.as_str(), app)
         .await;
 
-    if page_blob_storage.is_none() {
-        return Err(RestoreSubPageError::NotFound);
+    let page_blob_storage = match page_blob_storage {
+        Some(page_blob_storage) => page_blob_storage,
+        None => return Err(RestoreSubPageError::NotFound),
+    };
+
+    let result = read_archived_sub_page(
+        app,
+        topic_data.topic_id.as_str(),
+        page_blob_storage.as_ref(),
+        sub_page_id,
+    )
+    .await?;
+
+    match result {
+        Some(result) => Ok(SubPage::restore_from_archive(result)),
+        None => Err(RestoreSubPageError::NotFound),
     }
+}
+
+async fn read_archived_sub_page(
+    app: &AppContext,
+    topic_id: &str,
+    storage: &ArchiveStorage,
+    sub_page_id: SubPageId,
+) -> Result<Option<SubPageInner>, RestoreSubPageError> {
+    let mut sw = StopWatch::new();
+    sw.start();
 
-    let compressed_payload = page_blob_storage
-        .unwrap()
-        .read_sub_page_payload(sub_page_id)
-        .await?;
+    let compressed_payload = storage.read_sub_page_payload(sub_page_id).await;
 
-    if compressed_payload.is_none() {
-        return Err(RestoreSubPageError::NotFound);
+    sw.pause();
+
+    app.metrics_keeper.observe_archive_read(sw.duration());
+
+    if compressed_payload.is_err() {
+        app.metrics_keeper.page_blob_failure("archive_read");
     }
 
-    let compressed_payload = compressed_payload.unwrap();
+    let compressed_payload = match compressed_payload? {
+        Some(compressed_payload) => compressed_payload,
+        None => return Ok(None),
+    };
+
+    let compressed_payload =
+        app.encryption
+            .decrypt(topic_id, sub_page_id, compressed_payload.as_slice())?;
 
-    let result = SubPageInner::from_compressed_payload(sub_page_id, compressed_payload.as_slice())?;
+    let result = SubPageInner::from_compressed_payload(
+        sub_page_id,
+        compressed_payload.as_ref(),
+        &app.codecs,
+    )?;
 
-    Ok(SubPage::restore_from_archive(result))
+    Ok(Some(result))
 }
 
+#[tracing::instrument(
+    skip_all,
+    fields(topic_id = topic_data.topic_id.as_str(), sub_page_id = sub_page.get_id().get_value()),
+)]
 pub async fn save_sub_page(app: &AppContext, topic_data: &TopicData, sub_page: &SubPage) {
+    if !sub_page.is_active() {
+        return;
+    }
+
     let sub_page_id = sub_page.get_id();
-    if let Some(zip_payload) = sub_page.to_compressed_payload().await {
-        let storage = app
-            .archive_storage_list
-            .get_or_create(sub_page_id.into(), topic_data.topic_id.as_str(), app)
-            .await;
+    let codec = app.codecs.get(topic_data.topic_id.as_str());
+
+    let storage = app
+        .archive_storage_list
+        .get_or_create(sub_page_id.into(), topic_data.topic_id.as_str(), app)
+        .await;
+
+    // Sub page gets late messages after it is archived and gc-ed. It is created empty then,
+    // so archived messages are merged in before the archived payload is overwritten
+    let archived = read_archived_sub_page(
+        app,
+        topic_data.topic_id.as_str(),
+        storage.as_ref(),
+        sub_page_id,
+    )
+    .await;
+
+    match archived {
+        Ok(Some(archived)) => {
+            let merged = sub_page
+                .merge_archived_messages(topic_data.topic_id.as_str(), archived)
+                .await;
+
+            if merged > 0 {
+                app.logs.add_info(
+                    Some(topic_data.topic_id.as_str()),
+                    "SaveSubPage",
+                    format!(
+                        "{} archived messages are merged into sub page {} before it is overwritten",
+                        merged,
+                        sub_page_id.get_value()
+                    ),
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(err) => {
+            app.logs.add_error(
+                Some(topic_data.topic_id.as_str()),
+                "SaveSubPage",
+                format!(
+                    "Sub page {} is not saved: archived payload can not be read and would be overwritten",
+                    sub_page_id.get_value()
+                ),
+                Some(format!("{:?}", err)),
+            );
+            return;
+        }
+    }
+
+    if let Some(payload) = sub_page.to_compressed_payload(codec.as_ref()).await {
+        let payload = app
+            .encryption
+            .encrypt(topic_data.topic_id.as_str(), sub_page_id, payload);
 
         let mut sw = StopWatch::new();
 
         sw.start();
 
-        storage
-            .write_payload(sub_page_id, zip_payload.as_slice())
-            .await;
+        let result = storage.write_payload(sub_page_id, payload.as_slice()).await;
 
         sw.pause();
 
+        app.metrics_keeper.observe_archive_write(sw.duration());
+
+        match result.overwritten {
+            Some(overwritten) => {
+                // Stats are rescanned, since the sub page is already counted there
+                app.archive_stats.remove(topic_data.topic_id.as_str()).await;
+
+                app.logs.add_info(
+                    Some(topic_data.topic_id.as_str()),
+                    "SaveSubPage",
+                    format!(
+                        "Sub page {} is overwritten. {} bytes are orphaned until compaction",
+                        sub_page_id.get_value(),
+                        overwritten.length
+                    ),
+                );
+            }
+            None => {
+                update_archive_stats(app, topic_data, sub_page, result.pos).await;
+            }
+        }
+
+        super::index_archived_sub_page(app, topic_data.topic_id.as_str(), sub_page).await;
+
         topic_data.metrics.update_last_saved_duration(sw.duration());
 
         topic_data
@@ -82,3 +210,27 @@ pub async fn save_sub_page(app: &AppContext, topic_data: &TopicData, sub_page: &
             .update_last_saved_moment(DateTimeAsMicroseconds::now());
     }
 }
+
+async fn update_archive_stats(
+    app: &AppContext,
+    topic_data: &TopicData,
+    sub_page: &SubPage,
+    pos: SubPagePosition,
+) {
+    let first_and_last = sub_page.get_first_and_last_message().await;
+
+    if let Some((first, last)) = first_and_last {
+        let size_and_amount = sub_page.get_size_and_amount().await;
+
+        app.archive_stats
+            .sub_page_archived(
+                topic_data.topic_id.as_str(),
+                sub_page.get_id().into(),
+                pos,
+                size_and_amount.size,
+                ArchivedMessageInfo::from_message(first.as_ref()),
+                ArchivedMessageInfo::from_message(last.as_ref()),
+            )
+            .await;
+    }
+}
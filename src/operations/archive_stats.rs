@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use my_service_bus::shared::sub_page::SubPageId;
+
+use crate::{
+    app::AppContext,
+    archive_storage::{
+        ArchiveFileNo, ArchiveFileStats, ArchiveStorage, ArchivedMessageInfo, TopicArchiveStats,
+    },
+    message_pages::{SubPageInner, PAYLOAD_PREFIX_SIZE},
+};
+
+use super::OperationError;
+
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn get_archive_stats(
+    app: &AppContext,
+    topic_id: &str,
+) -> Result<TopicArchiveStats, OperationError> {
+    if let Some(stats) = app.archive_stats.get(topic_id).await {
+        return Ok(stats);
+    }
+
+    let message_id = app.topics_snapshot.get_current_message_id(topic_id).await;
+
+    if message_id.is_none() {
+        return Err(OperationError::TopicNotFound(topic_id.to_string()));
+    }
+
+    let last_sub_page_id: SubPageId = message_id.unwrap().into();
+    let last_archive_file_no: ArchiveFileNo = last_sub_page_id.into();
+
+    let mut stats = TopicArchiveStats::new();
+
+    let mut first_sub_page: Option<(Arc<ArchiveStorage>, SubPageId)> = None;
+    let mut last_sub_page: Option<(Arc<ArchiveStorage>, SubPageId)> = None;
+
+    for file_no in 0..=last_archive_file_no.get_value() {
+        let archive_file_no = ArchiveFileNo::new(file_no);
+
+        let archive_storage = app
+            .archive_storage_list
+            .get_existing_or_open(archive_file_no, topic_id, app)
+            .await;
+
+        if archive_storage.is_none() {
+            continue;
+        }
+
+        let archive_storage = archive_storage.unwrap();
+
+        let populated = archive_storage.get_populated_sub_pages().await;
+
+        if let Some((sub_page_id, _)) = populated.first() {
+            if first_sub_page.is_none() {
+                first_sub_page = Some((archive_storage.clone(), *sub_page_id));
+            }
+        }
+
+        if let Some((sub_page_id, _)) = populated.last() {
+            last_sub_page = Some((archive_storage.clone(), *sub_page_id));
+        }
+
+        let positions: Vec<_> = populated.iter().map(|(_, pos)| *pos).collect();
+
+        let prefixes = archive_storage
+            .read_payload_prefixes(positions.as_slice(), PAYLOAD_PREFIX_SIZE)
+            .await;
+
+        if let Ok(prefixes) = prefixes {
+            for (pos, prefix) in positions.iter().zip(prefixes) {
+                if let Some(uncompressed_len) =
+                    crate::message_pages::read_uncompressed_len(prefix.as_slice())
+                {
+                    stats.measured_compressed_size += pos.length as usize;
+                    stats.measured_uncompressed_size += uncompressed_len;
+                }
+            }
+        }
+
+        stats.files.insert(
+            file_no,
+            ArchiveFileStats {
+                archive_file_no: file_no,
+                blob_size: archive_storage.get_blob_size().await,
+                populated_toc_slots: populated.len(),
+                compressed_size: populated.iter().map(|(_, pos)| pos.length as u64).sum(),
+            },
+        );
+    }
+
+    if let Some((archive_storage, sub_page_id)) = first_sub_page {
+        read_first_and_last_message(
+            app,
+            topic_id,
+            &mut stats,
+            archive_storage.as_ref(),
+            sub_page_id,
+        )
+        .await;
+    }
+
+    if let Some((archive_storage, sub_page_id)) = last_sub_page {
+        read_first_and_last_message(
+            app,
+            topic_id,
+            &mut stats,
+            archive_storage.as_ref(),
+            sub_page_id,
+        )
+        .await;
+    }
+
+    app.archive_stats.insert(topic_id, stats.clone()).await;
+
+    Ok(stats)
+}
+
+async fn read_first_and_last_message(
+    app: &AppContext,
+    topic_id: &str,
+    stats: &mut TopicArchiveStats,
+    archive_storage: &ArchiveStorage,
+    sub_page_id: SubPageId,
+) {
+    if let Some(first_message) = &stats.first_message {
+        let read_sub_page_id: SubPageId = first_message.message_id.into();
+
+        if read_sub_page_id.get_value() == sub_page_id.get_value() {
+            return;
+        }
+    }
+
+    let payload = archive_storage.read_sub_page_payload(sub_page_id).await;
+
+    let payload = match payload {
+        Ok(Some(payload)) => payload,
+        _ => return,
+    };
+
+    let decrypted = match app
+        .encryption
+        .decrypt(topic_id, sub_page_id, payload.as_slice())
+    {
+        Ok(decrypted) => decrypted,
+        Err(_) => return,
+    };
+
+    let sub_page =
+        SubPageInner::from_compressed_payload(sub_page_id, decrypted.as_ref(), &app.codecs);
+
+    if let Ok(sub_page) = sub_page {
+        if let Some((first, last)) = sub_page.get_first_and_last_message() {
+            stats.update_first_message(ArchivedMessageInfo::from_message(first.as_ref()));
+            stats.update_last_message(ArchivedMessageInfo::from_message(last.as_ref()));
+        }
+    }
+}
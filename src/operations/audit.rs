@@ -0,0 +1,54 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, audit_log::AuditLogItemProtobufModel};
+
+const SETTINGS_CHANGED_ACTION: &str = "SettingsChanged";
+
+// Failure to persist audit log does not fail admin operation itself
+pub async fn write_audit_log(
+    app: &AppContext,
+    action: &str,
+    initiator: &str,
+    topic_id: Option<&str>,
+    parameters: String,
+) {
+    let item = AuditLogItemProtobufModel {
+        created: DateTimeAsMicroseconds::now().unix_microseconds,
+        action: action.to_string(),
+        initiator: initiator.to_string(),
+        topic_id: topic_id.map(|itm| itm.to_string()),
+        parameters,
+    };
+
+    if let Err(err) = app.audit_log.add(item).await {
+        app.logs.add_error(
+            topic_id,
+            "AuditLog",
+            format!("Can not persist audit log item {}", action),
+            Some(format!("{:?}", err)),
+        );
+
+        app.metrics_keeper.page_blob_failure("audit_log");
+    }
+}
+
+// Settings are read from the file on start, so changes are detected by comparing
+// with the parameters of the last audited start
+pub async fn audit_settings(app: &AppContext) {
+    let parameters = app.settings.get_audit_parameters();
+
+    if let Some(last) = app.audit_log.get_last(SETTINGS_CHANGED_ACTION).await {
+        if last.parameters == parameters {
+            return;
+        }
+    }
+
+    write_audit_log(
+        app,
+        SETTINGS_CHANGED_ACTION,
+        "settings file",
+        None,
+        parameters,
+    )
+    .await;
+}
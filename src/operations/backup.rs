@@ -0,0 +1,645 @@
+use std::sync::Arc;
+
+use chrono::Datelike;
+use my_service_bus::{
+    abstractions::MessageId,
+    shared::sub_page::{SubPageId, SUB_PAGE_MESSAGES_AMOUNT},
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{AppContext, APP_VERSION},
+    archive_storage::{ArchiveFileNo, ArchiveStorage},
+    backup::*,
+    file_storage::FileStorage,
+    header_index::HeaderIndexManifestProtobufModel,
+    message_pages::SubPageInner,
+    topics_snapshot::{TopicSnapshotProtobufModel, TopicsSnapshotProtobufModelV2},
+};
+
+use super::{
+    current_sub_pages_io::{ActivePages, ActiveSubPageModel},
+    OperationError,
+};
+
+// Archived sub pages are written to backup in chunks of about this size
+const ARCHIVE_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+const TOPICS_SNAPSHOT_FILE_NAME: &str = "topics-snapshot.pb";
+const ACTIVE_PAGES_FILE_NAME: &str = "active-pages.pb";
+
+// Consistent point is made the same way as before shutdown: complete sub pages are archived,
+// minute indexes and topics snapshot are flushed. Then message id of each topic is captured as a fence:
+// backup has only messages before it, even if messages keep coming while the backup is written.
+// Payloads are copied as they are stored, so restored instance must have the same encryption keys
+#[tracing::instrument(skip_all, fields(name = name))]
+pub async fn backup_persistence(
+    app: &AppContext,
+    storage: &FileStorage,
+    name: &str,
+) -> Result<BackupManifestModel, OperationError> {
+    let manifest_path = BackupManifestModel::get_manifest_path(name);
+
+    if storage.read_file(manifest_path.as_str()).await?.is_some() {
+        return Err(OperationError::BackupError(format!(
+            "Backup {} already exists in {}",
+            name,
+            storage.get_name()
+        )));
+    }
+
+    app.topics_snapshot
+        .flush_topics_snapshot_to_blob(&app.metrics_keeper)
+        .await;
+
+    for topic_data in app.topics_list.get_all().await {
+        super::gc_pages(app, topic_data.clone()).await?;
+
+        for index in topic_data.yearly_index_by_minute.get_all().await {
+            index.write_everything_before_gc().await;
+        }
+    }
+
+    let topics_snapshot = app.topics_snapshot.get().await.to_protobuf_model();
+    let active_pages = compile_fenced_active_pages(app, &topics_snapshot).await;
+
+    let mut manifest = BackupManifestModel {
+        version: BACKUP_MANIFEST_VERSION,
+        name: name.to_string(),
+        created: DateTimeAsMicroseconds::now().to_rfc3339(),
+        app_version: APP_VERSION.to_string(),
+        topics_snapshot: write_backup_file(
+            storage,
+            name,
+            TOPICS_SNAPSHOT_FILE_NAME.to_string(),
+            encode(&topics_snapshot)?,
+        )
+        .await?,
+        active_pages: write_backup_file(
+            storage,
+            name,
+            ACTIVE_PAGES_FILE_NAME.to_string(),
+            encode(&active_pages)?,
+        )
+        .await?,
+        topics: Vec::with_capacity(topics_snapshot.data.len()),
+    };
+
+    for topic in &topics_snapshot.data {
+        manifest
+            .topics
+            .push(backup_topic(app, storage, name, topic).await?);
+    }
+
+    storage
+        .write_file(
+            manifest_path.as_str(),
+            serde_json::to_vec_pretty(&manifest).unwrap(),
+        )
+        .await?;
+
+    app.logs.add_info(
+        None,
+        "Backup",
+        format!(
+            "Backup {} of {} topics and {} sub pages is written to {}",
+            name,
+            manifest.topics.len(),
+            manifest.get_sub_pages_amount(),
+            storage.get_name()
+        ),
+    );
+
+    Ok(manifest)
+}
+
+// Sub page of the fence is taken from memory or from archive if it is completed in the meantime
+async fn compile_fenced_active_pages(
+    app: &AppContext,
+    topics_snapshot: &TopicsSnapshotProtobufModelV2,
+) -> ActivePages {
+    let mut result = ActivePages {
+        sub_pages: Vec::new(),
+    };
+
+    for topic in &topics_snapshot.data {
+        let topic_id = topic.topic_id.as_str();
+        let fence = topic.get_message_id();
+        let sub_page_id: SubPageId = fence.into();
+
+        let sub_page = super::get_sub_page_to_read(app, topic_id, sub_page_id).await;
+
+        let messages = sub_page
+            .get_from_message_id(
+                sub_page_id.get_first_message_id(),
+                SUB_PAGE_MESSAGES_AMOUNT as usize,
+            )
+            .await;
+
+        let messages: Vec<_> = messages
+            .iter()
+            .filter(|msg| msg.get_message_id().get_value() < fence.get_value())
+            .collect();
+
+        if messages.is_empty() {
+            continue;
+        }
+
+        let payload = app
+            .codecs
+            .get(topic_id)
+            .encode_messages(messages.into_iter().map(|msg| msg.as_ref()));
+
+        result.sub_pages.push(ActiveSubPageModel {
+            topic_id: topic.topic_id.clone(),
+            sub_page_id: sub_page_id.get_value(),
+            payload: app.encryption.encrypt(topic_id, sub_page_id, payload),
+        });
+    }
+
+    result
+}
+
+// Sub pages from the sub page of the fence are not taken from archive. Sub page of the fence is in active pages
+async fn backup_topic(
+    app: &AppContext,
+    storage: &FileStorage,
+    name: &str,
+    topic: &TopicSnapshotProtobufModel,
+) -> Result<BackupTopicModel, OperationError> {
+    let topic_id = topic.topic_id.as_str();
+    let mut result = BackupTopicModel::new(topic_id, topic.get_message_id().get_value());
+
+    let current_sub_page_id: SubPageId = topic.get_message_id().into();
+    let last_archive_file_no: ArchiveFileNo = current_sub_page_id.into();
+
+    let mut first_year = None;
+
+    for file_no in 0..=last_archive_file_no.get_value() {
+        // Storage is pinned, so compaction does not move payloads while they are read
+        let pinned = app
+            .archive_storage_list
+            .pin(ArchiveFileNo::new(file_no), topic_id, app)
+            .await;
+
+        let pinned = match pinned {
+            Some(pinned) => pinned,
+            None => continue,
+        };
+
+        let backed_up = backup_archive_file(
+            app,
+            storage,
+            name,
+            &mut result,
+            pinned.storage.as_ref(),
+            current_sub_page_id,
+            &mut first_year,
+        )
+        .await;
+
+        app.archive_storage_list.unpin(topic_id, pinned).await;
+
+        backed_up?;
+    }
+
+    // Minute indexes exist for the years from the first archived message till now
+    let now_year = DateTimeAsMicroseconds::now().to_chrono_utc().year() as u32;
+    let first_year = first_year.unwrap_or(now_year);
+
+    for year in first_year..=now_year {
+        let content = app
+            .get_year_index_storage(topic_id, year.into())
+            .await
+            .download_content()
+            .await?;
+
+        if let Some(content) = content {
+            let path = BackupTopicModel::get_year_index_path(topic_id, year);
+
+            result.year_indexes.push(BackupYearIndexModel {
+                year,
+                file: write_backup_file(storage, name, path, content).await?,
+            });
+        }
+    }
+
+    let mut blob_names =
+        vec![crate::app::file_name_generators::generate_zstd_dictionaries_blob_name()];
+
+    // Manifest and runs of header index are copied under the lock, so they match each other
+    let lock = app.header_indexes.get_lock(topic_id).await;
+    let _lock = lock.lock().await;
+
+    let header_index_manifest = app
+        .get_header_index_manifest_storage(topic_id)
+        .await
+        .read_model::<HeaderIndexManifestProtobufModel>()
+        .await?;
+
+    if let Some(header_index_manifest) = header_index_manifest {
+        blob_names
+            .push(crate::app::file_name_generators::generate_header_index_manifest_blob_name());
+
+        for run_id in header_index_manifest.run_ids {
+            blob_names.push(
+                crate::app::file_name_generators::generate_header_index_run_blob_name(run_id),
+            );
+        }
+    }
+
+    for blob_name in blob_names {
+        let content = app
+            .get_topic_blob_storage(topic_id, blob_name.clone())
+            .await
+            .download_content()
+            .await?;
+
+        if let Some(content) = content {
+            let path = BackupTopicModel::get_blob_path(topic_id, blob_name.as_str());
+
+            result.blobs.push(BackupBlobModel {
+                blob_name,
+                file: write_backup_file(storage, name, path, content).await?,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+// Year of the first archived message is taken from the first populated sub page
+async fn backup_archive_file(
+    app: &AppContext,
+    storage: &FileStorage,
+    name: &str,
+    result: &mut BackupTopicModel,
+    archive_storage: &ArchiveStorage,
+    current_sub_page_id: SubPageId,
+    first_year: &mut Option<u32>,
+) -> Result<(), OperationError> {
+    let topic_id = result.topic_id.clone();
+    let mut chunk = BackupSubPagesProtobufModel::default();
+
+    for (sub_page_id, _) in archive_storage.get_populated_sub_pages().await {
+        if sub_page_id.get_value() >= current_sub_page_id.get_value() {
+            continue;
+        }
+
+        let payload = archive_storage
+            .read_sub_page_payload(sub_page_id)
+            .await
+            .map_err(|err| {
+                OperationError::BackupError(format!(
+                    "Can not read sub page {} of topic {}. Err: {:?}",
+                    sub_page_id.get_value(),
+                    topic_id,
+                    err
+                ))
+            })?;
+
+        if let Some(payload) = payload {
+            if first_year.is_none() {
+                *first_year = get_first_message_year(
+                    app,
+                    topic_id.as_str(),
+                    sub_page_id,
+                    payload.as_slice(),
+                )?;
+            }
+
+            chunk.sub_pages.push(BackupSubPageProtobufModel {
+                sub_page_id: sub_page_id.get_value(),
+                payload: payload.as_slice().to_vec(),
+            });
+        }
+
+        if chunk.get_payload_size() >= ARCHIVE_CHUNK_SIZE {
+            let full_chunk = std::mem::take(&mut chunk);
+            write_archive_chunk(storage, name, result, full_chunk).await?;
+        }
+    }
+
+    write_archive_chunk(storage, name, result, chunk).await
+}
+
+fn get_first_message_year(
+    app: &AppContext,
+    topic_id: &str,
+    sub_page_id: SubPageId,
+    payload: &[u8],
+) -> Result<Option<u32>, OperationError> {
+    let payload = app
+        .encryption
+        .decrypt(topic_id, sub_page_id, payload)
+        .map_err(|err| {
+            OperationError::BackupError(format!(
+                "Can not decrypt sub page {} of topic {}. Err: {:?}",
+                sub_page_id.get_value(),
+                topic_id,
+                err
+            ))
+        })?;
+
+    let sub_page =
+        SubPageInner::from_compressed_payload(sub_page_id, payload.as_ref(), &app.codecs).map_err(
+            |err| {
+                OperationError::BackupError(format!(
+                    "Can not decode sub page {} of topic {}. Err: {:?}",
+                    sub_page_id.get_value(),
+                    topic_id,
+                    err
+                ))
+            },
+        )?;
+
+    let result = sub_page
+        .get_first_and_last_message()
+        .map(|(first, _)| first.get_created().to_chrono_utc().year() as u32);
+
+    Ok(result)
+}
+
+async fn write_archive_chunk(
+    storage: &FileStorage,
+    name: &str,
+    topic: &mut BackupTopicModel,
+    chunk: BackupSubPagesProtobufModel,
+) -> Result<(), OperationError> {
+    let first_sub_page_id = match chunk.sub_pages.first() {
+        Some(sub_page) => sub_page.sub_page_id,
+        None => return Ok(()),
+    };
+
+    let path = BackupTopicModel::get_archive_chunk_path(topic.topic_id.as_str(), first_sub_page_id);
+
+    topic.sub_pages += chunk.sub_pages.len();
+
+    let file = write_backup_file(storage, name, path, encode(&chunk)?).await?;
+    topic.archive.push(file);
+
+    Ok(())
+}
+
+async fn write_backup_file(
+    storage: &FileStorage,
+    name: &str,
+    path: String,
+    content: Vec<u8>,
+) -> Result<BackupFileModel, OperationError> {
+    let result = BackupFileModel {
+        path,
+        size: content.len(),
+    };
+
+    storage
+        .write_file(
+            BackupManifestModel::get_file_path(name, &result).as_str(),
+            content,
+        )
+        .await?;
+
+    Ok(result)
+}
+
+// Backup is run in background. Its state is kept in backup jobs
+pub async fn start_backup(
+    app: Arc<AppContext>,
+    name: String,
+    target: &str,
+    initiator: &str,
+) -> Result<(), OperationError> {
+    super::check_leader(app.as_ref())?;
+
+    if !is_valid_backup_name(name.as_str()) {
+        return Err(OperationError::BackupError(format!(
+            "Invalid backup name {}. Only letters, digits, '-' and '_' are allowed",
+            name
+        )));
+    }
+
+    let storage = get_backup_storage(app.as_ref(), target)?;
+
+    let started = app
+        .backup_jobs
+        .try_start(
+            name.as_str(),
+            storage.get_name(),
+            DateTimeAsMicroseconds::now(),
+        )
+        .await;
+
+    if !started {
+        return Err(OperationError::BackupError(
+            "Backup is already running".to_string(),
+        ));
+    }
+
+    super::write_audit_log(
+        app.as_ref(),
+        "Backup",
+        initiator,
+        None,
+        format!("Name={}; Target={}", name, storage.get_name()),
+    )
+    .await;
+
+    tokio::spawn(async move {
+        let state = match backup_persistence(app.as_ref(), &storage, name.as_str()).await {
+            Ok(manifest) => BackupJobState::Finished {
+                topics: manifest.topics.len(),
+                sub_pages: manifest.get_sub_pages_amount(),
+                files: manifest.get_files().len(),
+                size: manifest.get_files().iter().map(|itm| itm.size).sum(),
+            },
+            Err(err) => {
+                app.logs.add_error(
+                    None,
+                    "Backup",
+                    format!("Backup {} failed", name),
+                    Some(format!("{:?}", err)),
+                );
+
+                BackupJobState::Failed(format!("{:?}", err))
+            }
+        };
+
+        app.backup_jobs
+            .finish(name.as_str(), state, DateTimeAsMicroseconds::now())
+            .await;
+    });
+
+    Ok(())
+}
+
+// Backup is restored only to a fresh instance. Archive files of the topics are truncated first
+// and topics snapshot is written the last, so an interrupted restore can be repeated
+#[tracing::instrument(skip_all, fields(name = name))]
+pub async fn restore_backup(
+    app: &AppContext,
+    storage: &FileStorage,
+    name: &str,
+) -> Result<BackupManifestModel, OperationError> {
+    let manifest = storage
+        .read_file(BackupManifestModel::get_manifest_path(name).as_str())
+        .await?
+        .ok_or(OperationError::BackupError(format!(
+            "Backup {} is not found in {}",
+            name,
+            storage.get_name()
+        )))?;
+
+    let manifest =
+        BackupManifestModel::parse(manifest.as_slice()).map_err(OperationError::BackupError)?;
+
+    if !app.topics_snapshot.get_topics_list().await.is_empty() {
+        return Err(OperationError::BackupError(
+            "Topics snapshot is not empty. Backup can be restored only to a fresh instance"
+                .to_string(),
+        ));
+    }
+
+    for topic in &manifest.topics {
+        let topic_id = topic.topic_id.as_str();
+
+        app.create_topic_container(topic_id).await;
+
+        truncate_archive_files(app, topic).await;
+
+        for file in &topic.archive {
+            let content = read_backup_file(storage, name, file).await?;
+            let chunk: BackupSubPagesProtobufModel = prost::Message::decode(content.as_slice())?;
+
+            for sub_page in chunk.sub_pages {
+                let sub_page_id = SubPageId::new(sub_page.sub_page_id);
+
+                app.archive_storage_list
+                    .get_or_create(sub_page_id.into(), topic_id, app)
+                    .await
+                    .write_payload(sub_page_id, sub_page.payload.as_slice())
+                    .await;
+            }
+        }
+
+        for year_index in &topic.year_indexes {
+            let content = read_backup_file(storage, name, &year_index.file).await?;
+
+            app.get_year_index_storage(topic_id, year_index.year.into())
+                .await
+                .write_content(content)
+                .await?;
+        }
+
+        for blob in &topic.blobs {
+            let content = read_backup_file(storage, name, &blob.file).await?;
+
+            app.get_topic_blob_storage(topic_id, blob.blob_name.to_string())
+                .await
+                .write_content(content)
+                .await?;
+        }
+
+        app.logs.add_info(
+            Some(topic_id),
+            "RestoreBackup",
+            format!("Restored {} sub pages", topic.sub_pages),
+        );
+    }
+
+    let active_pages = read_backup_file(storage, name, &manifest.active_pages).await?;
+    super::current_sub_pages_io::upload(app, active_pages).await;
+
+    let topics_snapshot = read_backup_file(storage, name, &manifest.topics_snapshot).await?;
+    let topics_snapshot: TopicsSnapshotProtobufModelV2 =
+        prost::Message::decode(topics_snapshot.as_slice())?;
+
+    app.topics_snapshot.rollback(topics_snapshot).await;
+
+    app.topics_snapshot
+        .flush_topics_snapshot_to_blob(&app.metrics_keeper)
+        .await;
+
+    if app
+        .topics_snapshot
+        .get_snapshot_if_there_are_changes()
+        .await
+        .is_some()
+    {
+        return Err(OperationError::BackupError(
+            "Can not write restored topics snapshot".to_string(),
+        ));
+    }
+
+    app.logs.add_info(
+        None,
+        "RestoreBackup",
+        format!(
+            "Backup {} of {} topics is restored from {}",
+            name,
+            manifest.topics.len(),
+            storage.get_name()
+        ),
+    );
+
+    Ok(manifest)
+}
+
+// Payloads are appended to archive files, so files left by a previous attempt are emptied
+async fn truncate_archive_files(app: &AppContext, topic: &BackupTopicModel) {
+    let sub_page_id: SubPageId = MessageId::new(topic.message_id).into();
+    let last_archive_file_no: ArchiveFileNo = sub_page_id.into();
+
+    for file_no in 0..=last_archive_file_no.get_value() {
+        let archive_storage = app
+            .archive_storage_list
+            .get_existing_or_open(ArchiveFileNo::new(file_no), topic.topic_id.as_str(), app)
+            .await;
+
+        if let Some(archive_storage) = archive_storage {
+            archive_storage.truncate().await;
+        }
+    }
+}
+
+async fn read_backup_file(
+    storage: &FileStorage,
+    name: &str,
+    file: &BackupFileModel,
+) -> Result<Vec<u8>, OperationError> {
+    let path = BackupManifestModel::get_file_path(name, file);
+
+    let content = storage
+        .read_file(path.as_str())
+        .await?
+        .ok_or(OperationError::BackupError(format!(
+            "Backup file {} is missing",
+            path
+        )))?;
+
+    if content.len() != file.size {
+        return Err(OperationError::BackupError(format!(
+            "Backup file {} has size {} instead of {}",
+            path,
+            content.len(),
+            file.size
+        )));
+    }
+
+    Ok(content)
+}
+
+fn encode(model: &impl prost::Message) -> Result<Vec<u8>, OperationError> {
+    let mut result = Vec::new();
+    model.encode(&mut result)?;
+    Ok(result)
+}
+
+pub fn get_backup_storage(app: &AppContext, target: &str) -> Result<FileStorage, OperationError> {
+    match app.get_backup_storage(target) {
+        Some(result) => Ok(result),
+        None => Err(OperationError::BackupError(format!(
+            "Backup target {} is not configured",
+            target
+        ))),
+    }
+}
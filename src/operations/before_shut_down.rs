@@ -3,18 +3,44 @@ use std::{sync::Arc, time::Duration};
 use crate::{app::AppContext, topic_data::TopicData};
 
 pub async fn execute_before_shutdown(app: Arc<AppContext>) {
+    // Passive instance must not overwrite data of the leader
+    if app.is_passive() {
+        app.logs.add_info(
+            None,
+            "BeforeShutDown",
+            "Instance is passive. Nothing to flush".to_string(),
+        );
+
+        crate::telemetry::shutdown();
+        return;
+    }
+
     let duration = Duration::from_secs(1);
-    println!("Waiting until we flush all the queues and messages");
+    app.logs.add_info(
+        None,
+        "BeforeShutDown",
+        "Waiting until we flush all the queues and messages".to_string(),
+    );
     tokio::time::sleep(duration).await;
 
-    app.topics_snapshot.flush_topics_snapshot_to_blob().await;
+    app.topics_snapshot
+        .flush_topics_snapshot_to_blob(&app.metrics_keeper)
+        .await;
 
-    println!("Topic snapshot is flushed");
+    app.logs.add_info(
+        None,
+        "BeforeShutDown",
+        "Topic snapshot is flushed".to_string(),
+    );
 
     let topics = app.topics_list.get_all().await;
 
     for topic_data in topics {
-        println!("Flushing data for topic {} to blob ", topic_data.topic_id);
+        app.logs.add_info(
+            Some(topic_data.topic_id.as_str()),
+            "BeforeShutDown",
+            "Flushing topic data to blob".to_string(),
+        );
         topic_data
             .yearly_index_by_minute
             .save_before_shutdown()
@@ -25,7 +51,15 @@ pub async fn execute_before_shutdown(app: Arc<AppContext>) {
 
     super::current_sub_pages_io::write(app.as_ref()).await;
 
-    println!("Application can be closed now safely");
+    super::release_leader_lease(app.as_ref()).await;
+
+    crate::telemetry::shutdown();
+
+    app.logs.add_info(
+        None,
+        "BeforeShutDown",
+        "Application can be closed now safely".to_string(),
+    );
 }
 
 pub async fn save_topic_messages_to_be_archived(app: &AppContext, topic_data: &TopicData) {
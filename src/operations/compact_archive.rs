@@ -0,0 +1,93 @@
+use my_service_bus::shared::sub_page::SubPageId;
+
+use crate::{
+    app::AppContext,
+    archive_storage::{ArchiveFileNo, ArchiveStorage},
+};
+
+// Files with less orphaned space are not worth rewriting
+const MIN_ORPHANED_SIZE_TO_COMPACT: usize = 1024 * 1024;
+
+// Every archive file of every topic is checked, not only the files opened since the start
+pub async fn compact_archive_files(app: &AppContext) {
+    for topic_id in app.topics_snapshot.get_topics_list().await {
+        let message_id = match app
+            .topics_snapshot
+            .get_current_message_id(topic_id.as_str())
+            .await
+        {
+            Some(message_id) => message_id,
+            None => continue,
+        };
+
+        let last_sub_page_id: SubPageId = message_id.into();
+        let last_archive_file_no: ArchiveFileNo = last_sub_page_id.into();
+
+        for file_no in 0..=last_archive_file_no.get_value() {
+            if app.app_states.is_shutting_down() {
+                return;
+            }
+
+            compact_archive_file(app, topic_id.as_str(), ArchiveFileNo::new(file_no)).await;
+        }
+    }
+}
+
+async fn compact_archive_file(app: &AppContext, topic_id: &str, archive_file_no: ArchiveFileNo) {
+    // Storage is pinned, so compaction and writes share the same lock
+    let pinned = app
+        .archive_storage_list
+        .pin(archive_file_no, topic_id, app)
+        .await;
+
+    let pinned = match pinned {
+        Some(pinned) => pinned,
+        None => return,
+    };
+
+    let orphaned_size = pinned.storage.get_orphaned_size().await;
+
+    if orphaned_size >= MIN_ORPHANED_SIZE_TO_COMPACT {
+        compact(app, topic_id, archive_file_no, pinned.storage.as_ref()).await;
+    }
+
+    app.archive_storage_list.unpin(topic_id, pinned).await;
+}
+
+async fn compact(
+    app: &AppContext,
+    topic_id: &str,
+    archive_file_no: ArchiveFileNo,
+    archive_storage: &ArchiveStorage,
+) {
+    match archive_storage.compact().await {
+        Ok(result) => {
+            app.archive_stats.remove(topic_id).await;
+
+            app.logs.add_info(
+                Some(topic_id),
+                "CompactArchive",
+                format!(
+                    "Archive file {} is compacted. Moved sub pages: {}. Blob size: {} -> {}",
+                    archive_file_no.get_value(),
+                    result.moved_sub_pages,
+                    result.blob_size_before,
+                    result.blob_size_after
+                ),
+            );
+        }
+        Err(err) => {
+            app.metrics_keeper.page_blob_failure("archive_compaction");
+
+            app.logs.add_error(
+                Some(topic_id),
+                "CompactArchive",
+                format!(
+                    "Can not compact archive file {}",
+                    archive_file_no.get_value()
+                ),
+                Some(format!("{:?}", err)),
+            );
+        }
+    }
+}
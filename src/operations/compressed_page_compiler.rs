@@ -5,6 +5,14 @@ use my_service_bus::shared::{page_compressor::CompressedPageBuilder, sub_page::S
 
 use crate::app::AppContext;
 
+#[tracing::instrument(
+    skip_all,
+    fields(
+        topic_id = topic_id,
+        from_message_id = from_message_id.get_value(),
+        to_message_id = to_message_id.get_value(),
+    ),
+)]
 pub async fn get_compressed_page(
     app: Arc<AppContext>,
     topic_id: &str,
@@ -21,6 +29,8 @@ pub async fn get_compressed_page(
 
     let mut sub_page_read_copy = None;
 
+    let mut bytes_read = 0;
+
     for message_id in from_message_id.get_value()..to_message_id.get_value() + 1 {
         let message_id: MessageId = message_id.into();
 
@@ -44,9 +54,12 @@ pub async fn get_compressed_page(
 
         if let Some(message) = message {
             compressed_writer.add_message(message).unwrap();
+            bytes_read += message.data.len();
         }
     }
 
+    app.metrics_keeper.bytes_read(topic_id, bytes_read);
+
     let result = compressed_writer.get_payload().unwrap();
 
     split(result.as_slice(), max_payload_size)
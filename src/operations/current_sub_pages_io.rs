@@ -42,8 +42,11 @@ pub struct ActivePages {
     pub sub_pages: Vec<ActiveSubPageModel>,
 }
 
+// Blob is deleted after restore unless it is only read by a one-off command
+#[tracing::instrument(skip_all)]
 pub async fn restore(
     app: &AppContext,
+    delete_blob: bool,
 ) -> Result<Option<Vec<(String, SubPageInner)>>, RestorePagesError> {
     let connection = app.get_storage_for_active_pages();
 
@@ -64,9 +67,25 @@ pub async fn restore(
                     let mut result = Vec::new();
 
                     for sub_page in active_pages_contract.sub_pages {
-                        let sub_page_inner_result = SubPageInner::from_compressed_payload(
+                        let payload = match app.encryption.decrypt(
+                            sub_page.topic_id.as_str(),
                             SubPageId::new(sub_page.sub_page_id),
                             sub_page.payload.as_slice(),
+                        ) {
+                            Ok(payload) => payload,
+                            Err(err) => {
+                                return RestorePagesError::Other(format!(
+                                    "Can not decrypt active sub pages data. Err: {:?}",
+                                    err
+                                ))
+                                .into_err()
+                            }
+                        };
+
+                        let sub_page_inner_result = SubPageInner::from_compressed_payload(
+                            SubPageId::new(sub_page.sub_page_id),
+                            payload.as_ref(),
+                            &app.codecs,
                         );
 
                         match sub_page_inner_result {
@@ -83,11 +102,13 @@ pub async fn restore(
                         }
                     }
 
-                    let page_blob = page_blob.clone();
+                    if delete_blob {
+                        let page_blob = page_blob.clone();
 
-                    tokio::spawn(async move {
-                        let _ = page_blob.delete().await;
-                    });
+                        tokio::spawn(async move {
+                            let _ = page_blob.delete().await;
+                        });
+                    }
 
                     return Ok(Some(result));
                 }
@@ -102,7 +123,11 @@ pub async fn restore(
         }
         Err(err) => {
             if let AzureStorageError::BlobNotFound = err {
-                println!("Blob with active pages not found. Creating empty active pages");
+                app.logs.add_info(
+                    None,
+                    "RestoreActivePages",
+                    "Blob with active pages not found. Creating empty active pages".to_string(),
+                );
                 return Ok(None);
             }
 
@@ -111,7 +136,18 @@ pub async fn restore(
     }
 }
 
+#[tracing::instrument(skip_all)]
 pub async fn write(app: &AppContext) {
+    let active_pages = compile(app).await;
+
+    let mut content_to_upload = Vec::new();
+    prost::Message::encode(&active_pages, &mut content_to_upload).unwrap();
+
+    upload(app, content_to_upload).await;
+}
+
+// Active sub page of each topic. Payloads are compressed and encrypted the same way as archived ones
+pub async fn compile(app: &AppContext) -> ActivePages {
     let topics = app.topics_list.get_all().await;
 
     let mut result: ActivePages = ActivePages {
@@ -122,33 +158,47 @@ pub async fn write(app: &AppContext) {
         let sub_page = topic.pages_list.get_active_sub_page().await;
 
         if let Some(sub_page) = sub_page {
-            let payload = sub_page.to_compressed_payload().await;
+            let codec = app.codecs.get(topic.topic_id.as_str());
+            let payload = sub_page.to_compressed_payload(codec.as_ref()).await;
 
             if let Some(payload) = payload {
                 result.sub_pages.push(ActiveSubPageModel {
                     topic_id: topic.topic_id.clone(),
                     sub_page_id: sub_page.get_id().get_value(),
-                    payload,
+                    payload: app.encryption.encrypt(
+                        topic.topic_id.as_str(),
+                        sub_page.get_id(),
+                        payload,
+                    ),
                 });
             }
         }
     }
 
-    let mut content_to_upload = Vec::new();
-    prost::Message::encode(&result, &mut content_to_upload).unwrap();
+    result
+}
 
+pub async fn upload(app: &AppContext, content_to_upload: Vec<u8>) {
     let conn_string = app.get_storage_for_active_pages();
 
     loop {
         let result = conn_string
-            .upload_block_blob("topics", ".active-pages", content_to_upload.clone())
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, content_to_upload.clone())
             .await;
 
         if result.is_ok() {
             break;
         }
 
-        println!("Can not write active pages: {:?}", result.unwrap_err());
+        app.logs.add_error(
+            None,
+            "WriteActivePages",
+            "Can not write active pages".to_string(),
+            Some(format!("{:?}", result.unwrap_err())),
+        );
+
+        // Active pages are written until it succeeds, so each failed attempt is retried
+        app.metrics_keeper.page_blob_retry("active_pages");
 
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
     }
@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use my_logger::LogEventCtx;
 use rust_extensions::StopWatch;
 
 use crate::app::AppContext;
@@ -10,26 +9,30 @@ pub async fn init(app: Arc<AppContext>) {
 
     sw.start();
 
-    restore_pages(&app).await;
+    crate::operations::audit_settings(&app).await;
+
+    restore_pages(&app, true).await;
 
     sw.pause();
 
-    my_logger::LOGGER.write_info(
-        "Initialization".to_string(),
+    app.logs.add_info(
+        None,
+        "Initialization",
         format!("Application is initialized in {:?}", sw.duration()),
-        LogEventCtx::new(),
     );
 
     app.app_states.set_initialized();
 }
 
-async fn restore_pages(app: &Arc<AppContext>) {
-    my_logger::LOGGER.write_info(
-        "Initialization".to_string(),
+pub async fn restore_pages(app: &Arc<AppContext>, delete_blob: bool) {
+    crate::operations::load_all_zstd_dictionaries(app).await;
+
+    app.logs.add_info(
+        None,
+        "Initialization",
         "Loading messages since last shutdown".to_string(),
-        LogEventCtx::new(),
     );
-    let sub_pages = crate::operations::current_sub_pages_io::restore(&app)
+    let sub_pages = crate::operations::current_sub_pages_io::restore(&app, delete_blob)
         .await
         .unwrap();
 
@@ -42,23 +45,22 @@ async fn restore_pages(app: &Arc<AppContext>) {
 
             sw.pause();
 
-            my_logger::LOGGER.write_info(
-                "Initialization".to_string(),
+            app.logs.add_info(
+                Some(topic_id.as_str()),
+                "Initialization",
                 format!(
-                    "Loaded sub page {} for topic {}in {}",
+                    "Loaded sub page {} in {}",
                     sub_page_inner.sub_page_id.get_value(),
-                    topic_id,
                     sw.duration_as_string()
                 ),
-                LogEventCtx::new(),
             );
             topic_data.pages_list.insert(sub_page_inner).await;
         }
     } else {
-        my_logger::LOGGER.write_info(
-            "Initialization".to_string(),
-            format!("No sub page data loaded"),
-            LogEventCtx::new(),
+        app.logs.add_info(
+            None,
+            "Initialization",
+            "No sub page data loaded".to_string(),
         );
     }
 }
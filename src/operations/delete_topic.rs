@@ -1,7 +1,10 @@
+use my_service_bus::abstractions::MessageId;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 use serde::*;
 
-use crate::app::AppContext;
+use crate::{app::AppContext, replication::ReplicationEventData};
+
+use super::OperationError;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TopicSoftDeleteMetadataBlobModel {
@@ -9,18 +12,153 @@ pub struct TopicSoftDeleteMetadataBlobModel {
     pub delete_after: String,
 }
 
-pub async fn delete_topic(app: &AppContext, topic_id: &str, delete_after: DateTimeAsMicroseconds) {
-    let message_id = app.topics_snapshot.get_current_message_id(topic_id).await;
+pub struct DeleteTopicResult {
+    pub topic_id: String,
+    pub message_id: MessageId,
+    pub gc_after: DateTimeAsMicroseconds,
+    pub dry_run: bool,
+}
+
+// Topic is only marked as deleted. It is purged by GC after gc_after and can be restored until then
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn delete_topic(
+    app: &AppContext,
+    topic_id: &str,
+    gc_after: DateTimeAsMicroseconds,
+    dry_run: bool,
+    initiator: &str,
+) -> Result<DeleteTopicResult, OperationError> {
+    super::check_leader(app)?;
+
+    let message_id = match app.topics_snapshot.get_current_message_id(topic_id).await {
+        Some(message_id) => message_id,
+        None => return Err(OperationError::TopicNotFound(topic_id.to_string())),
+    };
+
+    let result = DeleteTopicResult {
+        topic_id: topic_id.to_string(),
+        message_id,
+        gc_after,
+        dry_run,
+    };
+
+    if dry_run {
+        app.logs.add_info(
+            Some(topic_id),
+            "DeleteTopic",
+            format!(
+                "Dry run of topic deletion by {}. Message id is {}. Topic would be purged after {}",
+                initiator,
+                message_id.get_value(),
+                gc_after.to_rfc3339()
+            ),
+        );
 
-    if message_id.is_none() {
-        panic!("Topic {} not found", topic_id)
+        return Ok(result);
     }
 
-    app.topics_list.delete(topic_id).await;
+    mark_topic_deleted(app, topic_id, message_id, gc_after).await;
+
+    super::publish_replication_event(
+        app,
+        ReplicationEventData::DeleteTopic {
+            topic_id: topic_id.to_string(),
+            message_id: message_id.get_value(),
+            gc_after: gc_after.unix_microseconds,
+        },
+    );
+
+    app.logs.add_warning(
+        Some(topic_id),
+        "DeleteTopic",
+        format!(
+            "Topic is deleted by {}. Message id is {}. Topic is purged after {}",
+            initiator,
+            message_id.get_value(),
+            gc_after.to_rfc3339()
+        ),
+    );
+
+    super::write_audit_log(
+        app,
+        "DeleteTopic",
+        initiator,
+        Some(topic_id),
+        format!(
+            "MessageId={}; GcAfter={}",
+            message_id.get_value(),
+            gc_after.to_rfc3339()
+        ),
+    )
+    .await;
+
+    Ok(result)
+}
 
-    let message_id = message_id.unwrap();
+// Secondary marks the topic deleted with the message id and the purge date of the primary
+pub async fn mark_topic_deleted(
+    app: &AppContext,
+    topic_id: &str,
+    message_id: MessageId,
+    gc_after: DateTimeAsMicroseconds,
+) {
+    app.topics_list.delete(topic_id).await;
 
     app.topics_snapshot
-        .add_deleted_topic(topic_id, message_id, delete_after)
+        .add_deleted_topic(topic_id, message_id, gc_after)
+        .await;
+}
+
+// Topic is removed from snapshot only after its containers are deleted,
+// so purge is retried by the next GC round if storage is not available
+pub async fn purge_deleted_topics(app: &AppContext, now: DateTimeAsMicroseconds) {
+    let to_purge = app.topics_snapshot.get_topics_to_purge(now).await;
+
+    for deleted_topic in to_purge {
+        let topic_id = deleted_topic.topic_id.as_str();
+
+        if let Err(err) = app.delete_topic_containers(topic_id).await {
+            app.logs.add_error(
+                Some(topic_id),
+                "PurgeTopic",
+                "Can not delete topic containers. Purge is retried later".to_string(),
+                Some(format!("{:?}", err)),
+            );
+            continue;
+        }
+
+        app.archive_storage_list.remove_topic(topic_id).await;
+        app.archive_stats.remove(topic_id).await;
+
+        if app
+            .topics_snapshot
+            .remove_purged_topic(topic_id)
+            .await
+            .is_none()
+        {
+            continue;
+        }
+
+        // Topic with the same id can be created again after purge
+        app.topics_list.restore(topic_id).await;
+        app.topics_list.remove(topic_id).await;
+
+        app.logs.add_warning(
+            Some(topic_id),
+            "PurgeTopic",
+            format!(
+                "Topic is purged together with its archive, minute index and sub page blobs. Message id was {}",
+                deleted_topic.message_id
+            ),
+        );
+
+        super::write_audit_log(
+            app,
+            "PurgeTopic",
+            "GC",
+            Some(topic_id),
+            format!("MessageId={}", deleted_topic.message_id),
+        )
         .await;
+    }
 }
@@ -1,7 +1,10 @@
 use my_azure_storage_sdk::AzureStorageError;
 use zip::result::ZipError;
 
-use crate::message_pages::PageOperationError;
+use crate::{
+    file_storage::FileStorageError, message_pages::PageOperationError,
+    topics_snapshot::SnapshotAnomaly,
+};
 
 #[derive(Debug)]
 pub enum OperationError {
@@ -10,6 +13,36 @@ pub enum OperationError {
     ProtobufDecodeError(prost::DecodeError),
     ProtobufEncodeError(prost::EncodeError),
     ZipError(ZipError),
+    SnapshotVersionNotFound(i64),
+    InvalidSnapshot(Vec<SnapshotAnomaly>),
+    AzureStorageError(AzureStorageError),
+    IoError(std::io::Error),
+    HeaderIndexNotFound(String),
+    HeaderIndexRunNotFound(i64),
+    ParquetExportError(String),
+    FileStorageError(FileStorageError),
+    BackupError(String),
+    NotLeader,
+    SecondaryReplica,
+    ReplicationError(String),
+}
+
+impl From<FileStorageError> for OperationError {
+    fn from(src: FileStorageError) -> Self {
+        Self::FileStorageError(src)
+    }
+}
+
+impl From<std::io::Error> for OperationError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+impl From<AzureStorageError> for OperationError {
+    fn from(src: AzureStorageError) -> Self {
+        Self::AzureStorageError(src)
+    }
 }
 
 impl From<PageOperationError> for OperationError {
@@ -7,6 +7,7 @@ use crate::app::AppContext;
 
 use super::OperationError;
 
+#[tracing::instrument(skip_all, fields(topic_id = topic_id, message_id = message_id.get_value()))]
 pub async fn get_message_by_id(
     app: &AppContext,
     topic_id: &str,
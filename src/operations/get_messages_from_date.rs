@@ -1,72 +1,87 @@
 use std::sync::Arc;
 
-use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use my_service_bus::{abstractions::MessageId, shared::protobuf_models::MessageProtobufModel};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::{
     app::AppContext,
     index_by_minute::{MinuteWithinYear, YearlyIndexByMinute},
     topic_data::TopicData,
+    typing::Year,
 };
 
 use super::OperationError;
 
+#[tracing::instrument(skip_all, fields(topic_id = topic_id, max_amount = max_amount))]
 pub async fn get_messages_from_date(
     app: &AppContext,
     topic_id: &str,
     get_messages_from_date: DateTimeAsMicroseconds,
     max_amount: usize,
 ) -> Result<Vec<Arc<MessageProtobufModel>>, OperationError> {
-    let (minute, year) = app
-        .index_by_minute_utils
-        .get_minute_within_the_year(get_messages_from_date);
-
     let topic_data = super::topics::get_topic(app, topic_id).await?;
 
-    let now = DateTimeAsMicroseconds::now();
-
-    let mut yearly_index = topic_data.yearly_index_by_minute.get(year, Some(now)).await;
-
-    if yearly_index.is_none() {
-        yearly_index = app.try_open_index_by_minute(topic_id, year).await;
-    }
+    let message_id = get_message_id_by_date(app, topic_data.as_ref(), get_messages_from_date).await;
 
-    if yearly_index.is_none() {
+    if message_id.is_none() {
         return Ok(vec![]);
     }
 
-    let yearly_index = yearly_index.unwrap();
+    let message_id = message_id.unwrap();
 
-    topic_data
-        .yearly_index_by_minute
-        .add(year, yearly_index.clone())
-        .await;
+    let page_id = message_id.into();
 
-    let result =
-        read_from_yearly_index(app, topic_data.as_ref(), &yearly_index, minute, max_amount).await;
+    let page = crate::operations::get_page_to_read(app, topic_data.as_ref(), page_id).await;
 
-    return result;
+    let result = page.get_from_message_id(message_id, max_amount).await;
+    Ok(result)
 }
 
-async fn read_from_yearly_index(
+// First message id of the minute the date belongs to. If there are no messages within the minute -
+// first message id of the next indexed minute, up to the current year
+pub async fn get_message_id_by_date(
     app: &AppContext,
     topic_data: &TopicData,
-    yearly_index: &YearlyIndexByMinute,
-    minute: MinuteWithinYear,
-    max_amount: usize,
-) -> Result<Vec<Arc<MessageProtobufModel>>, OperationError> {
-    let message_id = yearly_index.get_message_id(minute).await;
+    date: DateTimeAsMicroseconds,
+) -> Option<MessageId> {
+    let now = DateTimeAsMicroseconds::now();
 
-    if message_id.is_none() {
-        return Ok(vec![]);
+    let (mut minute, year) = app.index_by_minute_utils.get_minute_within_the_year(date);
+    let (_, current_year) = app.index_by_minute_utils.get_minute_within_the_year(now);
+
+    for year in year.get_value()..=current_year.get_value() {
+        if let Some(yearly_index) = get_yearly_index(app, topic_data, year.into(), now).await {
+            if let Some(message_id) = yearly_index.find_message_id_from(minute).await {
+                return Some(message_id);
+            }
+        }
+
+        minute = MinuteWithinYear::new(0);
     }
 
-    let message_id = message_id.unwrap();
+    None
+}
 
-    let page_id = message_id.into();
+async fn get_yearly_index(
+    app: &AppContext,
+    topic_data: &TopicData,
+    year: Year,
+    now: DateTimeAsMicroseconds,
+) -> Option<Arc<YearlyIndexByMinute>> {
+    let mut yearly_index = topic_data.yearly_index_by_minute.get(year, Some(now)).await;
 
-    let page = crate::operations::get_page_to_read(app, topic_data, page_id).await;
+    if yearly_index.is_none() {
+        yearly_index = app
+            .try_open_index_by_minute(topic_data.topic_id.as_str(), year)
+            .await;
+    }
 
-    let result = page.get_from_message_id(message_id, max_amount).await;
-    Ok(result)
+    let yearly_index = yearly_index?;
+
+    topic_data
+        .yearly_index_by_minute
+        .add(year, yearly_index.clone())
+        .await;
+
+    Some(yearly_index)
 }
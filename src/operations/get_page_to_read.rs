@@ -2,17 +2,28 @@ use std::sync::Arc;
 
 use my_service_bus::shared::sub_page::SubPageId;
 
-use crate::{app::AppContext, message_pages::SubPage, topic_data::TopicData};
+use crate::{
+    app::{AppContext, SubPageRestoreResult},
+    message_pages::SubPage,
+    topic_data::TopicData,
+};
 
+#[tracing::instrument(
+    skip_all,
+    fields(topic_id = topic_data.topic_id.as_str(), sub_page_id = sub_page_id.get_value()),
+)]
 pub async fn get_page_to_read(
     app: &AppContext,
     topic_data: &TopicData,
     sub_page_id: SubPageId,
 ) -> Arc<SubPage> {
+    let mut restore_result = SubPageRestoreResult::Hit;
+
     loop {
         let page = topic_data.pages_list.get(sub_page_id).await;
 
         if let Some(page) = page {
+            app.metrics_keeper.sub_page_restored(restore_result);
             return page;
         };
 
@@ -22,13 +33,17 @@ pub async fn get_page_to_read(
         match sub_page {
             Ok(sub_page) => {
                 topic_data.pages_list.restore_from_archive(sub_page).await;
+                restore_result = SubPageRestoreResult::Miss;
             }
             Err(err) => {
-                println!(
-                    "Can not restore sub page {}  for topic {}. Err: {:?}",
-                    sub_page_id, topic_data.topic_id, err
+                app.logs.add_error(
+                    Some(topic_data.topic_id.as_str()),
+                    "GetPageToRead",
+                    format!("Can not restore sub page {}", sub_page_id),
+                    Some(format!("{:?}", err)),
                 );
                 topic_data.pages_list.add_missing(sub_page_id).await;
+                restore_result = SubPageRestoreResult::Missing;
             }
         }
     }
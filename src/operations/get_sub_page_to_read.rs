@@ -1,13 +1,14 @@
 use std::sync::Arc;
 
-use my_logger::LogEventCtx;
 use my_service_bus::shared::sub_page::SubPageId;
+use rust_extensions::StopWatch;
 
 use crate::{
-    app::AppContext,
+    app::{AppContext, SubPageRestoreResult},
     message_pages::{SubPage, SubPageInner},
 };
 
+#[tracing::instrument(skip_all, fields(topic_id = topic_id, sub_page_id = sub_page_id.get_value()))]
 pub async fn get_sub_page_to_read(
     app: &AppContext,
     topic_id: &str,
@@ -16,6 +17,8 @@ pub async fn get_sub_page_to_read(
     match read(app, topic_id, sub_page_id).await {
         Some(sub_page) => sub_page,
         None => {
+            app.metrics_keeper
+                .sub_page_restored(SubPageRestoreResult::Missing);
             let sub_page = SubPage::create_missing(sub_page_id);
             Arc::new(sub_page)
         }
@@ -26,6 +29,8 @@ async fn read(app: &AppContext, topic_id: &str, sub_page_id: SubPageId) -> Optio
     let topic = app.topics_list.get(topic_id).await?;
 
     if let Some(sub_page) = topic.pages_list.get(sub_page_id).await {
+        app.metrics_keeper
+            .sub_page_restored(SubPageRestoreResult::Hit);
         return Some(sub_page);
     }
 
@@ -36,31 +41,53 @@ async fn read(app: &AppContext, topic_id: &str, sub_page_id: SubPageId) -> Optio
         .try_get_or_open(archive_file_no, topic_id, app)
         .await?;
 
+    let mut sw = StopWatch::new();
+    sw.start();
+
     let payload = archive_storage.read_sub_page_payload(sub_page_id).await;
 
+    sw.pause();
+
+    app.metrics_keeper.observe_archive_read(sw.duration());
+
     match payload {
         Ok(payload) => {
-            let sub_page = SubPageInner::from_compressed_payload(sub_page_id, payload?.as_slice());
+            let payload = payload?;
 
-            if let Err(err) = &sub_page {
-                my_logger::LOGGER.write_warning(
-                    "get_sub_page_to_read",
-                    format!("{:?}", err),
-                    LogEventCtx::new().add("topicId", topic_id),
-                );
-            }
+            let sub_page = app
+                .encryption
+                .decrypt(topic_id, sub_page_id, payload.as_slice())
+                .map_err(|err| format!("{:?}", err))
+                .and_then(|payload| {
+                    SubPageInner::from_compressed_payload(
+                        sub_page_id,
+                        payload.as_ref(),
+                        &app.codecs,
+                    )
+                    .map_err(|err| format!("{:?}", err))
+                });
 
-            let sub_page = sub_page.unwrap();
+            // Unreadable payload is reported as a missing sub page
+            let sub_page = match sub_page {
+                Ok(sub_page) => sub_page,
+                Err(err) => {
+                    app.logs
+                        .add_warning(Some(topic_id), "get_sub_page_to_read", err);
+                    return None;
+                }
+            };
+
+            app.metrics_keeper
+                .sub_page_restored(SubPageRestoreResult::Miss);
 
             let sub_page = Arc::new(SubPage::restore_from_archive(sub_page));
             Some(sub_page)
         }
         Err(err) => {
-            my_logger::LOGGER.write_warning(
-                "get_sub_page_to_read",
-                format!("{:?}", err),
-                LogEventCtx::new().add("topicId", topic_id),
-            );
+            app.metrics_keeper.page_blob_failure("archive_read");
+
+            app.logs
+                .add_warning(Some(topic_id), "get_sub_page_to_read", format!("{:?}", err));
             None
         }
     }
@@ -0,0 +1,214 @@
+use my_service_bus::{abstractions::MessageId, shared::sub_page::SubPageId};
+
+use crate::{
+    app::AppContext,
+    header_index::{HeaderIndexManifestProtobufModel, HeaderIndexRunProtobufModel},
+    message_pages::SubPage,
+};
+
+use super::OperationError;
+
+const MIN_RUNS_TO_MERGE: usize = 8;
+
+// Each archived sub page adds a run to the index. Sub pages which are not archived yet are not indexed
+#[tracing::instrument(skip_all, fields(topic_id = topic_id, sub_page_id = sub_page.get_id().get_value()))]
+pub async fn index_archived_sub_page(app: &AppContext, topic_id: &str, sub_page: &SubPage) {
+    let header_key = match app.header_indexes.get_header_key(topic_id) {
+        Some(header_key) => header_key,
+        None => return,
+    };
+
+    let sub_page_id: SubPageId = sub_page.get_id();
+    let messages = sub_page.get_all_messages().await;
+
+    let run = HeaderIndexRunProtobufModel::from_messages(
+        header_key,
+        (sub_page_id.get_first_message_id().get_value()
+            ..=sub_page_id.get_last_message_id().get_value())
+            .filter_map(|message_id| messages.get(message_id.into()))
+            .map(|msg| msg.as_ref()),
+    );
+
+    if run.entries.is_empty() {
+        return;
+    }
+
+    if let Err(err) = append_run(app, topic_id, header_key, &run).await {
+        app.logs.add_error(
+            Some(topic_id),
+            "IndexArchivedSubPage",
+            format!("Can not index sub page {}", sub_page_id.get_value()),
+            Some(format!("{:?}", err)),
+        );
+    }
+}
+
+async fn append_run(
+    app: &AppContext,
+    topic_id: &str,
+    header_key: &str,
+    run: &HeaderIndexRunProtobufModel,
+) -> Result<(), OperationError> {
+    let lock = app.header_indexes.get_lock(topic_id).await;
+    let _lock = lock.lock().await;
+
+    let manifest_storage = app.get_header_index_manifest_storage(topic_id).await;
+
+    let mut manifest = manifest_storage
+        .read_model::<HeaderIndexManifestProtobufModel>()
+        .await?
+        .unwrap_or_default();
+
+    // Header key is changed in settings. Index of the previous one is dropped
+    if manifest.header_key != header_key {
+        let run_ids = manifest.clear_runs();
+        delete_runs(app, topic_id, run_ids.as_slice()).await;
+        manifest.header_key = header_key.to_string();
+    }
+
+    let run_id = manifest.next_run_id;
+    manifest.next_run_id += 1;
+
+    app.get_header_index_run_storage(topic_id, run_id)
+        .await
+        .write_topics_snapshot(run)
+        .await?;
+
+    manifest.add_run(run_id, run.entries.len());
+
+    manifest_storage.write_topics_snapshot(&manifest).await?;
+
+    Ok(())
+}
+
+// Runs of similar size are merged, so big runs are not rewritten on each merge.
+// Merged run is written and referenced by manifest before merged runs are deleted
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn merge_header_index(
+    app: &AppContext,
+    topic_id: &str,
+) -> Result<Option<usize>, OperationError> {
+    let header_key = match app.header_indexes.get_header_key(topic_id) {
+        Some(header_key) => header_key,
+        None => return Ok(None),
+    };
+
+    let lock = app.header_indexes.get_lock(topic_id).await;
+    let _lock = lock.lock().await;
+
+    let manifest_storage = app.get_header_index_manifest_storage(topic_id).await;
+
+    let mut manifest = match manifest_storage
+        .read_model::<HeaderIndexManifestProtobufModel>()
+        .await?
+    {
+        Some(manifest) => manifest,
+        None => return Ok(None),
+    };
+
+    if manifest.header_key != header_key {
+        return Ok(None);
+    }
+
+    let merged_run_ids = match manifest.get_runs_to_merge(MIN_RUNS_TO_MERGE) {
+        Some(run_ids) => run_ids,
+        None => return Ok(None),
+    };
+
+    let mut runs = Vec::with_capacity(merged_run_ids.len());
+
+    for run_id in &merged_run_ids {
+        runs.push(read_run(app, topic_id, *run_id).await?);
+    }
+
+    let merged = HeaderIndexRunProtobufModel::merge(runs.into_iter());
+
+    let run_id = manifest.next_run_id;
+    manifest.next_run_id += 1;
+
+    app.get_header_index_run_storage(topic_id, run_id)
+        .await
+        .write_topics_snapshot(&merged)
+        .await?;
+
+    manifest.replace_runs(merged_run_ids.as_slice(), run_id, merged.entries.len());
+
+    manifest_storage.write_topics_snapshot(&manifest).await?;
+
+    delete_runs(app, topic_id, merged_run_ids.as_slice()).await;
+
+    Ok(Some(merged_run_ids.len()))
+}
+
+// Returns candidates. Messages have to be checked, since different values can have the same hash.
+// Lookup holds the lock of the topic, so runs are not deleted by merge while they are read
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn lookup_header_index(
+    app: &AppContext,
+    topic_id: &str,
+    value: &str,
+) -> Result<Vec<MessageId>, OperationError> {
+    let header_key = app
+        .header_indexes
+        .get_header_key(topic_id)
+        .ok_or(OperationError::HeaderIndexNotFound(topic_id.to_string()))?;
+
+    let lock = app.header_indexes.get_lock(topic_id).await;
+    let _lock = lock.lock().await;
+
+    let manifest = app
+        .get_header_index_manifest_storage(topic_id)
+        .await
+        .read_model::<HeaderIndexManifestProtobufModel>()
+        .await?
+        .unwrap_or_default();
+
+    if manifest.header_key != header_key {
+        return Ok(vec![]);
+    }
+
+    let hash = crate::header_index::hash_header_value(value);
+
+    let mut result = Vec::new();
+
+    for run_id in &manifest.run_ids {
+        let run = read_run(app, topic_id, *run_id).await?;
+        result.extend(run.find(hash));
+    }
+
+    result.sort();
+    result.dedup();
+
+    Ok(result.into_iter().map(MessageId::new).collect())
+}
+
+// Run referenced by manifest must exist. Otherwise the index misses messages
+async fn read_run(
+    app: &AppContext,
+    topic_id: &str,
+    run_id: i64,
+) -> Result<HeaderIndexRunProtobufModel, OperationError> {
+    app.get_header_index_run_storage(topic_id, run_id)
+        .await
+        .read_model::<HeaderIndexRunProtobufModel>()
+        .await?
+        .ok_or(OperationError::HeaderIndexRunNotFound(run_id))
+}
+
+async fn delete_runs(app: &AppContext, topic_id: &str, run_ids: &[i64]) {
+    for run_id in run_ids {
+        let result = app
+            .get_header_index_run_storage(topic_id, *run_id)
+            .await
+            .delete()
+            .await;
+
+        if let Err(err) = result {
+            app.logs.add_warning(
+                Some(topic_id),
+                "DeleteHeaderIndexRun",
+                format!("Can not delete header index run {}. Err: {:?}", run_id, err),
+            );
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     app::AppContext, index_by_minute::MinuteWithinYear, topic_data::TopicData, typing::Year,
 };
 
+#[tracing::instrument(skip_all, fields(topic_id = topic_data.topic_id.as_str()))]
 pub async fn new_messages(
     app: &AppContext,
     topic_data: &TopicData,
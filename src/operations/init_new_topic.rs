@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::{app::AppContext, topic_data::TopicData};
 
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
 pub async fn init_new_topic(app: &AppContext, topic_id: &str) -> Option<Arc<TopicData>> {
     if app.topics_list.create_topic_data(topic_id).await {
         app.create_topic_container(topic_id).await;
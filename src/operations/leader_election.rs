@@ -0,0 +1,162 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{app::AppContext, leader_lease::LeaderLease};
+
+use super::OperationError;
+
+// Instance without leader lease is initialized at once. Otherwise it stays passive: gRPC calls
+// are rejected, timers do not write to storage and topics snapshot is reloaded from the storage.
+// Data is initialized only after the lease is acquired
+pub async fn run_leader_election(app: Arc<AppContext>) {
+    let leader_lease = match &app.leader_lease {
+        Some(leader_lease) => leader_lease,
+        None => {
+            super::data_initializer::init(app.clone()).await;
+            return;
+        }
+    };
+
+    app.logs.add_info(
+        None,
+        "LeaderLease",
+        format!(
+            "Instance {} is passive until it acquires the lease in {}",
+            leader_lease.get_instance_id(),
+            leader_lease.get_storage_name()
+        ),
+    );
+
+    let mut is_leader = false;
+
+    // Leader keeps renewing the lease while it flushes data before shutdown, until it releases the lease
+    while !leader_lease.is_released() {
+        if is_leader {
+            renew_lease(app.as_ref(), leader_lease).await;
+        } else {
+            if app.app_states.is_shutting_down() {
+                return;
+            }
+
+            is_leader = try_take_over(&app, leader_lease).await;
+        }
+
+        tokio::time::sleep(leader_lease.get_renew_interval()).await;
+    }
+}
+
+async fn try_take_over(app: &Arc<AppContext>, leader_lease: &LeaderLease) -> bool {
+    match leader_lease.try_acquire().await {
+        Ok(true) => {}
+        Ok(false) => {
+            if let Err(err) = app.topics_snapshot.reload().await {
+                app.logs.add_warning(
+                    None,
+                    "LeaderLease",
+                    format!("Can not reload topics snapshot. Err: {:?}", err),
+                );
+            }
+
+            return false;
+        }
+        Err(err) => {
+            app.logs.add_warning(
+                None,
+                "LeaderLease",
+                format!("Can not acquire the lease. Err: {:?}", err),
+            );
+
+            return false;
+        }
+    }
+
+    app.logs.add_info(
+        None,
+        "LeaderLease",
+        format!(
+            "Instance {} acquired the lease and becomes the leader",
+            leader_lease.get_instance_id()
+        ),
+    );
+
+    // Previous leader could change the snapshot after the last reload
+    while let Err(err) = app.topics_snapshot.reload().await {
+        if !leader_lease.is_leader() {
+            stop_lost_leader(
+                app.as_ref(),
+                format!(
+                    "Lease expired while topics snapshot was reloaded. Err: {:?}",
+                    err
+                ),
+            );
+        }
+
+        app.logs.add_warning(
+            None,
+            "LeaderLease",
+            format!("Can not reload topics snapshot. Err: {:?}", err),
+        );
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    super::data_initializer::init(app.clone()).await;
+
+    true
+}
+
+async fn renew_lease(app: &AppContext, leader_lease: &LeaderLease) {
+    match leader_lease.renew().await {
+        Ok(true) => {}
+        Ok(false) => stop_lost_leader(app, "Lease is taken over by another instance".to_string()),
+        Err(err) => {
+            if !leader_lease.is_leader() {
+                stop_lost_leader(app, format!("Lease is expired. Err: {:?}", err));
+            }
+
+            app.logs.add_warning(
+                None,
+                "LeaderLease",
+                format!("Can not renew the lease. Err: {:?}", err),
+            );
+        }
+    }
+}
+
+// Data in memory is not flushed, since the new leader writes the same blobs
+fn stop_lost_leader(app: &AppContext, reason: String) -> ! {
+    app.logs.add_error(
+        None,
+        "LeaderLease",
+        "Leadership is lost. Stopping without flushing".to_string(),
+        Some(reason),
+    );
+
+    std::process::exit(1);
+}
+
+// Administrative writes are served by the active primary only
+pub fn check_leader(app: &AppContext) -> Result<(), OperationError> {
+    if app.is_passive() {
+        return Err(OperationError::NotLeader);
+    }
+
+    if app.is_secondary() {
+        return Err(OperationError::SecondaryReplica);
+    }
+
+    Ok(())
+}
+
+// Leader flushes everything before it releases the lease. Passive instance has nothing to flush
+pub async fn release_leader_lease(app: &AppContext) {
+    if let Some(leader_lease) = &app.leader_lease {
+        if let Err(err) = leader_lease.release().await {
+            app.logs.add_error(
+                None,
+                "LeaderLease",
+                "Can not release the lease".to_string(),
+                Some(format!("{:?}", err)),
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod archive_io;
+mod archive_stats;
 pub mod compressed_page_compiler;
 mod current_sub_pages_io;
 pub mod data_initializer;
@@ -19,6 +20,7 @@ mod init_new_topic;
 pub mod before_shut_down;
 mod new_messages;
 mod topics;
+pub use archive_stats::*;
 pub use delete_topic::*;
 pub use error::*;
 pub use gc_pages::*;
@@ -35,3 +37,25 @@ pub use send_messages_to_channel::*;
 
 mod restore_topic;
 pub use restore_topic::*;
+mod save_topics_snapshot;
+pub use save_topics_snapshot::*;
+mod topics_snapshot_history;
+pub use topics_snapshot_history::*;
+mod audit;
+pub use audit::*;
+mod zstd_dictionaries;
+pub use zstd_dictionaries::*;
+mod compact_archive;
+pub use compact_archive::*;
+mod search_messages;
+pub use search_messages::*;
+mod header_index;
+pub use header_index::*;
+mod parquet_export;
+pub use parquet_export::*;
+mod backup;
+pub use backup::*;
+mod leader_election;
+pub use leader_election::*;
+mod replication;
+pub use replication::*;
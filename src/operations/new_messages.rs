@@ -2,15 +2,30 @@ use std::collections::BTreeMap;
 
 use my_service_bus::shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId};
 
-use crate::app::AppContext;
+use crate::{app::AppContext, replication::ReplicationEventData};
 
+#[tracing::instrument(skip_all, fields(topic_id = topic_id.as_str()))]
 pub async fn new_messages(
     app: &AppContext,
     topic_id: String,
     messages_by_sub_page: BTreeMap<i64, Vec<MessageProtobufModel>>,
 ) {
+    // Messages are published to secondaries once they are accepted in memory
+    let replicated_messages: Option<Vec<MessageProtobufModel>> =
+        app.replication_hub.as_ref().map(|_| {
+            messages_by_sub_page
+                .values()
+                .flat_map(|itm| itm.iter().cloned())
+                .collect()
+        });
+
     let topic_data = crate::operations::get_topic_data_to_write(app, topic_id.as_str()).await;
     for (sub_page_id, messages) in messages_by_sub_page {
+        let bytes: usize = messages.iter().map(|msg| msg.data.len()).sum();
+
+        app.metrics_keeper
+            .messages_persisted(topic_id.as_str(), messages.len(), bytes);
+
         let sub_page_id = SubPageId::new(sub_page_id);
         let page = topic_data
             .get_sub_page_to_publish_messages(sub_page_id)
@@ -19,6 +34,12 @@ pub async fn new_messages(
         crate::operations::index_by_minute::new_messages(app, &topic_data, messages.as_slice())
             .await;
 
-        page.new_messages(messages).await;
+        page.new_messages(topic_id.as_str(), messages).await;
+    }
+
+    if let (Some(replication_hub), Some(messages)) =
+        (app.replication_hub.as_ref(), replicated_messages)
+    {
+        replication_hub.publish(ReplicationEventData::NewMessages { topic_id, messages });
     }
 }
@@ -0,0 +1,354 @@
+use std::sync::Arc;
+
+use my_service_bus::{
+    abstractions::MessageId,
+    shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId},
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    app::AppContext,
+    file_storage::{FileStorage, FileStorageWriter},
+    parquet_export::{MessagesParquetWriter, ParquetExportJobState, ParquetExportState},
+};
+
+use super::{OperationError, SearchMessagesFilter};
+
+const EXPORT_CHANNEL_SIZE: usize = 1000;
+
+// Row groups are appended to the target while the file is written. Big ranges are split into files of about this size
+const MAX_FILE_SIZE: usize = 256 * 1024 * 1024;
+
+pub struct ParquetExportRequest {
+    pub topic_id: String,
+    pub from_message_id: Option<MessageId>,
+    pub to_message_id: Option<MessageId>,
+    pub from_date: Option<DateTimeAsMicroseconds>,
+    pub to_date: Option<DateTimeAsMicroseconds>,
+    // Continues from the last message id exported to the target
+    pub incremental: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ParquetExportResult {
+    pub files: Vec<String>,
+    pub exported: usize,
+    pub last_message_id: Option<i64>,
+}
+
+// Only sub pages before the sub page of the current message id are exported, since the rest of them
+// can still get messages and incremental export would skip them
+#[tracing::instrument(skip_all, fields(topic_id = request.topic_id.as_str()))]
+pub async fn export_topic_to_parquet(
+    app: &AppContext,
+    request: &ParquetExportRequest,
+    target: &FileStorage,
+) -> Result<ParquetExportResult, OperationError> {
+    let topic_id = request.topic_id.as_str();
+
+    let current_message_id = app
+        .topics_snapshot
+        .get_current_message_id(topic_id)
+        .await
+        .ok_or(OperationError::TopicNotFound(topic_id.to_string()))?;
+
+    let current_sub_page_id: SubPageId = current_message_id.into();
+    let last_complete_message_id = current_sub_page_id.get_first_message_id().get_value() - 1;
+
+    let mut from_message_id = match request.from_message_id {
+        Some(message_id) => message_id.get_value(),
+        None => 0,
+    };
+
+    if request.incremental {
+        if let Some(state) = read_export_state(target, topic_id).await? {
+            from_message_id = from_message_id.max(state.last_message_id + 1);
+        }
+    }
+
+    let to_message_id = match request.to_message_id {
+        Some(message_id) => message_id.get_value().min(last_complete_message_id),
+        None => last_complete_message_id,
+    };
+
+    if from_message_id > to_message_id {
+        return Ok(ParquetExportResult::default());
+    }
+
+    let filter = SearchMessagesFilter {
+        from_message_id: Some(MessageId::new(from_message_id)),
+        to_message_id: Some(MessageId::new(to_message_id)),
+        from_date: request.from_date,
+        to_date: request.to_date,
+        predicates: vec![],
+        json_path: None,
+        limit: 0,
+    };
+
+    let cancellation_token = CancellationToken::new();
+    let (found_tx, mut found_rx) = tokio::sync::mpsc::channel(EXPORT_CHANNEL_SIZE);
+
+    let export = async {
+        let found_tx = found_tx;
+        super::export_messages(app, topic_id, &filter, &cancellation_token, &found_tx).await
+    };
+
+    let write = async {
+        let mut file = ParquetFileInProgress::new();
+        let mut result = ParquetExportResult::default();
+
+        while let Some(msg) = found_rx.recv().await {
+            if let Err(err) = file.write(request, target, msg.as_ref()).await {
+                cancellation_token.cancel();
+                found_rx.close();
+                return Err(err);
+            }
+
+            if file.writer.get_size() < MAX_FILE_SIZE {
+                continue;
+            }
+
+            let full_file = std::mem::replace(&mut file, ParquetFileInProgress::new());
+
+            if let Err(err) = full_file.upload(request, target, &mut result).await {
+                cancellation_token.cancel();
+                found_rx.close();
+                return Err(err);
+            }
+        }
+
+        Ok((file, result))
+    };
+
+    let (export_result, write_result) = tokio::join!(export, write);
+
+    export_result?;
+
+    let (file, mut result) = write_result?;
+
+    file.upload(request, target, &mut result).await?;
+
+    app.logs.add_info(
+        Some(topic_id),
+        "Parquet export",
+        format!(
+            "Exported {} messages to {} files of {}",
+            result.exported,
+            result.files.len(),
+            target.get_name()
+        ),
+    );
+
+    Ok(result)
+}
+
+// Export is run in background. Its state is kept in parquet export jobs
+pub async fn start_parquet_export(
+    app: Arc<AppContext>,
+    request: ParquetExportRequest,
+    target: &str,
+    initiator: &str,
+) -> Result<(), OperationError> {
+    super::topics::get_topic(app.as_ref(), request.topic_id.as_str()).await?;
+
+    let target = get_parquet_export_target(app.as_ref(), target)?;
+
+    let started = app
+        .parquet_export_jobs
+        .try_start(
+            request.topic_id.as_str(),
+            target.get_name(),
+            DateTimeAsMicroseconds::now(),
+        )
+        .await;
+
+    if !started {
+        return Err(OperationError::ParquetExportError(format!(
+            "Parquet export of topic {} is already running",
+            request.topic_id
+        )));
+    }
+
+    super::write_audit_log(
+        app.as_ref(),
+        "ExportParquet",
+        initiator,
+        Some(request.topic_id.as_str()),
+        format!(
+            "Target={}; FromMessageId={:?}; ToMessageId={:?}; Incremental={}",
+            target.get_name(),
+            request.from_message_id.map(|itm| itm.get_value()),
+            request.to_message_id.map(|itm| itm.get_value()),
+            request.incremental
+        ),
+    )
+    .await;
+
+    tokio::spawn(async move {
+        let state = match export_topic_to_parquet(app.as_ref(), &request, &target).await {
+            Ok(result) => ParquetExportJobState::Finished {
+                files: result.files,
+                exported: result.exported,
+                last_message_id: result.last_message_id,
+            },
+            Err(err) => {
+                app.logs.add_error(
+                    Some(request.topic_id.as_str()),
+                    "Parquet export",
+                    "Export failed".to_string(),
+                    Some(format!("{:?}", err)),
+                );
+
+                ParquetExportJobState::Failed(format!("{:?}", err))
+            }
+        };
+
+        app.parquet_export_jobs
+            .finish(
+                request.topic_id.as_str(),
+                state,
+                DateTimeAsMicroseconds::now(),
+            )
+            .await;
+    });
+
+    Ok(())
+}
+
+struct ParquetFileInProgress {
+    writer: MessagesParquetWriter,
+    file: Option<FileStorageWriter>,
+    first_message_id: Option<i64>,
+    last_message_id: Option<i64>,
+}
+
+impl ParquetFileInProgress {
+    fn new() -> Self {
+        Self {
+            writer: MessagesParquetWriter::new(),
+            file: None,
+            first_message_id: None,
+            last_message_id: None,
+        }
+    }
+
+    async fn write(
+        &mut self,
+        request: &ParquetExportRequest,
+        target: &FileStorage,
+        msg: &MessageProtobufModel,
+    ) -> Result<(), OperationError> {
+        let message_id = msg.get_message_id().get_value();
+
+        if self.first_message_id.is_none() {
+            self.first_message_id = Some(message_id);
+
+            let tmp_path = format!("{}/{:020}.parquet.tmp", request.topic_id, message_id);
+            self.file = Some(target.create_file(tmp_path.as_str()).await?);
+        }
+
+        self.last_message_id = Some(message_id);
+
+        self.writer
+            .write(msg)
+            .map_err(|err| OperationError::ParquetExportError(format!("{:?}", err)))?;
+
+        let output = self.writer.take_output();
+
+        if !output.is_empty() {
+            if let Some(file) = self.file.as_mut() {
+                file.append(output.as_slice()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Incremental export position is moved only after the file is written
+    async fn upload(
+        self,
+        request: &ParquetExportRequest,
+        target: &FileStorage,
+        result: &mut ParquetExportResult,
+    ) -> Result<(), OperationError> {
+        let rows = self.writer.get_rows();
+
+        let content = self
+            .writer
+            .close()
+            .map_err(|err| OperationError::ParquetExportError(format!("{:?}", err)))?;
+
+        let (content, mut file, first_message_id, last_message_id) = match (
+            content,
+            self.file,
+            self.first_message_id,
+            self.last_message_id,
+        ) {
+            (Some(content), Some(file), Some(first), Some(last)) => (content, file, first, last),
+            _ => return Ok(()),
+        };
+
+        let file_name = format!("{:020}-{:020}.parquet", first_message_id, last_message_id);
+
+        file.append(content.as_slice()).await?;
+        file.commit(format!("{}/{}", request.topic_id, file_name).as_str())
+            .await?;
+
+        if request.incremental {
+            let state = ParquetExportState {
+                last_message_id,
+                updated: DateTimeAsMicroseconds::now().to_rfc3339(),
+            };
+
+            let content = serde_json::to_vec(&state).unwrap();
+
+            target
+                .write_file(
+                    ParquetExportState::get_file_path(request.topic_id.as_str()).as_str(),
+                    content,
+                )
+                .await?;
+        }
+
+        result.files.push(file_name);
+        result.exported += rows;
+        result.last_message_id = Some(last_message_id);
+
+        Ok(())
+    }
+}
+
+async fn read_export_state(
+    target: &FileStorage,
+    topic_id: &str,
+) -> Result<Option<ParquetExportState>, OperationError> {
+    let content = match target
+        .read_file(ParquetExportState::get_file_path(topic_id).as_str())
+        .await?
+    {
+        Some(content) => content,
+        None => return Ok(None),
+    };
+
+    match serde_json::from_slice(content.as_slice()) {
+        Ok(result) => Ok(Some(result)),
+        Err(err) => Err(OperationError::ParquetExportError(format!(
+            "Invalid export state of topic {}. Err: {:?}",
+            topic_id, err
+        ))),
+    }
+}
+
+pub fn get_parquet_export_target(
+    app: &AppContext,
+    target: &str,
+) -> Result<FileStorage, OperationError> {
+    match app.get_parquet_export_target(target) {
+        Some(result) => Ok(result),
+        None => Err(OperationError::ParquetExportError(format!(
+            "Parquet export target {} is not configured",
+            target
+        ))),
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use my_service_bus::{
+    abstractions::MessageId,
+    shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId},
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, replication::ReplicationEventData};
+
+use super::OperationError;
+
+// Events are applied by the same operations as writes of the service bus, so the secondary
+// persists them to its own storage the same way the primary does
+pub async fn apply_replication_event(
+    app: &AppContext,
+    data: ReplicationEventData,
+) -> Result<(), OperationError> {
+    match data {
+        ReplicationEventData::NewMessages { topic_id, messages } => {
+            let mut messages_by_sub_page: BTreeMap<i64, Vec<MessageProtobufModel>> =
+                BTreeMap::new();
+
+            for msg in messages {
+                let sub_page_id: SubPageId = msg.get_message_id().into();
+
+                messages_by_sub_page
+                    .entry(sub_page_id.get_value())
+                    .or_default()
+                    .push(msg);
+            }
+
+            super::new_messages(app, topic_id, messages_by_sub_page).await;
+            Ok(())
+        }
+        ReplicationEventData::TopicsSnapshot(snapshot) => {
+            super::save_topics_snapshot(app, snapshot).await
+        }
+        ReplicationEventData::UpsertTopicSnapshot(topic) => {
+            super::upsert_topic_snapshot(app, topic).await
+        }
+        ReplicationEventData::DeleteQueueSnapshot { topic_id, queue_id } => {
+            super::delete_queue_snapshot(app, topic_id.as_str(), queue_id.as_str()).await
+        }
+        ReplicationEventData::DeleteTopic {
+            topic_id,
+            message_id,
+            gc_after,
+        } => {
+            super::mark_topic_deleted(
+                app,
+                topic_id.as_str(),
+                MessageId::new(message_id),
+                DateTimeAsMicroseconds::new(gc_after),
+            )
+            .await;
+
+            super::publish_replication_event(
+                app,
+                ReplicationEventData::DeleteTopic {
+                    topic_id,
+                    message_id,
+                    gc_after,
+                },
+            );
+
+            Ok(())
+        }
+        ReplicationEventData::RestoreTopic { topic_id } => {
+            // Topic which is not deleted is restored already, so a replayed event is applied again
+            let restored = super::restore_deleted_topic(app, topic_id.as_str()).await;
+
+            if restored.is_none()
+                && app
+                    .topics_snapshot
+                    .get_current_message_id(topic_id.as_str())
+                    .await
+                    .is_none()
+            {
+                return Err(OperationError::TopicNotFound(topic_id));
+            }
+
+            super::publish_replication_event(app, ReplicationEventData::RestoreTopic { topic_id });
+
+            Ok(())
+        }
+        ReplicationEventData::RollbackTopicsSnapshot(snapshot) => {
+            super::rollback_to_snapshot(app, snapshot.clone()).await?;
+
+            super::publish_replication_event(
+                app,
+                ReplicationEventData::RollbackTopicsSnapshot(snapshot),
+            );
+
+            Ok(())
+        }
+    }
+}
+
+// Admin operations are published only when they are done
+pub fn publish_replication_event(app: &AppContext, data: ReplicationEventData) {
+    if let Some(replication_hub) = app.replication_hub.as_ref() {
+        replication_hub.publish(data);
+    }
+}
+
+// Secondary stops applying events of the primary and starts accepting writes
+pub async fn promote_replica(app: &AppContext, initiator: &str) -> Result<(), OperationError> {
+    let replica = match &app.replica {
+        Some(replica) => replica,
+        None => {
+            return Err(OperationError::ReplicationError(
+                "Instance is not a secondary replica".to_string(),
+            ))
+        }
+    };
+
+    // Messages missed by a gap are not backfilled yet
+    if !replica.is_in_sync() {
+        return Err(OperationError::ReplicationError(
+            "Secondary is not in sync with the primary. It is promoted after resync is finished"
+                .to_string(),
+        ));
+    }
+
+    if !replica.promote() {
+        return Err(OperationError::ReplicationError(
+            "Instance is already promoted".to_string(),
+        ));
+    }
+
+    let (session_id, applied_sequence) = replica.get_position();
+    let lag = replica.get_lag();
+
+    app.metrics_keeper.update_replication_lag(0, 0);
+
+    app.logs.add_info(
+        None,
+        "Replication",
+        format!(
+            "Secondary is promoted. Primary: {}; applied sequence: {}; not applied events: {}",
+            replica.get_primary_url(),
+            applied_sequence,
+            lag.events
+        ),
+    );
+
+    super::write_audit_log(
+        app,
+        "PromoteReplica",
+        initiator,
+        None,
+        format!(
+            "Primary={}; Session={}; Sequence={}; LagEvents={}; LagMs={}",
+            replica.get_primary_url(),
+            session_id,
+            applied_sequence,
+            lag.events,
+            lag.duration_ms
+        ),
+    )
+    .await;
+
+    Ok(())
+}
@@ -1,5 +1,59 @@
-use crate::{app::AppContext, topics_snapshot::DeletedTopicProtobufModel};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-pub async fn restore_topic(app: &AppContext, topic_id: &str) -> Option<DeletedTopicProtobufModel> {
-    app.topics_snapshot.remove_deleted_topic(topic_id).await
+use crate::{
+    app::AppContext, replication::ReplicationEventData, topics_snapshot::DeletedTopicProtobufModel,
+};
+
+use super::OperationError;
+
+// Topic which grace period is over is not found, since it is being purged
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn restore_topic(
+    app: &AppContext,
+    topic_id: &str,
+    initiator: &str,
+) -> Result<DeletedTopicProtobufModel, OperationError> {
+    super::check_leader(app)?;
+
+    let restored = match restore_deleted_topic(app, topic_id).await {
+        Some(restored) => restored,
+        None => return Err(OperationError::TopicNotFound(topic_id.to_string())),
+    };
+
+    super::publish_replication_event(
+        app,
+        ReplicationEventData::RestoreTopic {
+            topic_id: topic_id.to_string(),
+        },
+    );
+
+    app.logs.add_info(
+        Some(topic_id),
+        "RestoreTopic",
+        format!("Topic is restored with message id {}", restored.message_id),
+    );
+
+    super::write_audit_log(
+        app,
+        "RestoreTopic",
+        initiator,
+        Some(topic_id),
+        format!("MessageId={}", restored.message_id),
+    )
+    .await;
+
+    Ok(restored)
+}
+
+pub async fn restore_deleted_topic(
+    app: &AppContext,
+    topic_id: &str,
+) -> Option<DeletedTopicProtobufModel> {
+    app.topics_list
+        .restore_with(
+            topic_id,
+            app.topics_snapshot
+                .restore_deleted_topic(topic_id, DateTimeAsMicroseconds::now()),
+        )
+        .await
 }
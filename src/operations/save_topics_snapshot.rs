@@ -0,0 +1,100 @@
+use crate::{
+    app::AppContext,
+    replication::ReplicationEventData,
+    topics_snapshot::{SnapshotAnomaly, TopicSnapshotProtobufModel},
+};
+
+use super::OperationError;
+
+#[tracing::instrument(skip_all, fields(topics_amount = snapshot.len()))]
+pub async fn save_topics_snapshot(
+    app: &AppContext,
+    snapshot: Vec<TopicSnapshotProtobufModel>,
+) -> Result<(), OperationError> {
+    let replicated_snapshot = app.replication_hub.as_ref().map(|_| snapshot.clone());
+
+    let result = app.topics_snapshot.update(snapshot).await;
+
+    match result {
+        Ok(anomalies) => {
+            publish(
+                app,
+                replicated_snapshot.map(ReplicationEventData::TopicsSnapshot),
+            );
+
+            for anomaly in anomalies {
+                app.metrics_keeper.snapshot_anomaly(anomaly.as_str());
+                app.logs
+                    .add_warning(None, "SaveTopicsSnapshot", anomaly.get_details());
+            }
+
+            Ok(())
+        }
+        Err(anomalies) => Err(reject_snapshot(app, anomalies)),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(topic_id = topic.topic_id.as_str()))]
+pub async fn upsert_topic_snapshot(
+    app: &AppContext,
+    topic: TopicSnapshotProtobufModel,
+) -> Result<(), OperationError> {
+    let replicated_topic = app.replication_hub.as_ref().map(|_| topic.clone());
+
+    let result = app.topics_snapshot.upsert_topic(topic).await;
+
+    match result {
+        Ok(()) => {
+            publish(
+                app,
+                replicated_topic.map(ReplicationEventData::UpsertTopicSnapshot),
+            );
+            Ok(())
+        }
+        Err(anomalies) => Err(reject_snapshot(app, anomalies)),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(topic_id = topic_id, queue_id = queue_id))]
+pub async fn delete_queue_snapshot(
+    app: &AppContext,
+    topic_id: &str,
+    queue_id: &str,
+) -> Result<(), OperationError> {
+    let result = app.topics_snapshot.delete_queue(topic_id, queue_id).await;
+
+    match result {
+        Some(_) => {
+            publish(
+                app,
+                Some(ReplicationEventData::DeleteQueueSnapshot {
+                    topic_id: topic_id.to_string(),
+                    queue_id: queue_id.to_string(),
+                }),
+            );
+            Ok(())
+        }
+        None => Err(OperationError::TopicNotFound(topic_id.to_string())),
+    }
+}
+
+// Only accepted snapshot updates are published to secondaries
+fn publish(app: &AppContext, data: Option<ReplicationEventData>) {
+    if let (Some(replication_hub), Some(data)) = (app.replication_hub.as_ref(), data) {
+        replication_hub.publish(data);
+    }
+}
+
+fn reject_snapshot(app: &AppContext, anomalies: Vec<SnapshotAnomaly>) -> OperationError {
+    for anomaly in &anomalies {
+        app.metrics_keeper.snapshot_anomaly(anomaly.as_str());
+        app.logs.add_error(
+            None,
+            "SaveTopicsSnapshot",
+            "Snapshot is rejected".to_string(),
+            Some(anomaly.get_details()),
+        );
+    }
+
+    OperationError::InvalidSnapshot(anomalies)
+}
@@ -0,0 +1,339 @@
+use std::{sync::Arc, time::Duration};
+
+use my_service_bus::{
+    abstractions::MessageId,
+    shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId},
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio_util::sync::CancellationToken;
+
+use crate::{app::AppContext, json_path::JsonPathFilter};
+
+use super::OperationError;
+
+#[derive(Debug, Clone)]
+pub enum HeaderPredicate {
+    Equals { key: String, value: String },
+    Prefix { key: String, prefix: String },
+    Exists { key: String },
+}
+
+impl HeaderPredicate {
+    // key=value - equals, key^=prefix - starts with, key - header exists
+    pub fn parse(src: &str) -> Option<Self> {
+        if let Some((key, prefix)) = src.split_once("^=") {
+            return Self::Prefix {
+                key: get_key(key)?,
+                prefix: prefix.to_string(),
+            }
+            .into();
+        }
+
+        if let Some((key, value)) = src.split_once('=') {
+            return Self::Equals {
+                key: get_key(key)?,
+                value: value.to_string(),
+            }
+            .into();
+        }
+
+        Self::Exists { key: get_key(src)? }.into()
+    }
+
+    pub fn is_matching(&self, msg: &MessageProtobufModel) -> bool {
+        match self {
+            HeaderPredicate::Equals { key, value } => msg
+                .headers
+                .iter()
+                .any(|itm| &itm.key == key && &itm.value == value),
+            HeaderPredicate::Prefix { key, prefix } => msg
+                .headers
+                .iter()
+                .any(|itm| &itm.key == key && itm.value.starts_with(prefix.as_str())),
+            HeaderPredicate::Exists { key } => msg.headers.iter().any(|itm| &itm.key == key),
+        }
+    }
+}
+
+fn get_key(src: &str) -> Option<String> {
+    let src = src.trim();
+
+    if src.is_empty() {
+        return None;
+    }
+
+    Some(src.to_string())
+}
+
+pub struct SearchMessagesFilter {
+    pub from_message_id: Option<MessageId>,
+    pub to_message_id: Option<MessageId>,
+    pub from_date: Option<DateTimeAsMicroseconds>,
+    pub to_date: Option<DateTimeAsMicroseconds>,
+    // All predicates must match
+    pub predicates: Vec<HeaderPredicate>,
+    pub json_path: Option<JsonPathFilter>,
+    // Capped by MaxResponseRecordsAmount. 0 - MaxResponseRecordsAmount
+    pub limit: usize,
+}
+
+impl SearchMessagesFilter {
+    pub fn is_matching(&self, msg: &MessageProtobufModel) -> bool {
+        let created = msg.get_created().unix_microseconds;
+
+        if let Some(from_date) = self.from_date {
+            if created < from_date.unix_microseconds {
+                return false;
+            }
+        }
+
+        if let Some(to_date) = self.to_date {
+            if created > to_date.unix_microseconds {
+                return false;
+            }
+        }
+
+        if !self.predicates.iter().all(|itm| itm.is_matching(msg)) {
+            return false;
+        }
+
+        match &self.json_path {
+            Some(json_path) => json_path.is_matching(msg.data.as_slice()),
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SearchMessagesResult {
+    pub scanned_sub_pages: usize,
+    pub found: usize,
+    pub cancelled: bool,
+    // Messages up to it are checked. Scan is continued from the next one
+    pub last_scanned_message_id: Option<MessageId>,
+}
+
+// Sub pages are scanned through the regular read path. Matches are sent to the channel as they are found.
+// Search stops when limit is reached, token is cancelled or receiver is dropped
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn search_messages(
+    app: &AppContext,
+    topic_id: &str,
+    filter: &SearchMessagesFilter,
+    cancellation_token: &CancellationToken,
+    tx: &tokio::sync::mpsc::Sender<Arc<MessageProtobufModel>>,
+) -> Result<SearchMessagesResult, OperationError> {
+    let limit = if filter.limit == 0 {
+        app.settings.max_response_records_amount
+    } else {
+        filter.limit.min(app.settings.max_response_records_amount)
+    };
+
+    scan_messages(app, topic_id, filter, limit, cancellation_token, tx).await
+}
+
+// Same as search, but limit of the filter is ignored. Sender is awaited, so memory stays flat
+// no matter how many messages are exported
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn export_messages(
+    app: &AppContext,
+    topic_id: &str,
+    filter: &SearchMessagesFilter,
+    cancellation_token: &CancellationToken,
+    tx: &tokio::sync::mpsc::Sender<Arc<MessageProtobufModel>>,
+) -> Result<SearchMessagesResult, OperationError> {
+    scan_messages(app, topic_id, filter, usize::MAX, cancellation_token, tx).await
+}
+
+async fn scan_messages(
+    app: &AppContext,
+    topic_id: &str,
+    filter: &SearchMessagesFilter,
+    limit: usize,
+    cancellation_token: &CancellationToken,
+    tx: &tokio::sync::mpsc::Sender<Arc<MessageProtobufModel>>,
+) -> Result<SearchMessagesResult, OperationError> {
+    let topic_data = super::topics::get_topic(app, topic_id).await?;
+
+    let current_message_id = app
+        .topics_snapshot
+        .get_current_message_id(topic_id)
+        .await
+        .ok_or(OperationError::TopicNotFound(topic_id.to_string()))?;
+
+    let mut from_message_id = filter.from_message_id.unwrap_or(MessageId::new(0));
+
+    let mut result = SearchMessagesResult {
+        scanned_sub_pages: 0,
+        found: 0,
+        cancelled: false,
+        last_scanned_message_id: None,
+    };
+
+    // Nothing is indexed since the date, so there are no messages to scan
+    if let Some(from_date) = filter.from_date {
+        let message_id = super::get_message_id_by_date(app, topic_data.as_ref(), from_date).await;
+
+        match message_id {
+            Some(message_id) => {
+                if message_id.get_value() > from_message_id.get_value() {
+                    from_message_id = message_id;
+                }
+            }
+            None => return Ok(result),
+        }
+    }
+
+    let mut to_message_id = filter.to_message_id.unwrap_or(current_message_id);
+
+    if to_message_id.get_value() > current_message_id.get_value() {
+        to_message_id = current_message_id;
+    }
+
+    if from_message_id.get_value() > to_message_id.get_value() {
+        return Ok(result);
+    }
+
+    let from_sub_page_id: SubPageId = from_message_id.into();
+    let to_sub_page_id: SubPageId = to_message_id.into();
+
+    for sub_page_id in from_sub_page_id.get_value()..=to_sub_page_id.get_value() {
+        if cancellation_token.is_cancelled() {
+            result.cancelled = true;
+            return Ok(result);
+        }
+
+        let sub_page_id = SubPageId::new(sub_page_id);
+
+        let sub_page = super::get_sub_page_to_read(app, topic_id, sub_page_id).await;
+        let messages = sub_page.get_all_messages().await;
+
+        result.scanned_sub_pages += 1;
+
+        let first_message_id = sub_page_id
+            .get_first_message_id()
+            .get_value()
+            .max(from_message_id.get_value());
+
+        let last_message_id = sub_page_id
+            .get_last_message_id()
+            .get_value()
+            .min(to_message_id.get_value());
+
+        for message_id in first_message_id..=last_message_id {
+            result.last_scanned_message_id = Some(MessageId::new(message_id));
+
+            let msg = match messages.get(message_id.into()) {
+                Some(msg) => msg,
+                None => continue,
+            };
+
+            // Messages are written in order, so the rest of them are out of range as well
+            if let Some(to_date) = filter.to_date {
+                if msg.get_created().unix_microseconds > to_date.unix_microseconds {
+                    return Ok(result);
+                }
+            }
+
+            if !filter.is_matching(msg.as_ref()) {
+                continue;
+            }
+
+            if tx.send(msg.clone()).await.is_err() {
+                result.cancelled = true;
+                return Ok(result);
+            }
+
+            result.found += 1;
+
+            if result.found >= limit {
+                return Ok(result);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+// Collects found messages. Search is cancelled after timeout and found messages are returned
+pub async fn find_messages(
+    app: &AppContext,
+    topic_id: &str,
+    filter: &SearchMessagesFilter,
+    timeout: Duration,
+) -> Result<(Vec<Arc<MessageProtobufModel>>, SearchMessagesResult), OperationError> {
+    let cancellation_token = CancellationToken::new();
+
+    let timeout_token = cancellation_token.clone();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        timeout_token.cancel();
+    });
+
+    // Limit is capped by MaxResponseRecordsAmount, so search never waits for the channel
+    let (tx, mut rx) = tokio::sync::mpsc::channel(app.settings.max_response_records_amount.max(1));
+
+    let result = search_messages(app, topic_id, filter, &cancellation_token, &tx).await;
+
+    cancellation_token.cancel();
+
+    let result = result?;
+
+    let mut messages = Vec::new();
+
+    while let Ok(msg) = rx.try_recv() {
+        messages.push(msg);
+    }
+
+    Ok((messages, result))
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::{
+        abstractions::MessageId,
+        shared::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel},
+    };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::HeaderPredicate;
+
+    fn message(headers: &[(&str, &str)]) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            MessageId::new(1),
+            DateTimeAsMicroseconds::new(0),
+            vec![],
+            headers
+                .iter()
+                .map(|(key, value)| MessageMetaDataProtobufModel {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_and_match_predicates() {
+        let msg = message(&[("correlation-id", "abc-123"), ("source", "api")]);
+
+        let equals = HeaderPredicate::parse("correlation-id=abc-123").unwrap();
+        assert!(equals.is_matching(&msg));
+
+        let prefix = HeaderPredicate::parse("correlation-id^=abc").unwrap();
+        assert!(prefix.is_matching(&msg));
+
+        let exists = HeaderPredicate::parse("source").unwrap();
+        assert!(exists.is_matching(&msg));
+
+        let not_matching = HeaderPredicate::parse("source=web").unwrap();
+        assert!(!not_matching.is_matching(&msg));
+
+        let missing = HeaderPredicate::parse("tenant").unwrap();
+        assert!(!missing.is_matching(&msg));
+
+        assert!(HeaderPredicate::parse("=value").is_none());
+        assert!(HeaderPredicate::parse("").is_none());
+    }
+}
@@ -2,14 +2,26 @@ use std::sync::Arc;
 
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::sub_page::SubPageId;
+use tracing::Instrument;
 
-use crate::{app::AppContext, persistence_grpc::MessageContentGrpcModel};
+use crate::{
+    app::AppContext, json_path::JsonPathFilter, persistence_grpc::MessageContentGrpcModel,
+};
 
+#[tracing::instrument(
+    skip_all,
+    fields(
+        topic_id = topic_id.as_str(),
+        from_message_id = from_message_id.get_value(),
+        to_message_id = to_message_id.get_value(),
+    ),
+)]
 pub async fn send_messages_to_channel(
     app: Arc<AppContext>,
     topic_id: String,
     from_message_id: MessageId,
     to_message_id: MessageId,
+    json_path: Option<JsonPathFilter>,
     tx: tokio::sync::mpsc::Sender<Result<MessageContentGrpcModel, tonic::Status>>,
     send_timeout: std::time::Duration,
 ) {
@@ -37,7 +49,18 @@ pub async fn send_messages_to_channel(
         let message = sub_page_read_copy.as_ref().unwrap().get(message_id);
 
         if let Some(message) = message {
-            let future = tx.send(Ok(message.as_ref().into()));
+            if let Some(json_path) = &json_path {
+                if !json_path.is_matching(message.data.as_slice()) {
+                    continue;
+                }
+            }
+
+            app.metrics_keeper
+                .bytes_read(topic_id.as_str(), message.data.len());
+
+            let future = tx
+                .send(Ok(message.as_ref().into()))
+                .instrument(tracing::debug_span!("send_to_channel"));
 
             match tokio::time::timeout(send_timeout, future).await {
                 Ok(_) => {}
@@ -0,0 +1,106 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    replication::ReplicationEventData,
+    topics_snapshot::{TopicSnapshotDiff, TopicsSnapshotProtobufModelV2},
+};
+
+use super::OperationError;
+
+// Compares history version with the version to compare with or with the current snapshot
+pub async fn get_topics_snapshot_diff(
+    app: &AppContext,
+    version: i64,
+    compare_with: Option<i64>,
+) -> Result<Vec<TopicSnapshotDiff>, OperationError> {
+    let item = app
+        .topics_snapshot
+        .history
+        .get(version)
+        .await
+        .ok_or(OperationError::SnapshotVersionNotFound(version))?;
+
+    let before = item.snapshot.clone().unwrap_or_default();
+
+    let after = match compare_with {
+        Some(compare_with) => app
+            .topics_snapshot
+            .history
+            .get(compare_with)
+            .await
+            .ok_or(OperationError::SnapshotVersionNotFound(compare_with))?
+            .snapshot
+            .clone()
+            .unwrap_or_default(),
+        None => app.topics_snapshot.get().await.to_protobuf_model(),
+    };
+
+    Ok(crate::topics_snapshot::diff_topics(
+        before.data.as_slice(),
+        after.data.as_slice(),
+    ))
+}
+
+// Current snapshot is saved to history before rollback, so rollback can be reverted as well.
+// Returns the version of the saved current snapshot.
+#[tracing::instrument(skip_all, fields(version = version))]
+pub async fn rollback_topics_snapshot(
+    app: &AppContext,
+    version: i64,
+    initiator: &str,
+) -> Result<i64, OperationError> {
+    super::check_leader(app)?;
+
+    let item = app
+        .topics_snapshot
+        .history
+        .get(version)
+        .await
+        .ok_or(OperationError::SnapshotVersionNotFound(version))?;
+
+    let snapshot = item.snapshot.clone().unwrap_or_default();
+
+    let backup_version = rollback_to_snapshot(app, snapshot.clone()).await?;
+
+    super::publish_replication_event(app, ReplicationEventData::RollbackTopicsSnapshot(snapshot));
+
+    app.logs.add_warning(
+        None,
+        "RollbackTopicsSnapshot",
+        format!(
+            "Topics snapshot is rolled back to version {}. Previous snapshot is saved as version {}",
+            version, backup_version
+        ),
+    );
+
+    super::write_audit_log(
+        app,
+        "RollbackTopicsSnapshot",
+        initiator,
+        None,
+        format!("Version={}; BackupVersion={}", version, backup_version),
+    )
+    .await;
+
+    Ok(backup_version)
+}
+
+// Secondary has its own history, so the snapshot itself is replicated instead of the version.
+// Returns the version of the saved current snapshot
+pub async fn rollback_to_snapshot(
+    app: &AppContext,
+    snapshot: TopicsSnapshotProtobufModelV2,
+) -> Result<i64, OperationError> {
+    let current = app.topics_snapshot.get().await;
+
+    let backup_version = app
+        .topics_snapshot
+        .history
+        .add(&current.to_protobuf_model(), DateTimeAsMicroseconds::now())
+        .await?;
+
+    app.topics_snapshot.rollback(snapshot).await?;
+
+    Ok(backup_version)
+}
@@ -0,0 +1,198 @@
+use std::{sync::Arc, time::Duration};
+
+use my_service_bus::shared::sub_page::SubPageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, archive_storage::ArchiveFileNo, message_pages::ZstdDictionary};
+
+use super::OperationError;
+
+const MAX_DICTIONARY_SIZE: usize = 16 * 1024;
+const SUB_PAGES_TO_SAMPLE: usize = 16;
+const MIN_SAMPLES_AMOUNT: usize = 100;
+const RETRAIN_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+#[derive(Clone, prost::Message)]
+pub struct ZstdDictionaryModel {
+    #[prost(int64, tag = "1")]
+    pub version: i64,
+    #[prost(int64, tag = "2")]
+    pub created: i64,
+    #[prost(bytes, tag = "3")]
+    pub data: Vec<u8>,
+}
+
+// All versions are kept, since archived sub pages reference the dictionary they were written with
+#[derive(Clone, prost::Message)]
+pub struct ZstdDictionariesModel {
+    #[prost(message, repeated, tag = "1")]
+    pub dictionaries: Vec<ZstdDictionaryModel>,
+}
+
+// Dictionaries are loaded at startup, so reads and writes of sub pages do not touch the storage.
+// Topics failed to load are retried by the timer
+pub async fn load_all_zstd_dictionaries(app: &AppContext) {
+    for topic_id in app.topics_snapshot.get_topics_list().await {
+        load_zstd_dictionaries(app, topic_id.as_str()).await;
+    }
+}
+
+// Dictionaries of the topic are loaded once. The latest version is used to write new sub pages
+pub async fn load_zstd_dictionaries(app: &AppContext, topic_id: &str) {
+    if app.codecs.is_dictionaries_loaded(topic_id) {
+        return;
+    }
+
+    let storage = app.get_zstd_dictionaries_storage(topic_id).await;
+
+    match storage.read_model::<ZstdDictionariesModel>().await {
+        Ok(model) => {
+            let model = model.unwrap_or_default();
+
+            for (no, dictionary) in model.dictionaries.iter().enumerate() {
+                let dictionary = Arc::new(ZstdDictionary::new(dictionary.data.clone()));
+
+                if no == model.dictionaries.len() - 1 {
+                    app.codecs.set_trained_dictionary(topic_id, dictionary);
+                } else {
+                    app.codecs.add_dictionary(dictionary);
+                }
+            }
+
+            app.codecs.set_dictionaries_loaded(topic_id);
+        }
+        Err(err) => {
+            app.logs.add_error(
+                Some(topic_id),
+                "LoadZstdDictionaries",
+                "Can not load zstd dictionaries".to_string(),
+                Some(format!("{:?}", err)),
+            );
+        }
+    }
+}
+
+// Returns version of the trained dictionary. None if the last one is fresh enough
+// or there are not enough archived messages to train from
+#[tracing::instrument(skip_all, fields(topic_id = topic_id))]
+pub async fn train_zstd_dictionary(
+    app: &AppContext,
+    topic_id: &str,
+    now: DateTimeAsMicroseconds,
+) -> Result<Option<i64>, OperationError> {
+    load_zstd_dictionaries(app, topic_id).await;
+
+    let storage = app.get_zstd_dictionaries_storage(topic_id).await;
+
+    let mut model = storage
+        .read_model::<ZstdDictionariesModel>()
+        .await?
+        .unwrap_or_default();
+
+    if let Some(last) = model.dictionaries.last() {
+        if now.unix_microseconds - last.created < RETRAIN_INTERVAL.as_micros() as i64 {
+            return Ok(None);
+        }
+    }
+
+    let samples = collect_samples(app, topic_id).await?;
+
+    if samples.len() < MIN_SAMPLES_AMOUNT {
+        return Ok(None);
+    }
+
+    let data = zstd::dict::from_samples(samples.as_slice(), MAX_DICTIONARY_SIZE)?;
+
+    let version = match model.dictionaries.last() {
+        Some(last) => last.version + 1,
+        None => 1,
+    };
+
+    model.dictionaries.push(ZstdDictionaryModel {
+        version,
+        created: now.unix_microseconds,
+        data: data.clone(),
+    });
+
+    storage.write_topics_snapshot(&model).await?;
+
+    let dictionary = Arc::new(ZstdDictionary::new(data));
+
+    app.logs.add_info(
+        Some(topic_id),
+        "TrainZstdDictionary",
+        format!(
+            "Zstd dictionary version {} with id {} is trained from {} messages",
+            version,
+            dictionary.id,
+            samples.len()
+        ),
+    );
+
+    app.codecs.set_trained_dictionary(topic_id, dictionary);
+
+    Ok(Some(version))
+}
+
+// Samples are messages of the last archived sub pages
+async fn collect_samples(app: &AppContext, topic_id: &str) -> Result<Vec<Vec<u8>>, OperationError> {
+    let message_id = app
+        .topics_snapshot
+        .get_current_message_id(topic_id)
+        .await
+        .ok_or(OperationError::TopicNotFound(topic_id.to_string()))?;
+
+    let last_sub_page_id: SubPageId = message_id.into();
+    let last_archive_file_no: ArchiveFileNo = last_sub_page_id.into();
+
+    let mut samples = Vec::new();
+    let mut sub_pages_sampled = 0;
+
+    let mut file_no = last_archive_file_no.get_value();
+
+    while file_no >= 0 && sub_pages_sampled < SUB_PAGES_TO_SAMPLE {
+        let archive_storage = app
+            .archive_storage_list
+            .try_get_or_open(ArchiveFileNo::new(file_no), topic_id, app)
+            .await;
+
+        file_no -= 1;
+
+        let archive_storage = match archive_storage {
+            Some(archive_storage) => archive_storage,
+            None => continue,
+        };
+
+        for (sub_page_id, _) in archive_storage.get_populated_sub_pages().await.iter().rev() {
+            if sub_pages_sampled >= SUB_PAGES_TO_SAMPLE {
+                break;
+            }
+
+            let payload = match archive_storage.read_sub_page_payload(*sub_page_id).await {
+                Ok(Some(payload)) => payload,
+                _ => continue,
+            };
+
+            let payload = match app
+                .encryption
+                .decrypt(topic_id, *sub_page_id, payload.as_slice())
+            {
+                Ok(payload) => payload,
+                Err(_) => continue,
+            };
+
+            let messages = match app.codecs.decode_messages(payload.as_ref()) {
+                Ok(messages) => messages,
+                Err(_) => continue,
+            };
+
+            for msg in &messages {
+                samples.push(prost::Message::encode_to_vec(msg));
+            }
+
+            sub_pages_sampled += 1;
+        }
+    }
+
+    Ok(samples)
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use my_service_bus::abstractions::MessageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, operations::ParquetExportRequest};
+
+pub const EXPORT_PARQUET_COMMAND: &str = "export-parquet";
+
+pub const EXPORT_PARQUET_USAGE: &str = "Usage: export-parquet --topic <topic id> --target local|blob [--from-id <message id>] [--to-id <message id>] [--from-date <iso date>] [--to-date <iso date>] [--incremental]";
+
+pub struct ParquetExportCliArgs {
+    pub request: ParquetExportRequest,
+    pub target: String,
+}
+
+impl ParquetExportCliArgs {
+    // Args go after the command name
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut topic_id = None;
+        let mut target = None;
+
+        let mut request = ParquetExportRequest {
+            topic_id: String::new(),
+            from_message_id: None,
+            to_message_id: None,
+            from_date: None,
+            to_date: None,
+            incremental: false,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--incremental" {
+                request.incremental = true;
+                continue;
+            }
+
+            let value = match args.next() {
+                Some(value) => value.as_str(),
+                None => return Err(format!("Value of {} is missing", arg)),
+            };
+
+            match arg.as_str() {
+                "--topic" => topic_id = Some(value.to_string()),
+                "--target" => target = Some(value.to_string()),
+                "--from-id" => request.from_message_id = Some(parse_message_id(value)?),
+                "--to-id" => request.to_message_id = Some(parse_message_id(value)?),
+                "--from-date" => request.from_date = Some(parse_date(value)?),
+                "--to-date" => request.to_date = Some(parse_date(value)?),
+                _ => return Err(format!("Unknown argument {}", arg)),
+            }
+        }
+
+        request.topic_id = topic_id.ok_or("--topic is missing".to_string())?;
+
+        Ok(Self {
+            request,
+            target: target.ok_or("--target is missing".to_string())?,
+        })
+    }
+}
+
+fn parse_message_id(src: &str) -> Result<MessageId, String> {
+    match src.parse::<i64>() {
+        Ok(result) => Ok(MessageId::new(result)),
+        Err(_) => Err(format!("Invalid message id {}", src)),
+    }
+}
+
+fn parse_date(src: &str) -> Result<DateTimeAsMicroseconds, String> {
+    DateTimeAsMicroseconds::parse_iso_string(src).ok_or(format!("Invalid date time {}", src))
+}
+
+// Runs export once instead of starting the service. Active sub pages are restored first,
+// so sub pages which are not archived yet are exported as well. Their blob is kept for the service
+pub async fn run_export_cli(app: Arc<AppContext>, args: &[String]) {
+    let args = match ParquetExportCliArgs::parse(args) {
+        Ok(args) => args,
+        Err(err) => {
+            println!("{}\n{}", err, EXPORT_PARQUET_USAGE);
+            return;
+        }
+    };
+
+    let target = match crate::operations::get_parquet_export_target(&app, args.target.as_str()) {
+        Ok(target) => target,
+        Err(err) => {
+            println!("{:?}", err);
+            return;
+        }
+    };
+
+    crate::operations::data_initializer::restore_pages(&app, false).await;
+
+    app.topics_list
+        .init_topic_data(args.request.topic_id.as_str())
+        .await;
+
+    match crate::operations::export_topic_to_parquet(&app, &args.request, &target).await {
+        Ok(result) => {
+            println!(
+                "Exported {} messages to {}",
+                result.exported,
+                target.get_name()
+            );
+
+            for file in result.files {
+                println!("{}", file);
+            }
+        }
+        Err(err) => println!("Export failed: {:?}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParquetExportCliArgs;
+
+    fn to_args(src: &str) -> Vec<String> {
+        src.split(' ').map(|itm| itm.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = ParquetExportCliArgs::parse(&to_args(
+            "--topic orders --target blob --from-id 100 --incremental",
+        ))
+        .unwrap();
+
+        assert_eq!(args.request.topic_id, "orders");
+        assert_eq!(args.target, "blob");
+        assert_eq!(args.request.from_message_id.unwrap().get_value(), 100);
+        assert!(args.request.to_message_id.is_none());
+        assert!(args.request.incremental);
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(ParquetExportCliArgs::parse(&to_args("--target local")).is_err());
+        assert!(ParquetExportCliArgs::parse(&to_args("--topic orders --target")).is_err());
+        assert!(ParquetExportCliArgs::parse(&to_args(
+            "--topic orders --target local --from-id abc"
+        ))
+        .is_err());
+        assert!(
+            ParquetExportCliArgs::parse(&to_args("--topic orders --target local --x 1")).is_err()
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use arrow::{
+    array::{
+        ArrayRef, BinaryBuilder, Int64Builder, MapBuilder, StringBuilder,
+        TimestampMicrosecondBuilder,
+    },
+    record_batch::RecordBatch,
+};
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    errors::ParquetError,
+    file::properties::WriterProperties,
+};
+
+// Rows are buffered in builders and written as a row group when there are this many of them
+// or when their payloads and headers take this many bytes
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+const ROW_GROUP_MAX_BYTES: usize = 32 * 1024 * 1024;
+
+// Written row groups are taken from it by chunks while the file is still in progress
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// Columns: message_id, created, headers (map), data (binary) and data_utf8.
+// Payload which is valid UTF-8 goes to data_utf8, otherwise to data. The other column is null
+pub struct MessagesParquetWriter {
+    writer: Option<ArrowWriter<SharedBuffer>>,
+    output: SharedBuffer,
+    taken_size: usize,
+    message_ids: Int64Builder,
+    created: TimestampMicrosecondBuilder,
+    headers: MapBuilder<StringBuilder, StringBuilder>,
+    data: BinaryBuilder,
+    data_utf8: StringBuilder,
+    rows_in_batch: usize,
+    batch_size: usize,
+    rows: usize,
+}
+
+impl MessagesParquetWriter {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            output: SharedBuffer::default(),
+            taken_size: 0,
+            message_ids: Int64Builder::new(),
+            created: TimestampMicrosecondBuilder::new().with_timezone("UTC"),
+            headers: MapBuilder::new(None, StringBuilder::new(), StringBuilder::new()),
+            data: BinaryBuilder::new(),
+            data_utf8: StringBuilder::new(),
+            rows_in_batch: 0,
+            batch_size: 0,
+            rows: 0,
+        }
+    }
+
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    // Bytes of the file written so far plus bytes of rows which are not written yet
+    pub fn get_size(&self) -> usize {
+        self.taken_size + self.output.len() + self.batch_size
+    }
+
+    // Takes the part of the file which is already written
+    pub fn take_output(&mut self) -> Vec<u8> {
+        let result = self.output.take();
+        self.taken_size += result.len();
+        result
+    }
+
+    pub fn write(&mut self, msg: &MessageProtobufModel) -> Result<(), ParquetError> {
+        self.message_ids
+            .append_value(msg.get_message_id().get_value());
+        self.created
+            .append_value(msg.get_created().unix_microseconds);
+
+        for header in &msg.headers {
+            self.batch_size += header.key.len() + header.value.len();
+            self.headers.keys().append_value(header.key.as_str());
+            self.headers.values().append_value(header.value.as_str());
+        }
+
+        self.headers.append(true)?;
+
+        match std::str::from_utf8(msg.data.as_slice()) {
+            Ok(data) => {
+                self.data.append_null();
+                self.data_utf8.append_value(data);
+            }
+            Err(_) => {
+                self.data.append_value(msg.data.as_slice());
+                self.data_utf8.append_null();
+            }
+        }
+
+        self.rows_in_batch += 1;
+        self.batch_size += msg.data.len();
+        self.rows += 1;
+
+        if self.rows_in_batch >= ROW_GROUP_SIZE || self.batch_size >= ROW_GROUP_MAX_BYTES {
+            self.write_batch()?;
+        }
+
+        Ok(())
+    }
+
+    fn write_batch(&mut self) -> Result<(), ParquetError> {
+        if self.rows_in_batch == 0 {
+            return Ok(());
+        }
+
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "message_id",
+                Arc::new(self.message_ids.finish()) as ArrayRef,
+            ),
+            ("created", Arc::new(self.created.finish()) as ArrayRef),
+            ("headers", Arc::new(self.headers.finish()) as ArrayRef),
+            ("data", Arc::new(self.data.finish()) as ArrayRef),
+            ("data_utf8", Arc::new(self.data_utf8.finish()) as ArrayRef),
+        ])?;
+
+        self.rows_in_batch = 0;
+        self.batch_size = 0;
+
+        // Schema is taken from the first batch, so it always matches the builders
+        if self.writer.is_none() {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build();
+
+            self.writer = Some(ArrowWriter::try_new(
+                self.output.clone(),
+                batch.schema(),
+                Some(properties),
+            )?);
+        }
+
+        let writer = self.writer.as_mut().unwrap();
+        writer.write(&batch)?;
+        writer.flush()
+    }
+
+    // Returns the rest of the file which is not taken yet or None if there were no rows
+    pub fn close(mut self) -> Result<Option<Vec<u8>>, ParquetError> {
+        self.write_batch()?;
+
+        match self.writer.take() {
+            Some(writer) => {
+                writer.close()?;
+                Ok(Some(self.output.take()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Int64Type;
+    use my_service_bus::{
+        abstractions::MessageId,
+        shared::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel},
+    };
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::MessagesParquetWriter;
+
+    fn message(id: i64, data: Vec<u8>) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            MessageId::new(id),
+            DateTimeAsMicroseconds::new(id * 1000),
+            data,
+            vec![MessageMetaDataProtobufModel {
+                key: "source".to_string(),
+                value: "api".to_string(),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let mut writer = MessagesParquetWriter::new();
+
+        writer
+            .write(&message(1, br#"{"accountId":"123"}"#.to_vec()))
+            .unwrap();
+        writer.write(&message(2, vec![0xff, 0xfe])).unwrap();
+
+        assert_eq!(writer.get_rows(), 2);
+
+        let mut payload = writer.take_output();
+        payload.extend(writer.close().unwrap().unwrap());
+
+        let file_name = std::env::temp_dir().join(format!(
+            "messages-parquet-writer-{}.parquet",
+            std::process::id()
+        ));
+
+        std::fs::write(&file_name, payload).unwrap();
+
+        let file = std::fs::File::open(&file_name).unwrap();
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let batch = reader.next().unwrap().unwrap();
+
+        std::fs::remove_file(&file_name).unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+
+        let message_ids = batch
+            .column_by_name("message_id")
+            .unwrap()
+            .as_primitive::<Int64Type>();
+        assert_eq!(message_ids.value(1), 2);
+
+        let data_utf8 = batch
+            .column_by_name("data_utf8")
+            .unwrap()
+            .as_string::<i32>();
+        assert_eq!(data_utf8.value(0), r#"{"accountId":"123"}"#);
+        assert!(data_utf8.is_null(1));
+
+        let data = batch.column_by_name("data").unwrap().as_binary::<i32>();
+        assert!(data.is_null(0));
+        assert_eq!(data.value(1), &[0xff, 0xfe]);
+
+        let headers = batch.column_by_name("headers").unwrap().as_map();
+        assert_eq!(headers.value_length(0), 1);
+    }
+
+    #[test]
+    fn test_row_group_by_size() {
+        let mut writer = MessagesParquetWriter::new();
+
+        writer
+            .write(&message(1, vec![0xff; super::ROW_GROUP_MAX_BYTES]))
+            .unwrap();
+
+        // Row group is written by size before ROW_GROUP_SIZE rows are collected
+        assert!(!writer.take_output().is_empty());
+        assert!(writer.get_size() > 0);
+    }
+
+    #[test]
+    fn test_no_rows() {
+        assert!(MessagesParquetWriter::new().close().unwrap().is_none());
+    }
+}
@@ -0,0 +1,8 @@
+mod export_cli;
+mod messages_parquet_writer;
+mod parquet_export_jobs;
+mod parquet_export_state;
+pub use export_cli::*;
+pub use messages_parquet_writer::*;
+pub use parquet_export_jobs::*;
+pub use parquet_export_state::*;
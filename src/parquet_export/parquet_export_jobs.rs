@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub enum ParquetExportJobState {
+    Running,
+    Finished {
+        files: Vec<String>,
+        exported: usize,
+        last_message_id: Option<i64>,
+    },
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ParquetExportJob {
+    pub topic_id: String,
+    pub target: String,
+    pub started: DateTimeAsMicroseconds,
+    pub finished: Option<DateTimeAsMicroseconds>,
+    pub state: ParquetExportJobState,
+}
+
+// Last export job of each topic. Only one job of the topic can run at a time
+pub struct ParquetExportJobs {
+    jobs: Mutex<BTreeMap<String, ParquetExportJob>>,
+}
+
+impl ParquetExportJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    // Returns false if export of the topic is already running
+    pub async fn try_start(
+        &self,
+        topic_id: &str,
+        target: String,
+        now: DateTimeAsMicroseconds,
+    ) -> bool {
+        let mut write_access = self.jobs.lock().await;
+
+        if let Some(job) = write_access.get(topic_id) {
+            if let ParquetExportJobState::Running = job.state {
+                return false;
+            }
+        }
+
+        write_access.insert(
+            topic_id.to_string(),
+            ParquetExportJob {
+                topic_id: topic_id.to_string(),
+                target,
+                started: now,
+                finished: None,
+                state: ParquetExportJobState::Running,
+            },
+        );
+
+        true
+    }
+
+    pub async fn finish(
+        &self,
+        topic_id: &str,
+        state: ParquetExportJobState,
+        now: DateTimeAsMicroseconds,
+    ) {
+        let mut write_access = self.jobs.lock().await;
+
+        if let Some(job) = write_access.get_mut(topic_id) {
+            job.finished = Some(now);
+            job.state = state;
+        }
+    }
+
+    pub async fn get_all(&self) -> Vec<ParquetExportJob> {
+        let read_access = self.jobs.lock().await;
+        read_access.values().cloned().collect()
+    }
+}
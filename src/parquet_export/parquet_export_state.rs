@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// Position of incremental export of the topic
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParquetExportState {
+    #[serde(rename = "lastMessageId")]
+    pub last_message_id: i64,
+    #[serde(rename = "updated")]
+    pub updated: String,
+}
+
+impl ParquetExportState {
+    pub fn get_file_path(topic_id: &str) -> String {
+        format!("{}/export-state.json", topic_id)
+    }
+}
@@ -0,0 +1,6 @@
+mod replica_state;
+mod replication_event;
+mod replication_hub;
+pub use replica_state::*;
+pub use replication_event::*;
+pub use replication_hub::*;
@@ -0,0 +1,168 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicI64, Ordering},
+    Mutex,
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+pub struct ReplicaLag {
+    pub events: i64,
+    pub duration_ms: i64,
+}
+
+// State of the secondary instance. Position is the session of the primary and the sequence
+// of the last applied event. Secondary stays read-only until it is promoted
+pub struct ReplicaState {
+    primary_url: String,
+    promoted: AtomicBool,
+    connected: AtomicBool,
+    // Secondary is not in sync until messages missed by a gap are backfilled from the primary.
+    // It does not serve reads and is not promoted meanwhile
+    in_sync: AtomicBool,
+    session_id: Mutex<String>,
+    applied_sequence: AtomicI64,
+    // Moment the last applied event was accepted by the primary (unix microseconds)
+    applied_created: AtomicI64,
+    primary_sequence: AtomicI64,
+    // Moment the primary sent the last event or heartbeat (unix microseconds)
+    primary_created: AtomicI64,
+}
+
+impl ReplicaState {
+    pub fn new(primary_url: String) -> Self {
+        Self {
+            primary_url,
+            promoted: AtomicBool::new(false),
+            connected: AtomicBool::new(false),
+            in_sync: AtomicBool::new(false),
+            session_id: Mutex::new(String::new()),
+            applied_sequence: AtomicI64::new(0),
+            applied_created: AtomicI64::new(0),
+            primary_sequence: AtomicI64::new(0),
+            primary_created: AtomicI64::new(0),
+        }
+    }
+
+    pub fn get_primary_url(&self) -> &str {
+        self.primary_url.as_str()
+    }
+
+    pub fn is_promoted(&self) -> bool {
+        self.promoted.load(Ordering::SeqCst)
+    }
+
+    // Returns false if it is already promoted
+    pub fn promote(&self) -> bool {
+        !self.promoted.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::SeqCst);
+    }
+
+    pub fn is_in_sync(&self) -> bool {
+        self.in_sync.load(Ordering::SeqCst)
+    }
+
+    pub fn set_in_sync(&self, in_sync: bool) {
+        self.in_sync.store(in_sync, Ordering::SeqCst);
+    }
+
+    pub fn get_position(&self) -> (String, i64) {
+        let session_id = self.session_id.lock().unwrap();
+        (
+            session_id.to_string(),
+            self.applied_sequence.load(Ordering::SeqCst),
+        )
+    }
+
+    // Events replayed from the backlog are older than the last heartbeat
+    pub fn update_primary(&self, last_sequence: i64, created: DateTimeAsMicroseconds) {
+        self.primary_sequence.store(last_sequence, Ordering::SeqCst);
+        self.primary_created
+            .fetch_max(created.unix_microseconds, Ordering::SeqCst);
+    }
+
+    pub fn event_applied(&self, session_id: &str, sequence: i64, created: DateTimeAsMicroseconds) {
+        let mut current_session_id = self.session_id.lock().unwrap();
+
+        if current_session_id.as_str() != session_id {
+            *current_session_id = session_id.to_string();
+        }
+
+        self.applied_sequence.store(sequence, Ordering::SeqCst);
+        self.applied_created
+            .store(created.unix_microseconds, Ordering::SeqCst);
+    }
+
+    // Nothing is behind if all events known by the primary are applied
+    pub fn get_lag(&self) -> ReplicaLag {
+        let applied_sequence = self.applied_sequence.load(Ordering::SeqCst);
+        let primary_sequence = self.primary_sequence.load(Ordering::SeqCst);
+
+        let events = (primary_sequence - applied_sequence).max(0);
+
+        let duration_ms = if events == 0 {
+            0
+        } else {
+            let applied_created = self.applied_created.load(Ordering::SeqCst);
+            let primary_created = self.primary_created.load(Ordering::SeqCst);
+            (primary_created - applied_created).max(0) / 1000
+        };
+
+        ReplicaLag {
+            events,
+            duration_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::ReplicaState;
+
+    #[test]
+    fn test_lag() {
+        let state = ReplicaState::new("http://primary:7124".to_string());
+
+        state.update_primary(10, DateTimeAsMicroseconds::new(5_000_000));
+        state.event_applied("session", 7, DateTimeAsMicroseconds::new(3_000_000));
+
+        let lag = state.get_lag();
+        assert_eq!(lag.events, 3);
+        assert_eq!(lag.duration_ms, 2000);
+        assert_eq!(state.get_position(), ("session".to_string(), 7));
+
+        state.event_applied("session", 10, DateTimeAsMicroseconds::new(4_000_000));
+
+        let lag = state.get_lag();
+        assert_eq!(lag.events, 0);
+        assert_eq!(lag.duration_ms, 0);
+    }
+
+    #[test]
+    fn test_not_in_sync_until_synced() {
+        let state = ReplicaState::new("http://primary:7124".to_string());
+
+        assert!(!state.is_in_sync());
+
+        state.set_in_sync(true);
+        assert!(state.is_in_sync());
+    }
+
+    #[test]
+    fn test_promote_once() {
+        let state = ReplicaState::new("http://primary:7124".to_string());
+
+        assert!(!state.is_promoted());
+        assert!(state.promote());
+        assert!(!state.promote());
+        assert!(state.is_promoted());
+    }
+}
@@ -0,0 +1,48 @@
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::topics_snapshot::{TopicSnapshotProtobufModel, TopicsSnapshotProtobufModelV2};
+
+#[derive(Debug, Clone)]
+pub enum ReplicationEventData {
+    NewMessages {
+        topic_id: String,
+        messages: Vec<MessageProtobufModel>,
+    },
+    TopicsSnapshot(Vec<TopicSnapshotProtobufModel>),
+    UpsertTopicSnapshot(TopicSnapshotProtobufModel),
+    DeleteQueueSnapshot {
+        topic_id: String,
+        queue_id: String,
+    },
+    DeleteTopic {
+        topic_id: String,
+        message_id: i64,
+        gc_after: i64,
+    },
+    RestoreTopic {
+        topic_id: String,
+    },
+    RollbackTopicsSnapshot(TopicsSnapshotProtobufModelV2),
+}
+
+impl ReplicationEventData {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReplicationEventData::NewMessages { .. } => "new_messages",
+            ReplicationEventData::TopicsSnapshot(_) => "topics_snapshot",
+            ReplicationEventData::UpsertTopicSnapshot(_) => "upsert_topic_snapshot",
+            ReplicationEventData::DeleteQueueSnapshot { .. } => "delete_queue_snapshot",
+            ReplicationEventData::DeleteTopic { .. } => "delete_topic",
+            ReplicationEventData::RestoreTopic { .. } => "restore_topic",
+            ReplicationEventData::RollbackTopicsSnapshot(_) => "rollback_topics_snapshot",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicationEvent {
+    pub sequence: i64,
+    pub created: DateTimeAsMicroseconds,
+    pub data: ReplicationEventData,
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::broadcast;
+
+use super::{ReplicationEvent, ReplicationEventData};
+
+const CHANNEL_SIZE: usize = 1024;
+
+pub struct ReplicationSubscription {
+    // false if events after the requested sequence are not in the backlog anymore
+    // or the primary is restarted. Secondary gets the whole topics snapshot instead
+    pub resumed: bool,
+    pub last_sequence: i64,
+    pub backlog: Vec<Arc<ReplicationEvent>>,
+    pub receiver: broadcast::Receiver<Arc<ReplicationEvent>>,
+}
+
+struct ReplicationHubInner {
+    last_sequence: i64,
+    backlog: VecDeque<Arc<ReplicationEvent>>,
+}
+
+// Keeps the last published events, so a secondary resumes from its sequence after reconnect.
+// Sequence starts from 1 on every start of the service, so it is valid within the session only
+pub struct ReplicationHub {
+    session_id: String,
+    backlog_size: usize,
+    inner: Mutex<ReplicationHubInner>,
+    sender: broadcast::Sender<Arc<ReplicationEvent>>,
+}
+
+impl ReplicationHub {
+    pub fn new(session_id: String, backlog_size: usize) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_SIZE);
+
+        Self {
+            session_id,
+            backlog_size,
+            inner: Mutex::new(ReplicationHubInner {
+                last_sequence: 0,
+                backlog: VecDeque::new(),
+            }),
+            sender,
+        }
+    }
+
+    pub fn get_session_id(&self) -> &str {
+        self.session_id.as_str()
+    }
+
+    pub fn get_last_sequence(&self) -> i64 {
+        self.inner.lock().unwrap().last_sequence
+    }
+
+    pub fn get_subscribers_amount(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    pub fn publish(&self, data: ReplicationEventData) {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.last_sequence += 1;
+
+        let event = Arc::new(ReplicationEvent {
+            sequence: inner.last_sequence,
+            created: DateTimeAsMicroseconds::now(),
+            data,
+        });
+
+        inner.backlog.push_back(event.clone());
+
+        while inner.backlog.len() > self.backlog_size {
+            inner.backlog.pop_front();
+        }
+
+        // Error means there are no subscribers. Event stays in the backlog
+        let _ = self.sender.send(event);
+    }
+
+    // Receiver is created under the same lock as the backlog is copied,
+    // so no event is lost or repeated between them
+    pub fn subscribe(&self, session_id: &str, from_sequence: i64) -> ReplicationSubscription {
+        let inner = self.inner.lock().unwrap();
+
+        let receiver = self.sender.subscribe();
+
+        let first_sequence = match inner.backlog.front() {
+            Some(event) => event.sequence,
+            None => inner.last_sequence + 1,
+        };
+
+        let resumed = session_id == self.session_id
+            && from_sequence + 1 >= first_sequence
+            && from_sequence <= inner.last_sequence;
+
+        let backlog = if resumed {
+            inner
+                .backlog
+                .iter()
+                .filter(|itm| itm.sequence > from_sequence)
+                .cloned()
+                .collect()
+        } else {
+            vec![]
+        };
+
+        ReplicationSubscription {
+            resumed,
+            last_sequence: inner.last_sequence,
+            backlog,
+            receiver,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delete_queue(queue_id: &str) -> ReplicationEventData {
+        ReplicationEventData::DeleteQueueSnapshot {
+            topic_id: "orders".to_string(),
+            queue_id: queue_id.to_string(),
+        }
+    }
+
+    fn get_sequences(subscription: &ReplicationSubscription) -> Vec<i64> {
+        subscription
+            .backlog
+            .iter()
+            .map(|itm| itm.sequence)
+            .collect()
+    }
+
+    #[test]
+    fn test_resume_from_backlog() {
+        let hub = ReplicationHub::new("session".to_string(), 3);
+
+        for queue_id in ["q1", "q2", "q3", "q4"] {
+            hub.publish(delete_queue(queue_id));
+        }
+
+        let subscription = hub.subscribe("session", 2);
+        assert!(subscription.resumed);
+        assert_eq!(subscription.last_sequence, 4);
+        assert_eq!(get_sequences(&subscription), vec![3, 4]);
+
+        let subscription = hub.subscribe("session", 4);
+        assert!(subscription.resumed);
+        assert!(subscription.backlog.is_empty());
+
+        // Event 1 is evicted from the backlog
+        let subscription = hub.subscribe("session", 0);
+        assert!(!subscription.resumed);
+        assert!(subscription.backlog.is_empty());
+    }
+
+    #[test]
+    fn test_other_session_is_not_resumed() {
+        let hub = ReplicationHub::new("session".to_string(), 10);
+
+        hub.publish(delete_queue("q1"));
+
+        assert!(!hub.subscribe("", 0).resumed);
+        assert!(!hub.subscribe("previous-session", 1).resumed);
+        assert!(!hub.subscribe("session", 2).resumed);
+        assert!(hub.subscribe("session", 0).resumed);
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_receives_new_events() {
+        let hub = ReplicationHub::new("session".to_string(), 10);
+
+        let mut subscription = hub.subscribe("session", 0);
+        assert!(subscription.resumed);
+        assert_eq!(hub.get_subscribers_amount(), 1);
+
+        hub.publish(delete_queue("q1"));
+
+        let event = subscription.receiver.recv().await.unwrap();
+        assert_eq!(event.sequence, 1);
+        assert_eq!(hub.get_last_sequence(), 1);
+    }
+}
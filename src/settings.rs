@@ -1,14 +1,34 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
 
 use my_azure_page_blob_ext::MyAzurePageBlobStorageWithRetries;
 use my_azure_storage_sdk::{page_blob::AzurePageBlobStorage, AzureStorageConnection};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use serde::{Deserialize, Serialize};
 use tokio::{fs::File, io::AsyncReadExt};
 
-use crate::topics_snapshot::page_blob_storage::TopicsSnapshotPageBlobStorage;
+use crate::audit_log::AuditLog;
+use crate::encryption::{EncryptionKeyProvider, LocalKeyFileProvider, PayloadEncryption};
+use crate::file_storage::{BlobAccount, FileStorage};
+use crate::header_index::HeaderIndexes;
+use crate::leader_lease::LeaderLease;
+use crate::message_pages::{SubPageCodec, SubPageCodecs, ZstdDictionary, DEFAULT_ZSTD_LEVEL};
+use crate::replication::{ReplicaState, ReplicationHub};
+use crate::topics_snapshot::{
+    page_blob_storage::TopicsSnapshotPageBlobStorage, TopicsSnapshotHistory, TopicsSnapshotSlots,
+};
 
 pub const PAGE_BLOB_MAX_PAGES_TO_UPLOAD_PER_ROUND_TRIP: usize = 1024 * 1024 * 3 / 512;
 
+const DEFAULT_SNAPSHOT_HISTORY_SIZE: usize = 60;
+const DEFAULT_SNAPSHOT_HISTORY_INTERVAL_SEC: u64 = 60;
+const DEFAULT_LEADER_LEASE_DURATION_SEC: u64 = 30;
+const DEFAULT_DELETED_TOPIC_GRACE_PERIOD_SEC: u64 = 60 * 60 * 24;
+const DEFAULT_REPLICATION_BACKLOG_SIZE: usize = 10_000;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SettingsModel {
     #[serde(rename = "TopicsConnectionString")]
@@ -21,23 +41,364 @@ pub struct SettingsModel {
     pub max_response_records_amount: usize,
     #[serde(rename = "DeleteTopicSecretKey")]
     pub delete_topic_secret_key: String,
+    // Grace period of topics deleted by HTTP without gracePeriodSec
+    #[serde(rename = "DeletedTopicGracePeriodSec")]
+    pub deleted_topic_grace_period_sec: Option<u64>,
+    // Rollback of topics snapshot is disabled if it is not set
+    #[serde(rename = "RollbackSnapshotSecretKey")]
+    pub rollback_snapshot_secret_key: Option<String>,
+    #[serde(rename = "OtlpEndpoint")]
+    pub otlp_endpoint: Option<String>,
+    #[serde(rename = "SnapshotHistorySize")]
+    pub snapshot_history_size: Option<usize>,
+    #[serde(rename = "SnapshotHistoryIntervalSec")]
+    pub snapshot_history_interval_sec: Option<u64>,
+    #[serde(rename = "DefaultCodec")]
+    pub default_codec: Option<CodecSettingsModel>,
+    #[serde(rename = "TopicCodecs")]
+    pub topic_codecs: Option<HashMap<String, CodecSettingsModel>>,
+    #[serde(rename = "EncryptionKeyFile")]
+    pub encryption_key_file: Option<String>,
+    #[serde(rename = "EncryptedTopics")]
+    pub encrypted_topics: Option<HashMap<String, u32>>,
+    #[serde(rename = "HeaderIndexes")]
+    pub header_indexes: Option<HashMap<String, String>>,
+    #[serde(rename = "ParquetExport")]
+    pub parquet_export: Option<FileStorageSettingsModel>,
+    #[serde(rename = "Backup")]
+    pub backup: Option<FileStorageSettingsModel>,
+    #[serde(rename = "LeaderLease")]
+    pub leader_lease: Option<LeaderLeaseSettingsModel>,
+    #[serde(rename = "Replication")]
+    pub replication: Option<ReplicationSettingsModel>,
+    #[serde(rename = "Audit")]
+    pub audit: Option<AuditSettingsModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuditSettingsModel {
+    // Api keys are written as HMAC with this secret. Keys are not told apart if it is not set
+    #[serde(rename = "IdentitySecret")]
+    pub identity_secret: Option<String>,
+    // Audit log is not served by HTTP if it is not set
+    #[serde(rename = "ReadSecretKey")]
+    pub read_secret_key: Option<String>,
+    // Sealed segments are kept forever if it is not set
+    #[serde(rename = "RetainedSegments")]
+    pub retained_segments: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReplicationSettingsModel {
+    // Set on the secondary instance only
+    #[serde(rename = "PrimaryGrpcUrl")]
+    pub primary_grpc_url: Option<String>,
+    #[serde(rename = "BacklogSize")]
+    pub backlog_size: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LeaderLeaseSettingsModel {
+    #[serde(rename = "InstanceId")]
+    pub instance_id: Option<String>,
+    #[serde(rename = "LocalDir")]
+    pub local_dir: Option<String>,
+    #[serde(rename = "BlobContainer")]
+    pub blob_container: Option<String>,
+    #[serde(rename = "LeaseDurationSec")]
+    pub lease_duration_sec: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileStorageSettingsModel {
+    #[serde(rename = "LocalDir")]
+    pub local_dir: Option<String>,
+    #[serde(rename = "BlobContainer")]
+    pub blob_container: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CodecSettingsModel {
+    #[serde(rename = "Codec")]
+    pub codec: String,
+    #[serde(rename = "Level")]
+    pub level: Option<i32>,
+    #[serde(rename = "Dictionary")]
+    pub dictionary: Option<String>,
+    #[serde(rename = "TrainDictionary")]
+    pub train_dictionary: Option<bool>,
+}
+
+impl CodecSettingsModel {
+    pub fn to_codec(&self) -> SubPageCodec {
+        match self.codec.as_str() {
+            "zip" => SubPageCodec::Zip,
+            "lz4" => SubPageCodec::Lz4,
+            "zstd" => SubPageCodec::Zstd {
+                level: self.level.unwrap_or(DEFAULT_ZSTD_LEVEL),
+                dictionary: self.dictionary.as_ref().map(|file_name| {
+                    let data = std::fs::read(file_name).unwrap_or_else(|err| {
+                        panic!("Can not read zstd dictionary {}. Err: {:?}", file_name, err)
+                    });
+
+                    Arc::new(ZstdDictionary::new(data))
+                }),
+            },
+            _ => panic!(
+                "Unknown codec {}. Supported codecs are: zip, zstd, lz4",
+                self.codec
+            ),
+        }
+    }
 }
 
 impl SettingsModel {
-    pub async fn get_topics_snapshot_repository(&self) -> TopicsSnapshotPageBlobStorage {
-        let connection =
-            AzureStorageConnection::from_conn_string(self.topics_connection_string.as_str());
-        let page_blob = AzurePageBlobStorage::new(
-            Arc::new(connection),
-            "topics".to_string(),
-            "topicsdata".to_string(),
+    pub async fn get_topics_snapshot_repository(&self) -> TopicsSnapshotSlots {
+        let connection = Arc::new(AzureStorageConnection::from_conn_string(
+            self.topics_connection_string.as_str(),
+        ));
+
+        TopicsSnapshotSlots::new(
+            create_topics_page_blob(&connection, "topicsdata-a").await,
+            create_topics_page_blob(&connection, "topicsdata-b").await,
+            create_topics_page_blob(&connection, "topicsdata").await,
         )
-        .await;
+    }
+
+    pub async fn get_topics_snapshot_history(&self) -> TopicsSnapshotHistory {
+        let connection = Arc::new(AzureStorageConnection::from_conn_string(
+            self.topics_connection_string.as_str(),
+        ));
 
-        let page_blob =
-            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+        let history_size = self
+            .snapshot_history_size
+            .unwrap_or(DEFAULT_SNAPSHOT_HISTORY_SIZE)
+            .max(1);
 
-        TopicsSnapshotPageBlobStorage::new(page_blob)
+        let mut slots = Vec::with_capacity(history_size);
+
+        for slot_no in 0..history_size {
+            let blob_name = format!("topicsdata-history-{:03}", slot_no);
+            slots.push(create_topics_page_blob(&connection, blob_name.as_str()).await);
+        }
+
+        let interval = Duration::from_secs(
+            self.snapshot_history_interval_sec
+                .unwrap_or(DEFAULT_SNAPSHOT_HISTORY_INTERVAL_SEC),
+        );
+
+        TopicsSnapshotHistory::load(slots, interval).await
+    }
+
+    pub async fn get_audit_log(&self) -> AuditLog {
+        let connection = Arc::new(AzureStorageConnection::from_conn_string(
+            self.topics_connection_string.as_str(),
+        ));
+
+        let retained_segments = self.audit.as_ref().and_then(|itm| itm.retained_segments);
+
+        AuditLog::load(connection, retained_segments).await
+    }
+
+    pub fn get_sub_page_codecs(&self) -> SubPageCodecs {
+        let default_codec = match &self.default_codec {
+            Some(codec) => codec.to_codec(),
+            None => SubPageCodec::Zip,
+        };
+
+        let mut result = SubPageCodecs::new(default_codec);
+
+        if let Some(topic_codecs) = &self.topic_codecs {
+            for (topic_id, codec) in topic_codecs {
+                result.set_topic_codec(topic_id, codec.to_codec());
+
+                if codec.train_dictionary.unwrap_or(false) {
+                    if codec.codec != "zstd" {
+                        panic!(
+                            "Dictionary can be trained only for zstd codec. Topic: {}",
+                            topic_id
+                        );
+                    }
+
+                    result.enable_dictionary_training(topic_id);
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn get_payload_encryption(&self) -> PayloadEncryption {
+        let key_provider: Option<Arc<dyn EncryptionKeyProvider>> = match &self.encryption_key_file {
+            Some(file_name) => {
+                let provider = LocalKeyFileProvider::load(file_name).unwrap_or_else(|err| {
+                    panic!("Can not load encryption keys. {}", err);
+                });
+                Some(Arc::new(provider))
+            }
+            None => None,
+        };
+
+        let mut result = PayloadEncryption::new(key_provider);
+
+        if let Some(encrypted_topics) = &self.encrypted_topics {
+            for (topic_id, key_id) in encrypted_topics {
+                // Trained dictionary keeps fragments of messages and is stored as it is
+                let train_dictionary = self
+                    .topic_codecs
+                    .as_ref()
+                    .and_then(|itm| itm.get(topic_id))
+                    .and_then(|itm| itm.train_dictionary)
+                    .unwrap_or(false);
+
+                if train_dictionary {
+                    panic!(
+                        "Dictionary can not be trained for encrypted topic {}",
+                        topic_id
+                    );
+                }
+
+                if let Err(err) = result.set_topic_key(topic_id, *key_id) {
+                    panic!(
+                        "Can not set encryption key to topic {}. Err: {:?}",
+                        topic_id, err
+                    );
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn get_header_indexes(&self) -> HeaderIndexes {
+        let mut result = HeaderIndexes::new();
+
+        if let Some(header_indexes) = &self.header_indexes {
+            for (topic_id, header_key) in header_indexes {
+                result.set_header_key(topic_id, header_key);
+            }
+        }
+
+        result
+    }
+
+    // Lease file is written only if it is not changed since it was read. Blob container is in the archive
+    // storage account and is written with If-Match by the account key. Local dir is for a single host only
+    pub fn get_leader_lease(&self) -> Option<LeaderLease> {
+        let settings = self.leader_lease.as_ref()?;
+
+        let storage = match (&settings.local_dir, &settings.blob_container) {
+            (Some(local_dir), _) => FileStorage::LocalDir(local_dir.into()),
+            (None, Some(container)) => {
+                let account = BlobAccount::from_conn_string(self.archive_connection_string.as_str())
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "LeaderLease in BlobContainer {} requires AccountName and AccountKey in ArchiveConnectionString",
+                            container
+                        )
+                    });
+
+                FileStorage::BlobContainer {
+                    connection: Arc::new(AzureStorageConnection::from_conn_string(
+                        self.archive_connection_string.as_str(),
+                    )),
+                    container: container.to_string(),
+                    account: Some(Arc::new(account)),
+                }
+            }
+            (None, None) => panic!("LeaderLease requires LocalDir or BlobContainer"),
+        };
+
+        let instance_id = match &settings.instance_id {
+            Some(instance_id) => instance_id.to_string(),
+            None => format!(
+                "{}-{}",
+                std::env::var("HOSTNAME").unwrap_or("persistence".to_string()),
+                std::process::id()
+            ),
+        };
+
+        let duration = Duration::from_secs(
+            settings
+                .lease_duration_sec
+                .unwrap_or(DEFAULT_LEADER_LEASE_DURATION_SEC),
+        );
+
+        Some(LeaderLease::new(storage, instance_id, duration))
+    }
+
+    // Events are published only if replication is configured. Secondary publishes applied events
+    // as well, so it serves its own secondaries once it is promoted
+    pub fn get_replication_hub(&self) -> Option<ReplicationHub> {
+        let settings = self.replication.as_ref()?;
+
+        let session_id = format!(
+            "{}-{}",
+            std::process::id(),
+            DateTimeAsMicroseconds::now().unix_microseconds
+        );
+
+        let backlog_size = settings
+            .backlog_size
+            .unwrap_or(DEFAULT_REPLICATION_BACKLOG_SIZE);
+
+        Some(ReplicationHub::new(session_id, backlog_size))
+    }
+
+    // Zero would purge the topic right away, so it is replaced by the default
+    pub fn get_deleted_topic_grace_period(&self) -> Duration {
+        let grace_period_sec = self
+            .deleted_topic_grace_period_sec
+            .filter(|itm| *itm > 0)
+            .unwrap_or(DEFAULT_DELETED_TOPIC_GRACE_PERIOD_SEC);
+
+        Duration::from_secs(grace_period_sec)
+    }
+
+    pub fn get_replica_state(&self) -> Option<ReplicaState> {
+        let primary_grpc_url = self.replication.as_ref()?.primary_grpc_url.as_ref()?;
+        Some(ReplicaState::new(primary_grpc_url.to_string()))
+    }
+
+    pub fn get_api_key_identity(&self, api_key: &str) -> String {
+        let identity_secret = self
+            .audit
+            .as_ref()
+            .and_then(|itm| itm.identity_secret.as_deref());
+
+        crate::audit_log::get_api_key_identity(identity_secret, api_key)
+    }
+
+    // Secrets are written as fingerprints. Maps are sorted, so the same settings
+    // give the same parameters on every start
+    pub fn get_audit_parameters(&self) -> String {
+        let audit = self.audit.as_ref();
+
+        format!(
+            "MaxResponseRecordsAmount={}; DeleteTopicSecretKey={}; DeletedTopicGracePeriodSec={:?}; RollbackSnapshotSecretKey={:?}; OtlpEndpoint={:?}; SnapshotHistorySize={:?}; SnapshotHistoryIntervalSec={:?}; DefaultCodec={:?}; TopicCodecs={:?}; EncryptionKeyFile={:?}; EncryptedTopics={:?}; HeaderIndexes={:?}; ParquetExport={:?}; Backup={:?}; LeaderLease={:?}; Replication={:?}; Audit.ReadSecretKey={:?}; Audit.RetainedSegments={:?}",
+            self.max_response_records_amount,
+            self.get_api_key_identity(self.delete_topic_secret_key.as_str()),
+            self.deleted_topic_grace_period_sec,
+            self.rollback_snapshot_secret_key
+                .as_ref()
+                .map(|itm| self.get_api_key_identity(itm.as_str())),
+            self.otlp_endpoint,
+            self.snapshot_history_size,
+            self.snapshot_history_interval_sec,
+            self.default_codec,
+            sorted(&self.topic_codecs),
+            self.encryption_key_file,
+            sorted(&self.encrypted_topics),
+            sorted(&self.header_indexes),
+            self.parquet_export,
+            self.backup,
+            self.leader_lease,
+            self.replication,
+            audit
+                .and_then(|itm| itm.read_secret_key.as_ref())
+                .map(|itm| self.get_api_key_identity(itm.as_str())),
+            audit.and_then(|itm| itm.retained_segments),
+        )
     }
 
     /*
@@ -50,7 +411,11 @@ impl SettingsModel {
             ".myservicebus-persistence",
         );
 
-        println!("Reading settings file {}", filename);
+        crate::app::LOGS.add_info(
+            None,
+            "Settings",
+            format!("Reading settings file {}", filename),
+        );
 
         let file = File::open(&filename).await;
 
@@ -84,3 +449,23 @@ impl SettingsModel {
         result
     }
 }
+
+fn sorted<T>(src: &Option<HashMap<String, T>>) -> Option<BTreeMap<&String, &T>> {
+    src.as_ref().map(|itm| itm.iter().collect())
+}
+
+pub async fn create_topics_page_blob(
+    connection: &Arc<AzureStorageConnection>,
+    blob_name: &str,
+) -> TopicsSnapshotPageBlobStorage {
+    let page_blob = AzurePageBlobStorage::new(
+        connection.clone(),
+        "topics".to_string(),
+        blob_name.to_string(),
+    )
+    .await;
+
+    let page_blob = MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+    TopicsSnapshotPageBlobStorage::new(page_blob)
+}
@@ -0,0 +1,67 @@
+use opentelemetry::{propagation::Extractor, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::settings::SettingsModel;
+
+const SERVICE_NAME: &str = "my-sb-persistence";
+
+pub fn init(settings: &SettingsModel) {
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let endpoint = match &settings.otlp_endpoint {
+        Some(endpoint) => endpoint,
+        None => return,
+    };
+
+    crate::app::LOGS.add_info(
+        None,
+        "Telemetry",
+        format!("Exporting traces to {}", endpoint),
+    );
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint.as_str()),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+            opentelemetry_sdk::Resource::new(vec![KeyValue::new("service.name", SERVICE_NAME)]),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .unwrap();
+
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+}
+
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+pub fn create_grpc_span(request: &tonic::codegen::http::Request<()>) -> tracing::Span {
+    let parent_context = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+
+    let span = tracing::info_span!("grpc", rpc.method = request.uri().path());
+    span.set_parent(parent_context);
+    span
+}
+
+struct HeaderExtractor<'s>(&'s tonic::codegen::http::HeaderMap);
+
+impl<'s> Extractor for HeaderExtractor<'s> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
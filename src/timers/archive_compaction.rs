@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+
+use crate::app::AppContext;
+
+pub struct ArchiveCompactionTimer {
+    app: Arc<AppContext>,
+}
+
+impl ArchiveCompactionTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ArchiveCompactionTimer {
+    async fn tick(&self) {
+        // Passive instance does not write to storage
+        if self.app.is_passive() {
+            return;
+        }
+
+        crate::operations::compact_archive_files(&self.app).await;
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+
+use crate::app::AppContext;
+
+pub struct HeaderIndexMergerTimer {
+    app: Arc<AppContext>,
+}
+
+impl HeaderIndexMergerTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for HeaderIndexMergerTimer {
+    async fn tick(&self) {
+        // Passive instance does not write to storage
+        if self.app.is_passive() {
+            return;
+        }
+
+        for topic_id in self.app.header_indexes.get_topics() {
+            let result = crate::operations::merge_header_index(&self.app, topic_id.as_str()).await;
+
+            if let Err(err) = result {
+                self.app.logs.add_error(
+                    Some(topic_id.as_str()),
+                    "MergeHeaderIndex",
+                    "Can not merge header index".to_string(),
+                    Some(format!("{:?}", err)),
+                );
+            }
+        }
+    }
+}
@@ -30,11 +30,13 @@ impl MyTimerTick for MetricsUpdater {
             match self.app.topics_list.get(topic_id).await {
                 Some(topic_data) => {
                     let queue_size = topic_data.pages_list.get_messages_amount_to_save().await;
+                    let last_saved_moment = topic_data.metrics.get_last_saved_moment();
                     metrics.insert(
                         topic_id.as_str(),
                         PrometheusMetricsToUpdate {
                             not_persisted_size: queue_size.amount,
                             content_size: queue_size.size,
+                            last_saved_moment: last_saved_moment.unix_microseconds / 1_000_000,
                         },
                     );
                 }
@@ -44,6 +46,7 @@ impl MyTimerTick for MetricsUpdater {
                         PrometheusMetricsToUpdate {
                             not_persisted_size: 0,
                             content_size: 0,
+                            last_saved_moment: 0,
                         },
                     );
                 }
@@ -1,5 +1,9 @@
+pub mod archive_compaction;
+pub mod header_index_merger;
 pub mod metrics_updater;
 pub mod pages_gc;
 
 pub mod save_min_index;
 pub mod topics_snapshot_saver;
+pub mod zstd_dictionaries_loader;
+pub mod zstd_dictionaries_trainer;
@@ -4,7 +4,7 @@ use crate::{
     app::AppContext, operations::OperationError, topics_snapshot::TopicSnapshotProtobufModel,
 };
 
-use rust_extensions::MyTimerTick;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
 
 pub struct PagesGcTimer {
     app: Arc<AppContext>,
@@ -19,17 +19,27 @@ impl PagesGcTimer {
 #[async_trait::async_trait]
 impl MyTimerTick for PagesGcTimer {
     async fn tick(&self) {
+        // Passive instance does not write to storage
+        if self.app.is_passive() {
+            return;
+        }
+
+        crate::operations::purge_deleted_topics(&self.app, DateTimeAsMicroseconds::now()).await;
+
         let topics_snapshot = self.app.topics_snapshot.get().await;
 
-        gc_pages(self.app.clone(), &topics_snapshot.snapshot.data)
-            .await
-            .unwrap();
+        let topics = topics_snapshot
+            .topics
+            .values()
+            .filter(|itm| !topics_snapshot.is_deleted(itm.topic_id.as_str()));
+
+        gc_pages(self.app.clone(), topics).await.unwrap();
     }
 }
 
 async fn gc_pages(
     app: Arc<AppContext>,
-    topics: &Vec<TopicSnapshotProtobufModel>,
+    topics: impl Iterator<Item = &Arc<TopicSnapshotProtobufModel>>,
 ) -> Result<(), OperationError> {
     for topic_snapshot in topics {
         let topic_data = app.topics_list.get(topic_snapshot.topic_id.as_str()).await;
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use rust_extensions::MyTimerTick;
+use rust_extensions::{MyTimerTick, StopWatch};
 
 use crate::app::AppContext;
 
@@ -17,10 +17,22 @@ impl SaveMinIndexTimer {
 #[async_trait::async_trait]
 impl MyTimerTick for SaveMinIndexTimer {
     async fn tick(&self) {
+        // Passive instance does not write to storage
+        if self.app.is_passive() {
+            return;
+        }
+
         let topics_snapshot = self.app.topics_list.get_all().await;
         for topic_data in &topics_snapshot {
             for index in topic_data.yearly_index_by_minute.get_all().await {
+                let mut sw = StopWatch::new();
+                sw.start();
+
                 index.flush_to_storage().await;
+
+                sw.pause();
+
+                self.app.metrics_keeper.observe_index_flush(sw.duration());
             }
         }
     }
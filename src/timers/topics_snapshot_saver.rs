@@ -17,9 +17,14 @@ impl TopicsSnapshotSaverTimer {
 #[async_trait::async_trait]
 impl MyTimerTick for TopicsSnapshotSaverTimer {
     async fn tick(&self) {
+        // Passive instance does not write to storage
+        if self.app.is_passive() {
+            return;
+        }
+
         self.app
             .topics_snapshot
-            .flush_topics_snapshot_to_blob()
+            .flush_topics_snapshot_to_blob(&self.app.metrics_keeper)
             .await;
     }
 }
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+
+use crate::app::AppContext;
+
+// Retries loading dictionaries of the topics which failed to load at startup
+// or were created afterwards. Loaded topics are skipped without reading the storage
+pub struct ZstdDictionariesLoaderTimer {
+    app: Arc<AppContext>,
+}
+
+impl ZstdDictionariesLoaderTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ZstdDictionariesLoaderTimer {
+    async fn tick(&self) {
+        if !self.app.app_states.is_initialized() {
+            return;
+        }
+
+        crate::operations::load_all_zstd_dictionaries(&self.app).await;
+    }
+}
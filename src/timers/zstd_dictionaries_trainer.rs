@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+
+use crate::app::AppContext;
+
+pub struct ZstdDictionariesTrainerTimer {
+    app: Arc<AppContext>,
+}
+
+impl ZstdDictionariesTrainerTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ZstdDictionariesTrainerTimer {
+    async fn tick(&self) {
+        // Passive instance does not write to storage
+        if self.app.is_passive() {
+            return;
+        }
+
+        for topic_id in self.app.codecs.get_topics_to_train() {
+            let result = crate::operations::train_zstd_dictionary(
+                &self.app,
+                topic_id.as_str(),
+                DateTimeAsMicroseconds::now(),
+            )
+            .await;
+
+            if let Err(err) = result {
+                self.app.logs.add_error(
+                    Some(topic_id.as_str()),
+                    "TrainZstdDictionary",
+                    "Can not train zstd dictionary".to_string(),
+                    Some(format!("{:?}", err)),
+                );
+            }
+        }
+    }
+}
@@ -4,7 +4,6 @@ use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::sorted_vec::EntityWithStrKey;
 
 use crate::{
-    archive_storage::ArchiveStorageList,
     index_by_minute::IndexByMinuteList,
     message_pages::{PagesList, SubPage, SubPageInner},
 };
@@ -16,7 +15,6 @@ pub struct TopicData {
     pub pages_list: PagesList,
     pub metrics: TopicDataMetrics,
     pub yearly_index_by_minute: IndexByMinuteList,
-    pub archive_pages_list: ArchiveStorageList,
 }
 
 impl EntityWithStrKey for TopicData {
@@ -32,7 +30,6 @@ impl TopicData {
             pages_list: PagesList::new(),
             metrics: TopicDataMetrics::new(),
             yearly_index_by_minute: IndexByMinuteList::new(),
-            archive_pages_list: ArchiveStorageList::new(),
         }
     }
 
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc};
 
 use rust_extensions::sorted_vec::SortedVecOfArcWithStrKey;
 use tokio::sync::RwLock;
@@ -82,6 +82,28 @@ impl TopicsDataList {
         write_access.data.remove(topic_id);
     }
 
+    // Returns false if topic was not marked as deleted
+    pub async fn restore(&self, topic_id: &str) -> bool {
+        let mut write_access = self.data.write().await;
+        write_access.deleted.remove(topic_id).is_some()
+    }
+
+    // Snapshot is restored while the write lock is held, so the topic is not seen as deleted here
+    // and restored in the snapshot at the same time. Marker stays if snapshot has nothing to restore
+    pub async fn restore_with<TResult>(
+        &self,
+        topic_id: &str,
+        restore_snapshot: impl Future<Output = Option<TResult>>,
+    ) -> Option<TResult> {
+        let mut write_access = self.data.write().await;
+
+        let result = restore_snapshot.await?;
+
+        write_access.deleted.remove(topic_id);
+
+        Some(result)
+    }
+
     pub async fn delete(&self, topic_id: &str) -> Option<Arc<TopicData>> {
         let mut write_access = self.data.write().await;
         let result = write_access.data.remove(topic_id);
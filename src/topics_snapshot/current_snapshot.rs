@@ -1,37 +1,151 @@
-use my_logger::LogEventCtx;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+use my_azure_storage_sdk::AzureStorageError;
 use my_service_bus::abstractions::MessageId;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::app::PrometheusMetrics;
 
-use super::{page_blob_storage::TopicsSnapshotPageBlobStorage, protobuf_model::*};
+use super::{protobuf_model::*, SnapshotAnomaly, TopicsSnapshotHistory, TopicsSnapshotSlots};
 
 #[derive(Clone)]
 pub struct TopicsSnapshotData {
     pub snapshot_id: i64,
     pub last_saved_snapshot_id: i64,
-    pub snapshot: TopicsSnapshotProtobufModelV2,
+    pub topics: BTreeMap<String, Arc<TopicSnapshotProtobufModel>>,
+    pub deleted_topics: Vec<DeletedTopicProtobufModel>,
+    // Topics missing in the last accepted snapshot. They are reported once until they are back
+    disappeared_topics: HashSet<String>,
 }
 
 impl TopicsSnapshotData {
-    pub fn new(
-        data: Vec<TopicSnapshotProtobufModel>,
-        deleted_topics: Vec<DeletedTopicProtobufModel>,
-    ) -> Self {
+    pub fn new(snapshot: TopicsSnapshotProtobufModelV2) -> Self {
         Self {
-            snapshot: TopicsSnapshotProtobufModelV2 {
-                data,
-                deleted_topics,
-            },
+            topics: to_topics_map(snapshot.data),
+            deleted_topics: snapshot.deleted_topics,
             snapshot_id: 0,
             last_saved_snapshot_id: 0,
+            disappeared_topics: HashSet::new(),
+        }
+    }
+
+    pub fn to_protobuf_model(&self) -> TopicsSnapshotProtobufModelV2 {
+        TopicsSnapshotProtobufModelV2 {
+            data: self
+                .topics
+                .values()
+                .map(|itm| itm.as_ref().clone())
+                .collect(),
+            deleted_topics: self.deleted_topics.clone(),
+        }
+    }
+
+    // Snapshot with rejected anomalies is not applied. Disappeared topics are kept as they were.
+    // Topic which is still missing since the previous snapshot is not reported again
+    pub fn update(
+        &mut self,
+        data: Vec<TopicSnapshotProtobufModel>,
+    ) -> Result<Vec<SnapshotAnomaly>, Vec<SnapshotAnomaly>> {
+        let anomalies = super::validate_snapshot(
+            &self.topics,
+            self.deleted_topics.as_slice(),
+            data.as_slice(),
+        );
+
+        if anomalies.iter().any(|itm| itm.is_rejected()) {
+            return Err(anomalies);
         }
+
+        let mut topics = to_topics_map(data);
+        let mut disappeared_topics = HashSet::new();
+
+        for anomaly in &anomalies {
+            if let SnapshotAnomaly::TopicDisappeared(topic_id) = anomaly {
+                if let Some(topic) = self.topics.get(topic_id) {
+                    topics.insert(topic_id.to_string(), topic.clone());
+                }
+
+                disappeared_topics.insert(topic_id.to_string());
+            }
+        }
+
+        let anomalies = anomalies
+            .into_iter()
+            .filter(|itm| match itm {
+                SnapshotAnomaly::TopicDisappeared(topic_id) => {
+                    !self.disappeared_topics.contains(topic_id)
+                }
+                _ => true,
+            })
+            .collect();
+
+        self.topics = topics;
+        self.disappeared_topics = disappeared_topics;
+        self.snapshot_id += 1;
+
+        Ok(anomalies)
+    }
+
+    pub fn upsert_topic(
+        &mut self,
+        topic: TopicSnapshotProtobufModel,
+    ) -> Result<(), Vec<SnapshotAnomaly>> {
+        let current = self.topics.get(topic.topic_id.as_str());
+
+        let anomalies = super::validate_topic(current.map(|itm| itm.as_ref()), &topic);
+
+        if anomalies.len() > 0 {
+            return Err(anomalies);
+        }
+
+        self.topics
+            .insert(topic.topic_id.to_string(), Arc::new(topic));
+        self.snapshot_id += 1;
+
+        Ok(())
+    }
+
+    // Returns None if topic is not found
+    pub fn delete_queue(&mut self, topic_id: &str, queue_id: &str) -> Option<bool> {
+        let topic = self.topics.get(topic_id)?;
+
+        if !topic.queues.iter().any(|itm| itm.queue_id == queue_id) {
+            return Some(false);
+        }
+
+        let mut topic = topic.as_ref().clone();
+        topic.queues.retain(|itm| itm.queue_id != queue_id);
+
+        self.topics.insert(topic_id.to_string(), Arc::new(topic));
+        self.snapshot_id += 1;
+
+        Some(true)
+    }
+
+    pub fn remove_topic(&mut self, topic_id: &str) -> Option<Arc<TopicSnapshotProtobufModel>> {
+        let result = self.topics.remove(topic_id)?;
+        self.snapshot_id += 1;
+        Some(result)
     }
 
-    pub fn update(&mut self, data: Vec<TopicSnapshotProtobufModel>) {
-        self.snapshot.data = data;
+    pub fn rollback(&mut self, snapshot: TopicsSnapshotProtobufModelV2) {
+        self.topics = to_topics_map(snapshot.data);
+        self.deleted_topics = snapshot.deleted_topics;
         self.snapshot_id += 1;
     }
 
+    // Replaces data by the saved snapshot. There is nothing to save afterwards
+    pub fn reload(&mut self, snapshot: TopicsSnapshotProtobufModelV2) {
+        self.topics = to_topics_map(snapshot.data);
+        self.deleted_topics = snapshot.deleted_topics;
+        self.snapshot_id += 1;
+        self.last_saved_snapshot_id = self.snapshot_id;
+    }
+
     pub fn update_snapshot_id(&mut self, saved_id: i64) {
         self.last_saved_snapshot_id = saved_id;
     }
@@ -42,9 +156,7 @@ impl TopicsSnapshotData {
         message_id: MessageId,
         gc_after: DateTimeAsMicroseconds,
     ) {
-        self.snapshot
-            .deleted_topics
-            .retain(|itm| itm.topic_id != topic_id);
+        self.deleted_topics.retain(|itm| itm.topic_id != topic_id);
 
         let deleted_topic = DeletedTopicProtobufModel {
             topic_id: topic_id.to_string(),
@@ -52,13 +164,40 @@ impl TopicsSnapshotData {
             gc_after: gc_after.unix_microseconds,
         };
 
-        self.snapshot.deleted_topics.push(deleted_topic);
+        self.deleted_topics.push(deleted_topic);
+        self.snapshot_id += 1;
+    }
+
+    // Deleted topics which grace period is over. They stay in snapshot until their data is deleted
+    pub fn get_topics_to_purge(
+        &self,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<DeletedTopicProtobufModel> {
+        self.deleted_topics
+            .iter()
+            .filter(|itm| itm.gc_after <= now.unix_microseconds)
+            .cloned()
+            .collect()
+    }
+
+    // Removes purged topic together with its snapshot entry
+    pub fn remove_purged_topic(&mut self, topic_id: &str) -> Option<DeletedTopicProtobufModel> {
+        let result = self.remove_deleted_topic(topic_id)?;
+        self.topics.remove(topic_id);
+        self.snapshot_id += 1;
+        Some(result)
+    }
+
+    pub fn is_deleted(&self, topic_id: &str) -> bool {
+        self.deleted_topics
+            .iter()
+            .any(|itm| itm.topic_id == topic_id)
     }
 
     pub fn remove_deleted_topic(&mut self, topic_id: &str) -> Option<DeletedTopicProtobufModel> {
         let mut index = None;
 
-        for (idx, itm) in self.snapshot.deleted_topics.iter().enumerate() {
+        for (idx, itm) in self.deleted_topics.iter().enumerate() {
             if itm.topic_id == topic_id {
                 index = Some(idx);
                 break;
@@ -71,26 +210,99 @@ impl TopicsSnapshotData {
 
         let index = index.unwrap();
 
-        let result = self.snapshot.deleted_topics[index].clone();
+        let result = self.deleted_topics[index].clone();
 
-        self.snapshot.deleted_topics.remove(index);
+        self.deleted_topics.remove(index);
 
         Some(result)
     }
+
+    // Topic gets back to snapshot with the message id it had when it was deleted.
+    // It can not be restored after the grace period, since its data may be purged already
+    pub fn restore_deleted_topic(
+        &mut self,
+        topic_id: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<DeletedTopicProtobufModel> {
+        let deleted = self
+            .deleted_topics
+            .iter()
+            .find(|itm| itm.topic_id == topic_id)?;
+
+        if deleted.gc_after <= now.unix_microseconds {
+            return None;
+        }
+
+        let deleted = self.remove_deleted_topic(topic_id)?;
+
+        let restored = match self.topics.get(topic_id) {
+            Some(topic) => {
+                if topic.get_message_id().get_value() >= deleted.message_id {
+                    None
+                } else {
+                    Some(TopicSnapshotProtobufModel::new(
+                        topic_id.to_string(),
+                        deleted.message_id.into(),
+                        topic.queues.clone(),
+                        topic.persist,
+                    ))
+                }
+            }
+            None => Some(TopicSnapshotProtobufModel::new(
+                topic_id.to_string(),
+                deleted.message_id.into(),
+                vec![],
+                None,
+            )),
+        };
+
+        if let Some(restored) = restored {
+            self.topics.insert(topic_id.to_string(), Arc::new(restored));
+        }
+
+        self.snapshot_id += 1;
+
+        Some(deleted)
+    }
 }
 
 pub struct CurrentTopicsSnapshot {
     data: RwLock<TopicsSnapshotData>,
-    pub blob: TopicsSnapshotPageBlobStorage,
+    // Snapshot read before rollback is not written over the rolled back one
+    flush_lock: Mutex<()>,
+    pub blob: TopicsSnapshotSlots,
+    pub history: TopicsSnapshotHistory,
 }
 
 impl CurrentTopicsSnapshot {
-    pub async fn read_or_create(blob: TopicsSnapshotPageBlobStorage) -> Self {
-        let snapshot = blob.read_or_create_topics_snapshot().await.unwrap();
-        let result = snapshot.get_result();
+    // Service can not start without the snapshot, so storage errors are retried until it is read
+    pub async fn read_or_create(blob: TopicsSnapshotSlots, history: TopicsSnapshotHistory) -> Self {
+        let snapshot = loop {
+            match blob.read().await {
+                Ok(snapshot) => break snapshot,
+                Err(err) => {
+                    crate::app::LOGS.add_error(
+                        None,
+                        "TopicsSnapshot",
+                        "Can not read topics snapshot. Retrying".to_string(),
+                        Some(format!("{:?}", err)),
+                    );
+
+                    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+                }
+            }
+        };
+
+        let result = match snapshot {
+            Some(snapshot) => snapshot.get_result(),
+            None => restore_from_history(&history).await,
+        };
+
         Self {
-            data: RwLock::new(TopicsSnapshotData::new(result.data, result.deleted_topics)),
+            data: RwLock::new(TopicsSnapshotData::new(result)),
+            flush_lock: Mutex::new(()),
             blob,
+            history,
         }
     }
 
@@ -101,17 +313,38 @@ impl CurrentTopicsSnapshot {
 
     pub async fn get_topics_list(&self) -> Vec<String> {
         let read_access = self.data.read().await;
-        read_access
-            .snapshot
-            .data
-            .iter()
-            .map(|itm| itm.topic_id.clone())
-            .collect()
+        read_access.topics.keys().cloned().collect()
+    }
+
+    pub async fn update(
+        &self,
+        snapshot: Vec<TopicSnapshotProtobufModel>,
+    ) -> Result<Vec<SnapshotAnomaly>, Vec<SnapshotAnomaly>> {
+        let mut write_access = self.data.write().await;
+        write_access.update(snapshot)
     }
 
-    pub async fn update(&self, snapshot: Vec<TopicSnapshotProtobufModel>) {
+    pub async fn upsert_topic(
+        &self,
+        topic: TopicSnapshotProtobufModel,
+    ) -> Result<(), Vec<SnapshotAnomaly>> {
         let mut write_access = self.data.write().await;
-        write_access.update(snapshot);
+        write_access.upsert_topic(topic)
+    }
+
+    pub async fn delete_queue(&self, topic_id: &str, queue_id: &str) -> Option<bool> {
+        let mut write_access = self.data.write().await;
+        write_access.delete_queue(topic_id, queue_id)
+    }
+
+    pub async fn remove_topic(&self, topic_id: &str) -> Option<Arc<TopicSnapshotProtobufModel>> {
+        let mut write_access = self.data.write().await;
+        write_access.remove_topic(topic_id)
+    }
+
+    pub async fn get_topic(&self, topic_id: &str) -> Option<Arc<TopicSnapshotProtobufModel>> {
+        let read_access = self.data.read().await;
+        read_access.topics.get(topic_id).cloned()
     }
 
     pub async fn add_deleted_topic(
@@ -124,9 +357,59 @@ impl CurrentTopicsSnapshot {
         write_access.add_deleted_topic(topic_id, message_id, gc_after);
     }
 
-    pub async fn remove_deleted_topic(&self, topic_id: &str) -> Option<DeletedTopicProtobufModel> {
+    pub async fn get_topics_to_purge(
+        &self,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<DeletedTopicProtobufModel> {
+        let read_access = self.data.read().await;
+        read_access.get_topics_to_purge(now)
+    }
+
+    pub async fn remove_purged_topic(&self, topic_id: &str) -> Option<DeletedTopicProtobufModel> {
+        let mut write_access = self.data.write().await;
+        write_access.remove_purged_topic(topic_id)
+    }
+
+    pub async fn restore_deleted_topic(
+        &self,
+        topic_id: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<DeletedTopicProtobufModel> {
         let mut write_access = self.data.write().await;
-        write_access.remove_deleted_topic(topic_id)
+        write_access.restore_deleted_topic(topic_id, now)
+    }
+
+    // Data stays locked until the rolled back snapshot is written to the blob,
+    // so incoming SaveSnapshot requests wait and are validated against the rolled back version
+    pub async fn rollback(
+        &self,
+        snapshot: TopicsSnapshotProtobufModelV2,
+    ) -> Result<(), AzureStorageError> {
+        let _flush_access = self.flush_lock.lock().await;
+        let mut write_access = self.data.write().await;
+
+        self.blob.write(&snapshot).await?;
+
+        write_access.rollback(snapshot);
+
+        let snapshot_id = write_access.snapshot_id;
+        write_access.update_snapshot_id(snapshot_id);
+
+        Ok(())
+    }
+
+    // Passive instance keeps the snapshot written by the leader. Sequence of the slots is read
+    // as well, so the snapshot is written to the right slot after the takeover
+    pub async fn reload(&self) -> Result<(), AzureStorageError> {
+        let snapshot = match self.blob.read().await? {
+            Some(snapshot) => snapshot.get_result(),
+            None => return Ok(()),
+        };
+
+        let mut write_access = self.data.write().await;
+        write_access.reload(snapshot);
+
+        Ok(())
     }
 
     pub async fn update_snapshot_id_as_saved(&self, saved_id: i64) {
@@ -145,17 +428,13 @@ impl CurrentTopicsSnapshot {
 
     pub async fn get_current_message_id(&self, topic_id: &str) -> Option<MessageId> {
         let read_access = self.data.read().await;
-
-        for topic in &read_access.snapshot.data {
-            if topic.topic_id == topic_id {
-                return Some(topic.get_message_id());
-            }
-        }
-
-        None
+        let topic = read_access.topics.get(topic_id)?;
+        Some(topic.get_message_id())
     }
 
-    pub async fn flush_topics_snapshot_to_blob(&self) {
+    pub async fn flush_topics_snapshot_to_blob(&self, metrics: &PrometheusMetrics) {
+        let _flush_access = self.flush_lock.lock().await;
+
         let topics_snapshot = self.get_snapshot_if_there_are_changes().await;
 
         if topics_snapshot.is_none() {
@@ -164,37 +443,228 @@ impl CurrentTopicsSnapshot {
 
         let topics_snapshot = topics_snapshot.unwrap();
 
+        let snapshot = topics_snapshot.to_protobuf_model();
+
         let mut attempt_no = 0;
 
         loop {
-            let result = {
-                self.blob
-                    .write_topics_snapshot(&topics_snapshot.snapshot)
-                    .await
-            };
+            let result = { self.blob.write(&snapshot).await };
 
             if let Err(err) = result {
-                my_logger::LOGGER.write_error(
-                    "Write Topics Snapshot".to_string(),
+                crate::app::LOGS.add_error(
+                    None,
+                    "Write Topics Snapshot",
                     format!(
-                        "Can not snapshot with ID #{}. Attempt:{}. Err: {:?}",
-                        topics_snapshot.snapshot_id, attempt_no, err
+                        "Can not snapshot with ID #{}. Attempt:{}",
+                        topics_snapshot.snapshot_id, attempt_no
                     ),
-                    LogEventCtx::new(),
+                    Some(format!("{:?}", err)),
                 );
 
                 if attempt_no >= 5 {
+                    metrics.page_blob_failure("topics_snapshot");
                     return;
                 }
 
                 attempt_no += 1;
 
+                metrics.page_blob_retry("topics_snapshot");
+
                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             } else {
                 self.update_snapshot_id_as_saved(topics_snapshot.snapshot_id)
                     .await;
+
+                let result = self
+                    .history
+                    .add_if_interval_passed(&snapshot, DateTimeAsMicroseconds::now())
+                    .await;
+
+                if let Err(err) = result {
+                    crate::app::LOGS.add_error(
+                        None,
+                        "Write Topics Snapshot History",
+                        "Can not write topics snapshot history".to_string(),
+                        Some(format!("{:?}", err)),
+                    );
+
+                    metrics.page_blob_failure("topics_snapshot_history");
+                }
+
                 return;
             }
         }
     }
 }
+
+// There is no valid snapshot in slots. It is either the first start or both slots are corrupted
+async fn restore_from_history(history: &TopicsSnapshotHistory) -> TopicsSnapshotProtobufModelV2 {
+    let last_version = history.get_list().await.into_iter().next();
+
+    match last_version {
+        Some(item) => {
+            crate::app::LOGS.add_error(
+                None,
+                "TopicsSnapshot",
+                format!(
+                    "No valid topics snapshot found. Restored from history version {}",
+                    item.version
+                ),
+                None,
+            );
+
+            item.snapshot.clone().unwrap_or_default()
+        }
+        None => TopicsSnapshotProtobufModelV2::default(),
+    }
+}
+
+fn to_topics_map(
+    data: Vec<TopicSnapshotProtobufModel>,
+) -> BTreeMap<String, Arc<TopicSnapshotProtobufModel>> {
+    data.into_iter()
+        .map(|itm| (itm.topic_id.to_string(), Arc::new(itm)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::AsMessageId;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::topics_snapshot::{
+        QueueSnapshotProtobufModel, TopicSnapshotProtobufModel, TopicsSnapshotProtobufModelV2,
+    };
+
+    use super::TopicsSnapshotData;
+
+    fn topic(topic_id: &str, message_id: i64, queues: &[&str]) -> TopicSnapshotProtobufModel {
+        TopicSnapshotProtobufModel::new(
+            topic_id.to_string(),
+            message_id.as_message_id(),
+            queues
+                .iter()
+                .map(|queue_id| QueueSnapshotProtobufModel {
+                    queue_id: queue_id.to_string(),
+                    ranges: vec![],
+                    queue_type: 0,
+                })
+                .collect(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_restore_deleted_topic() {
+        let mut data = TopicsSnapshotData::new(TopicsSnapshotProtobufModelV2::default());
+
+        data.add_deleted_topic("a", 15.as_message_id(), DateTimeAsMicroseconds::new(100));
+
+        let now = DateTimeAsMicroseconds::new(50);
+
+        assert!(data.restore_deleted_topic("unknown", now).is_none());
+
+        // Grace period is over
+        assert!(data
+            .restore_deleted_topic("a", DateTimeAsMicroseconds::new(100))
+            .is_none());
+
+        let restored = data.restore_deleted_topic("a", now).unwrap();
+        assert_eq!(restored.message_id, 15);
+
+        assert_eq!(data.deleted_topics.len(), 0);
+        assert_eq!(
+            data.topics.get("a").unwrap().get_message_id().get_value(),
+            15
+        );
+    }
+
+    #[test]
+    fn test_purge_deleted_topics() {
+        let mut data = TopicsSnapshotData::new(TopicsSnapshotProtobufModelV2 {
+            data: vec![
+                TopicSnapshotProtobufModel::new("a".to_string(), 10.as_message_id(), vec![], None),
+                TopicSnapshotProtobufModel::new("b".to_string(), 20.as_message_id(), vec![], None),
+            ],
+            deleted_topics: vec![],
+        });
+
+        data.add_deleted_topic("a", 10.as_message_id(), DateTimeAsMicroseconds::new(100));
+        data.add_deleted_topic("b", 20.as_message_id(), DateTimeAsMicroseconds::new(200));
+
+        assert!(data
+            .get_topics_to_purge(DateTimeAsMicroseconds::new(50))
+            .is_empty());
+
+        let to_purge = data.get_topics_to_purge(DateTimeAsMicroseconds::new(100));
+
+        assert_eq!(to_purge.len(), 1);
+        assert_eq!(to_purge[0].topic_id, "a");
+
+        // Topic stays in snapshot until its data is deleted
+        assert!(data.topics.get("a").is_some());
+
+        let purged = data.remove_purged_topic("a").unwrap();
+        assert_eq!(purged.message_id, 10);
+        assert!(data.remove_purged_topic("a").is_none());
+
+        assert!(data.topics.get("a").is_none());
+        assert!(data.topics.get("b").is_some());
+        assert!(data.is_deleted("b"));
+    }
+
+    #[test]
+    fn test_delta_updates() {
+        let mut data = TopicsSnapshotData::new(TopicsSnapshotProtobufModelV2 {
+            data: vec![topic("a", 10, &["q1", "q2"])],
+            deleted_topics: vec![],
+        });
+
+        data.upsert_topic(topic("b", 5, &["q1"])).unwrap();
+        assert!(data.upsert_topic(topic("a", 9, &["q1"])).is_err());
+
+        assert_eq!(data.delete_queue("a", "q2"), Some(true));
+        assert_eq!(data.delete_queue("a", "q2"), Some(false));
+        assert_eq!(data.delete_queue("c", "q1"), None);
+
+        assert_eq!(data.topics.get("a").unwrap().queues.len(), 1);
+        assert_eq!(data.snapshot_id, 2);
+
+        // Topic b disappeared from full snapshot but is kept
+        let anomalies = data.update(vec![topic("a", 11, &["q1"])]).unwrap();
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(data.topics.len(), 2);
+        assert_eq!(
+            data.topics.get("a").unwrap().get_message_id().get_value(),
+            11
+        );
+
+        // It is reported once while it keeps missing and again after it is back and missing
+        let anomalies = data.update(vec![topic("a", 12, &["q1"])]).unwrap();
+        assert_eq!(anomalies.len(), 0);
+
+        let anomalies = data
+            .update(vec![topic("a", 12, &["q1"]), topic("b", 5, &["q1"])])
+            .unwrap();
+        assert_eq!(anomalies.len(), 0);
+
+        let anomalies = data.update(vec![topic("a", 13, &["q1"])]).unwrap();
+        assert_eq!(anomalies.len(), 1);
+    }
+
+    #[test]
+    fn test_reload_has_nothing_to_save() {
+        let mut data = TopicsSnapshotData::new(TopicsSnapshotProtobufModelV2::default());
+
+        data.upsert_topic(topic("a", 5, &[])).unwrap();
+
+        data.reload(TopicsSnapshotProtobufModelV2 {
+            data: vec![topic("b", 7, &["q1"])],
+            deleted_topics: vec![],
+        });
+
+        assert!(data.topics.get("a").is_none());
+        assert!(data.topics.get("b").is_some());
+        assert_eq!(data.snapshot_id, data.last_saved_snapshot_id);
+    }
+}
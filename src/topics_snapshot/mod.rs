@@ -3,3 +3,11 @@ pub mod page_blob_storage;
 #[allow(non_snake_case)]
 mod protobuf_model;
 pub use protobuf_model::*;
+mod snapshot_diff;
+pub use snapshot_diff::*;
+mod snapshot_history;
+pub use snapshot_history::*;
+mod snapshot_slots;
+pub use snapshot_slots::*;
+mod snapshot_validation;
+pub use snapshot_validation::*;
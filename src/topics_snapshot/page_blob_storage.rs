@@ -73,6 +73,7 @@ impl TopicsSnapshotPageBlobStorage {
         );
     }
 
+    #[tracing::instrument(skip_all)]
     pub async fn read_or_create_topics_snapshot(
         &self,
     ) -> Result<TopicsSnapshotResult, AzureStorageError> {
@@ -81,7 +82,16 @@ impl TopicsSnapshotPageBlobStorage {
             Ok(result) => {
                 self.size
                     .store(result.len(), std::sync::atomic::Ordering::SeqCst);
-                return Ok(deserialize_model(result.as_slice()));
+
+                if result.len() == 0 {
+                    return Ok(TopicsSnapshotResult::default());
+                }
+
+                return get_payload(result.as_slice())
+                    .and_then(deserialize_model)
+                    .ok_or(AzureStorageError::UnknownError {
+                        msg: "Can not deserialize topics snapshot".to_string(),
+                    });
             }
             Err(err) => match err {
                 AzureStorageError::ContainerNotFound => {
@@ -97,6 +107,67 @@ impl TopicsSnapshotPageBlobStorage {
         }
     }
 
+    // Returns None if blob does not exist
+    #[tracing::instrument(skip_all)]
+    pub async fn download_content(&self) -> Result<Option<Vec<u8>>, AzureStorageError> {
+        let content = match self.page_blob.download().await {
+            Ok(content) => content,
+            Err(AzureStorageError::ContainerNotFound) => return Ok(None),
+            Err(AzureStorageError::BlobNotFound) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        self.size
+            .store(content.len(), std::sync::atomic::Ordering::SeqCst);
+
+        Ok(Some(content))
+    }
+
+    // Reads model written by write_topics_snapshot. Returns None if blob does not exist or is corrupted
+    pub async fn read_topics_snapshot(
+        &self,
+    ) -> Result<Option<TopicsSnapshotResult>, AzureStorageError> {
+        let content = match self.download_content().await? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+
+        Ok(get_payload(content.as_slice()).and_then(deserialize_model))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn read_model<TModel: Message + Default>(
+        &self,
+    ) -> Result<Option<TModel>, AzureStorageError> {
+        let content = match self.download_content().await? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+
+        // Length in the header is beyond the content, so the blob is corrupted and not just empty
+        let data = match get_payload(content.as_slice()) {
+            Some(data) => data,
+            None if content.len() == 0 => return Ok(None),
+            None => {
+                return Err(AzureStorageError::UnknownError {
+                    msg: format!("Payload of {} bytes is corrupted", content.len()),
+                })
+            }
+        };
+
+        if data.len() == 0 {
+            return Ok(None);
+        }
+
+        match prost::Message::decode(data) {
+            Ok(model) => Ok(Some(model)),
+            Err(err) => Err(AzureStorageError::UnknownError {
+                msg: format!("Can not deserialize model. Err: {:?}", err),
+            }),
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
     pub async fn write_topics_snapshot<TModel: Message>(
         &self,
         model: &TModel,
@@ -121,6 +192,15 @@ impl TopicsSnapshotPageBlobStorage {
 
         data[0..4].copy_from_slice(&len_as_bytes[0..4]);
 
+        self.write_content(data).await
+    }
+
+    pub async fn delete(&self) -> Result<(), AzureStorageError> {
+        self.page_blob.delete().await
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn write_content(&self, data: Vec<u8>) -> Result<(), AzureStorageError> {
         let page_blob_content = PageBlobContentToUpload::new(data, 0);
 
         loop {
@@ -139,6 +219,10 @@ impl TopicsSnapshotPageBlobStorage {
                 AzureStorageError::InvalidPageRange => {
                     self.update_size(&page_blob_content).await;
                 }
+                AzureStorageError::ContainerNotFound | AzureStorageError::BlobNotFound => {
+                    self.page_blob.create_if_not_exists(0, true).await?;
+                    self.size.store(0, std::sync::atomic::Ordering::SeqCst);
+                }
 
                 _ => return Err(err),
             }
@@ -158,34 +242,47 @@ pub trait TopicsSnapshotPageBlobExts {
     ) -> Result<(), AzureStorageError>;
 }
 
-fn deserialize_model(content: &[u8]) -> TopicsSnapshotResult {
-    if content.len() == 0 {
-        return TopicsSnapshotResult::default();
+fn get_payload(content: &[u8]) -> Option<&[u8]> {
+    if content.len() < 4 {
+        return None;
     }
-    let mut array = [0u8; 4];
-    let slice = &content[..4];
 
-    array.copy_from_slice(slice);
+    let mut array = [0u8; 4];
+    array.copy_from_slice(&content[..4]);
 
     let data_size = u32::from_le_bytes(array) as usize;
 
-    let data = &content[4..data_size + 4];
+    if content.len() < data_size + 4 {
+        return None;
+    }
+
+    Some(&content[4..data_size + 4])
+}
 
+pub fn deserialize_model(data: &[u8]) -> Option<TopicsSnapshotResult> {
     let result: Result<TopicsSnapshotProtobufModelV2, prost::DecodeError> =
         prost::Message::decode(data);
 
     match result {
         Ok(msg) => {
-            println!(
-                "Loaded topic snapshot V2. Topics amount is: {}. Deleted topics amount is: {}",
-                msg.data.len(),
-                msg.deleted_topics.len()
+            crate::app::LOGS.add_info(
+                None,
+                "TopicsSnapshot",
+                format!(
+                    "Loaded topic snapshot V2. Topics amount is: {}. Deleted topics amount is: {}",
+                    msg.data.len(),
+                    msg.deleted_topics.len()
+                ),
             );
 
-            return msg.into();
+            return Some(msg.into());
         }
         Err(_) => {
-            println!("Can not deserialize V2 topics. Trying to deserialize V1");
+            crate::app::LOGS.add_warning(
+                None,
+                "TopicsSnapshot",
+                "Can not deserialize V2 topics. Trying to deserialize V1".to_string(),
+            );
         }
     }
 
@@ -194,15 +291,26 @@ fn deserialize_model(content: &[u8]) -> TopicsSnapshotResult {
 
     match result {
         Ok(msg) => {
-            println!(
-                "Loaded topic snapshot V1. Topics amount is: {}",
-                msg.data.len()
+            crate::app::LOGS.add_info(
+                None,
+                "TopicsSnapshot",
+                format!(
+                    "Loaded topic snapshot V1. Topics amount is: {}",
+                    msg.data.len()
+                ),
             );
 
-            return msg.into();
+            Some(msg.into())
         }
-        Err(_) => {
-            panic!("Can not deserialize V2 topics. Trying to deserialize V1");
+        Err(err) => {
+            crate::app::LOGS.add_error(
+                None,
+                "TopicsSnapshot",
+                "Can not deserialize topics snapshot".to_string(),
+                Some(format!("{:?}", err)),
+            );
+
+            None
         }
     }
 }
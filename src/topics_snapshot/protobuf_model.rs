@@ -14,6 +14,16 @@ pub struct TopicsSnapshotProtobufModelV2 {
     pub deleted_topics: Vec<DeletedTopicProtobufModel>,
 }
 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TopicsSnapshotHistoryItemProtobufModel {
+    #[prost(int64, tag = "1")]
+    pub version: i64,
+    #[prost(int64, tag = "2")]
+    pub created: i64,
+    #[prost(message, optional, tag = "3")]
+    pub snapshot: Option<TopicsSnapshotProtobufModelV2>,
+}
+
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TopicSnapshotProtobufModel {
     #[prost(string, tag = "1")]
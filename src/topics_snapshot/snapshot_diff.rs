@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use super::{QueueSnapshotProtobufModel, TopicSnapshotProtobufModel};
+
+#[derive(Debug)]
+pub struct QueueSnapshotDiff {
+    pub queue_id: String,
+    pub ranges_before: Option<Vec<(i64, i64)>>,
+    pub ranges_after: Option<Vec<(i64, i64)>>,
+}
+
+#[derive(Debug)]
+pub struct TopicSnapshotDiff {
+    pub topic_id: String,
+    pub message_id_before: Option<i64>,
+    pub message_id_after: Option<i64>,
+    pub queues: Vec<QueueSnapshotDiff>,
+}
+
+// Returns only topics which are added, removed or changed between two snapshots
+pub fn diff_topics(
+    before: &[TopicSnapshotProtobufModel],
+    after: &[TopicSnapshotProtobufModel],
+) -> Vec<TopicSnapshotDiff> {
+    let mut topics: BTreeMap<
+        &str,
+        (
+            Option<&TopicSnapshotProtobufModel>,
+            Option<&TopicSnapshotProtobufModel>,
+        ),
+    > = BTreeMap::new();
+
+    for topic in before {
+        topics.entry(topic.topic_id.as_str()).or_default().0 = Some(topic);
+    }
+
+    for topic in after {
+        topics.entry(topic.topic_id.as_str()).or_default().1 = Some(topic);
+    }
+
+    let mut result = Vec::new();
+
+    for (topic_id, (before, after)) in topics {
+        let queues = diff_queues(
+            before.map(|itm| itm.queues.as_slice()).unwrap_or(&[]),
+            after.map(|itm| itm.queues.as_slice()).unwrap_or(&[]),
+        );
+
+        let message_id_before = before.map(|itm| itm.get_message_id().get_value());
+        let message_id_after = after.map(|itm| itm.get_message_id().get_value());
+
+        if message_id_before == message_id_after && queues.len() == 0 {
+            continue;
+        }
+
+        result.push(TopicSnapshotDiff {
+            topic_id: topic_id.to_string(),
+            message_id_before,
+            message_id_after,
+            queues,
+        });
+    }
+
+    result
+}
+
+fn diff_queues(
+    before: &[QueueSnapshotProtobufModel],
+    after: &[QueueSnapshotProtobufModel],
+) -> Vec<QueueSnapshotDiff> {
+    let mut queues: BTreeMap<&str, (Option<Vec<(i64, i64)>>, Option<Vec<(i64, i64)>>)> =
+        BTreeMap::new();
+
+    for queue in before {
+        queues.entry(queue.queue_id.as_str()).or_default().0 = Some(get_ranges(queue));
+    }
+
+    for queue in after {
+        queues.entry(queue.queue_id.as_str()).or_default().1 = Some(get_ranges(queue));
+    }
+
+    let mut result = Vec::new();
+
+    for (queue_id, (ranges_before, ranges_after)) in queues {
+        if ranges_before == ranges_after {
+            continue;
+        }
+
+        result.push(QueueSnapshotDiff {
+            queue_id: queue_id.to_string(),
+            ranges_before,
+            ranges_after,
+        });
+    }
+
+    result
+}
+
+fn get_ranges(queue: &QueueSnapshotProtobufModel) -> Vec<(i64, i64)> {
+    queue
+        .ranges
+        .iter()
+        .map(|range| {
+            (
+                range.get_from_id().get_value(),
+                range.get_to_id().get_value(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::AsMessageId;
+
+    use crate::topics_snapshot::{
+        QueueRangeProtobufModel, QueueSnapshotProtobufModel, TopicSnapshotProtobufModel,
+    };
+
+    fn topic(topic_id: &str, message_id: i64, ranges: &[(i64, i64)]) -> TopicSnapshotProtobufModel {
+        TopicSnapshotProtobufModel::new(
+            topic_id.to_string(),
+            message_id.as_message_id(),
+            vec![QueueSnapshotProtobufModel {
+                queue_id: "queue".to_string(),
+                ranges: ranges
+                    .iter()
+                    .map(|(from_id, to_id)| {
+                        QueueRangeProtobufModel::new(from_id.as_message_id(), to_id.as_message_id())
+                    })
+                    .collect(),
+                queue_type: 0,
+            }],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_diff_topics() {
+        let before = vec![
+            topic("changed", 10, &[(0, 10)]),
+            topic("removed", 5, &[(0, 5)]),
+            topic("same", 7, &[(0, 7)]),
+        ];
+
+        let after = vec![
+            topic("added", 1, &[(0, 1)]),
+            topic("changed", 10, &[]),
+            topic("same", 7, &[(0, 7)]),
+        ];
+
+        let result = super::diff_topics(before.as_slice(), after.as_slice());
+
+        assert_eq!(result.len(), 3);
+
+        assert_eq!(result[0].topic_id, "added");
+        assert_eq!(result[0].message_id_before, None);
+        assert_eq!(result[0].message_id_after, Some(1));
+
+        assert_eq!(result[1].topic_id, "changed");
+        assert_eq!(result[1].queues.len(), 1);
+        assert_eq!(result[1].queues[0].ranges_before, Some(vec![(0, 10)]));
+        assert_eq!(result[1].queues[0].ranges_after, Some(vec![]));
+
+        assert_eq!(result[2].topic_id, "removed");
+        assert_eq!(result[2].message_id_after, None);
+    }
+}
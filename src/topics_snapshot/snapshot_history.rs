@@ -0,0 +1,205 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use my_azure_storage_sdk::AzureStorageError;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+use super::{
+    page_blob_storage::TopicsSnapshotPageBlobStorage, TopicsSnapshotHistoryItemProtobufModel,
+    TopicsSnapshotProtobufModelV2,
+};
+
+// Keeps last N versions of topics snapshot. Each version is written to the slot version % N,
+// so the oldest version is overwritten by the newest one.
+pub struct TopicsSnapshotHistory {
+    slots: Vec<TopicsSnapshotPageBlobStorage>,
+    items: Mutex<BTreeMap<i64, Arc<TopicsSnapshotHistoryItemProtobufModel>>>,
+    interval: Duration,
+}
+
+impl TopicsSnapshotHistory {
+    pub async fn load(slots: Vec<TopicsSnapshotPageBlobStorage>, interval: Duration) -> Self {
+        let mut items = BTreeMap::new();
+
+        for (slot_no, slot) in slots.iter().enumerate() {
+            match slot
+                .read_model::<TopicsSnapshotHistoryItemProtobufModel>()
+                .await
+            {
+                Ok(Some(item)) => {
+                    items.insert(item.version, Arc::new(item));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    crate::app::LOGS.add_error(
+                        None,
+                        "TopicsSnapshotHistory",
+                        format!("Can not load topics snapshot history slot {}", slot_no),
+                        Some(format!("{:?}", err)),
+                    );
+                }
+            }
+        }
+
+        Self {
+            slots,
+            items: Mutex::new(items),
+            interval,
+        }
+    }
+
+    pub async fn get_list(&self) -> Vec<Arc<TopicsSnapshotHistoryItemProtobufModel>> {
+        let read_access = self.items.lock().await;
+        read_access.values().rev().cloned().collect()
+    }
+
+    pub async fn get(&self, version: i64) -> Option<Arc<TopicsSnapshotHistoryItemProtobufModel>> {
+        let read_access = self.items.lock().await;
+        read_access.get(&version).cloned()
+    }
+
+    pub async fn add_if_interval_passed(
+        &self,
+        snapshot: &TopicsSnapshotProtobufModelV2,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<Option<i64>, AzureStorageError> {
+        {
+            let read_access = self.items.lock().await;
+
+            if let Some(last) = read_access.values().last() {
+                if now.unix_microseconds - last.created < self.interval.as_micros() as i64 {
+                    return Ok(None);
+                }
+            }
+        }
+
+        let version = self.add(snapshot, now).await?;
+        Ok(Some(version))
+    }
+
+    pub async fn add(
+        &self,
+        snapshot: &TopicsSnapshotProtobufModelV2,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<i64, AzureStorageError> {
+        let mut write_access = self.items.lock().await;
+
+        let version = match write_access.keys().last() {
+            Some(last_version) => last_version + 1,
+            None => 1,
+        };
+
+        let item = TopicsSnapshotHistoryItemProtobufModel {
+            version,
+            created: now.unix_microseconds,
+            snapshot: Some(snapshot.clone()),
+        };
+
+        let slot_no = version as usize % self.slots.len();
+
+        self.slots[slot_no].write_topics_snapshot(&item).await?;
+
+        write_access.insert(version, Arc::new(item));
+
+        while write_access.len() > self.slots.len() {
+            let first_version = *write_access.keys().next().unwrap();
+            write_access.remove(&first_version);
+        }
+
+        Ok(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use my_azure_page_blob_ext::MyAzurePageBlobStorageWithRetries;
+    use my_azure_storage_sdk::{page_blob::AzurePageBlobStorage, AzureStorageConnection};
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::topics_snapshot::{
+        page_blob_storage::TopicsSnapshotPageBlobStorage, TopicsSnapshotProtobufModelV2,
+    };
+
+    use super::TopicsSnapshotHistory;
+
+    async fn create_slots(
+        connection: &Arc<AzureStorageConnection>,
+        amount: usize,
+    ) -> Vec<TopicsSnapshotPageBlobStorage> {
+        let mut result = Vec::new();
+
+        for slot_no in 0..amount {
+            let page_blob = AzurePageBlobStorage::new(
+                connection.clone(),
+                "topics".to_string(),
+                format!("topicsdata-history-{:03}", slot_no),
+            )
+            .await;
+
+            let page_blob =
+                MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+            result.push(TopicsSnapshotPageBlobStorage::new(page_blob));
+        }
+
+        result
+    }
+
+    #[tokio::test]
+    async fn test_oldest_versions_are_overwritten() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let history = TopicsSnapshotHistory::load(
+            create_slots(&connection, 2).await,
+            Duration::from_secs(60),
+        )
+        .await;
+
+        let snapshot = TopicsSnapshotProtobufModelV2::default();
+
+        assert_eq!(
+            history
+                .add_if_interval_passed(&snapshot, DateTimeAsMicroseconds::new(0))
+                .await
+                .unwrap(),
+            Some(1)
+        );
+
+        // Interval is not passed yet
+        assert_eq!(
+            history
+                .add_if_interval_passed(&snapshot, DateTimeAsMicroseconds::new(1_000_000))
+                .await
+                .unwrap(),
+            None
+        );
+
+        history
+            .add(&snapshot, DateTimeAsMicroseconds::new(2_000_000))
+            .await
+            .unwrap();
+
+        history
+            .add(&snapshot, DateTimeAsMicroseconds::new(3_000_000))
+            .await
+            .unwrap();
+
+        let history = TopicsSnapshotHistory::load(
+            create_slots(&connection, 2).await,
+            Duration::from_secs(60),
+        )
+        .await;
+
+        let versions: Vec<i64> = history
+            .get_list()
+            .await
+            .iter()
+            .map(|itm| itm.version)
+            .collect();
+
+        assert_eq!(versions, vec![3, 2]);
+        assert_eq!(history.get(2).await.unwrap().created, 2_000_000);
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use my_azure_storage_sdk::AzureStorageError;
+use prost::Message;
+
+use super::page_blob_storage::{TopicsSnapshotPageBlobStorage, TopicsSnapshotResult};
+
+const SLOT_MAGIC: u32 = 0x5353_5450;
+// magic: u32, sequence: u64, crc: u32, payload_len: u32
+const SLOT_HEADER_SIZE: usize = 20;
+
+const READ_ATTEMPTS: usize = 5;
+const READ_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// Topics snapshot is written to slots A and B in turn. Each slot has a sequence number and CRC,
+// so a torn write spoils only one slot and the previous version is still available in the other one.
+pub struct TopicsSnapshotSlots {
+    slots: [TopicsSnapshotPageBlobStorage; 2],
+    // Blob with [len][protobuf] format which was used before slots were introduced
+    legacy: TopicsSnapshotPageBlobStorage,
+    sequence: AtomicU64,
+}
+
+impl TopicsSnapshotSlots {
+    pub fn new(
+        slot_a: TopicsSnapshotPageBlobStorage,
+        slot_b: TopicsSnapshotPageBlobStorage,
+        legacy: TopicsSnapshotPageBlobStorage,
+    ) -> Self {
+        Self {
+            slots: [slot_a, slot_b],
+            legacy,
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    // Returns the newest valid snapshot. None if there is no valid snapshot at all.
+    // Storage errors are retried and returned, so an older slot is used only if the newer one is corrupted
+    #[tracing::instrument(skip_all)]
+    pub async fn read(&self) -> Result<Option<TopicsSnapshotResult>, AzureStorageError> {
+        let mut candidates = Vec::new();
+
+        for (slot_no, slot) in self.slots.iter().enumerate() {
+            let content = read_with_retries(format!("slot {}", slot_no).as_str(), || {
+                slot.download_content()
+            })
+            .await?;
+
+            match content {
+                Some(content) => match decode_slot(content.as_slice()) {
+                    Some((sequence, payload)) => {
+                        candidates.push((sequence, payload.to_vec()));
+                    }
+                    None => {
+                        if content.iter().any(|b| *b != 0) {
+                            crate::app::LOGS.add_warning(
+                                None,
+                                "TopicsSnapshotSlots",
+                                format!(
+                                    "Topics snapshot slot {} is corrupted. Skipping it",
+                                    slot_no
+                                ),
+                            );
+                        }
+                    }
+                },
+                None => {}
+            }
+        }
+
+        candidates.sort_by(|a, b| b.0.cmp(&a.0));
+
+        if let Some((sequence, _)) = candidates.first() {
+            self.sequence.store(*sequence, Ordering::SeqCst);
+        }
+
+        for (_, payload) in candidates {
+            if let Some(result) = super::page_blob_storage::deserialize_model(payload.as_slice()) {
+                return Ok(Some(result));
+            }
+        }
+
+        read_with_retries("legacy blob", || self.legacy.read_topics_snapshot()).await
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn write<TModel: Message>(&self, model: &TModel) -> Result<(), AzureStorageError> {
+        let mut payload = Vec::new();
+
+        if let Err(err) = prost::Message::encode(model, &mut payload) {
+            return Err(AzureStorageError::UnknownError {
+                msg: format!("Can not serialize model. Err: {:?}", err),
+            });
+        }
+
+        // Sequence is committed only after the slot is written, so a retry after a failed write goes
+        // to the same slot and the other one keeps the last good snapshot
+        let sequence = self.sequence.load(Ordering::SeqCst) + 1;
+
+        let slot = &self.slots[sequence as usize % self.slots.len()];
+
+        slot.write_content(encode_slot(sequence, payload.as_slice()))
+            .await?;
+
+        self.sequence.store(sequence, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+async fn read_with_retries<
+    TResult,
+    TFuture: Future<Output = Result<TResult, AzureStorageError>>,
+>(
+    name: &str,
+    read: impl Fn() -> TFuture,
+) -> Result<TResult, AzureStorageError> {
+    let mut attempt_no = 1;
+
+    loop {
+        match read().await {
+            Ok(result) => return Ok(result),
+            Err(err) => {
+                if attempt_no >= READ_ATTEMPTS {
+                    return Err(err);
+                }
+
+                crate::app::LOGS.add_warning(
+                    None,
+                    "TopicsSnapshotSlots",
+                    format!(
+                        "Can not read topics snapshot {}. Attempt: {}. Err: {:?}",
+                        name, attempt_no, err
+                    ),
+                );
+
+                attempt_no += 1;
+
+                tokio::time::sleep(READ_RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+fn calc_crc(sequence: u64, payload: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&sequence.to_le_bytes());
+    hasher.update(payload);
+    hasher.finalize()
+}
+
+fn encode_slot(sequence: u64, payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(SLOT_HEADER_SIZE + payload.len());
+    result.extend_from_slice(&SLOT_MAGIC.to_le_bytes());
+    result.extend_from_slice(&sequence.to_le_bytes());
+    result.extend_from_slice(&calc_crc(sequence, payload).to_le_bytes());
+    result.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    result.extend_from_slice(payload);
+    result
+}
+
+fn decode_slot(content: &[u8]) -> Option<(u64, &[u8])> {
+    if content.len() < SLOT_HEADER_SIZE {
+        return None;
+    }
+
+    let magic = u32::from_le_bytes(content[0..4].try_into().unwrap());
+
+    if magic != SLOT_MAGIC {
+        return None;
+    }
+
+    let sequence = u64::from_le_bytes(content[4..12].try_into().unwrap());
+    let crc = u32::from_le_bytes(content[12..16].try_into().unwrap());
+    let payload_len = u32::from_le_bytes(content[16..20].try_into().unwrap()) as usize;
+
+    let payload = content.get(SLOT_HEADER_SIZE..SLOT_HEADER_SIZE + payload_len)?;
+
+    if calc_crc(sequence, payload) != crc {
+        return None;
+    }
+
+    Some((sequence, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use my_azure_page_blob_ext::MyAzurePageBlobStorageWithRetries;
+    use my_azure_storage_sdk::{page_blob::AzurePageBlobStorage, AzureStorageConnection};
+    use my_service_bus::abstractions::AsMessageId;
+
+    use crate::topics_snapshot::{
+        page_blob_storage::TopicsSnapshotPageBlobStorage, TopicSnapshotProtobufModel,
+        TopicsSnapshotProtobufModelV2,
+    };
+
+    use super::*;
+
+    async fn create_storage(
+        connection: &Arc<AzureStorageConnection>,
+        blob_name: &str,
+    ) -> TopicsSnapshotPageBlobStorage {
+        let page_blob = AzurePageBlobStorage::new(
+            connection.clone(),
+            "topics".to_string(),
+            blob_name.to_string(),
+        )
+        .await;
+
+        let page_blob =
+            MyAzurePageBlobStorageWithRetries::new(page_blob, 3, Duration::from_secs(1));
+
+        TopicsSnapshotPageBlobStorage::new(page_blob)
+    }
+
+    async fn create_slots(connection: &Arc<AzureStorageConnection>) -> TopicsSnapshotSlots {
+        TopicsSnapshotSlots::new(
+            create_storage(connection, "topicsdata-a").await,
+            create_storage(connection, "topicsdata-b").await,
+            create_storage(connection, "topicsdata").await,
+        )
+    }
+
+    fn create_snapshot(message_id: i64) -> TopicsSnapshotProtobufModelV2 {
+        TopicsSnapshotProtobufModelV2 {
+            data: vec![TopicSnapshotProtobufModel::new(
+                "Test".to_string(),
+                message_id.as_message_id(),
+                vec![],
+                None,
+            )],
+            deleted_topics: vec![],
+        }
+    }
+
+    fn get_message_id(result: TopicsSnapshotResult) -> i64 {
+        result.get_result().data[0].get_message_id().get_value()
+    }
+
+    #[test]
+    fn test_decode_detects_corruption() {
+        let mut content = encode_slot(5, "Hello".as_bytes());
+
+        let (sequence, payload) = decode_slot(content.as_slice()).unwrap();
+        assert_eq!(sequence, 5);
+        assert_eq!(payload, "Hello".as_bytes());
+
+        content[SLOT_HEADER_SIZE] = b'J';
+        assert!(decode_slot(content.as_slice()).is_none());
+
+        // Torn write: payload is shorter than header says
+        let content = encode_slot(5, "Hello".as_bytes());
+        assert!(decode_slot(&content[..content.len() - 1]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_newest_valid_slot_is_used() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let slots = create_slots(&connection).await;
+
+        assert!(slots.read().await.unwrap().is_none());
+
+        slots.write(&create_snapshot(1)).await.unwrap();
+        slots.write(&create_snapshot(2)).await.unwrap();
+
+        let slots = create_slots(&connection).await;
+        assert_eq!(get_message_id(slots.read().await.unwrap().unwrap()), 2);
+
+        // Sequence 3 goes to slot B where sequence 1 was. Simulating a torn write of it
+        let slot_b = create_storage(&connection, "topicsdata-b").await;
+        let mut content = encode_slot(3, "Corrupted".as_bytes());
+        content.truncate(SLOT_HEADER_SIZE + 2);
+        slot_b.write_content(content).await.unwrap();
+
+        let slots = create_slots(&connection).await;
+        assert_eq!(get_message_id(slots.read().await.unwrap().unwrap()), 2);
+
+        // Next write continues the sequence of the valid slot
+        slots.write(&create_snapshot(3)).await.unwrap();
+
+        let slots = create_slots(&connection).await;
+        assert_eq!(get_message_id(slots.read().await.unwrap().unwrap()), 3);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_snapshot_is_read_if_there_are_no_slots() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let legacy = create_storage(&connection, "topicsdata").await;
+        legacy
+            .write_topics_snapshot(&create_snapshot(10))
+            .await
+            .unwrap();
+
+        let slots = create_slots(&connection).await;
+        assert_eq!(get_message_id(slots.read().await.unwrap().unwrap()), 10);
+    }
+}
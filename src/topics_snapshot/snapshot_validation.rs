@@ -0,0 +1,303 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+use super::{DeletedTopicProtobufModel, QueueSnapshotProtobufModel, TopicSnapshotProtobufModel};
+
+#[derive(Debug, Clone)]
+pub enum SnapshotAnomaly {
+    DuplicatedTopic(String),
+    DuplicatedQueue {
+        topic_id: String,
+        queue_id: String,
+    },
+    MessageIdWentBackwards {
+        topic_id: String,
+        before: i64,
+        after: i64,
+    },
+    InvertedRange {
+        topic_id: String,
+        queue_id: String,
+        from_id: i64,
+        to_id: i64,
+    },
+    OverlappingRanges {
+        topic_id: String,
+        queue_id: String,
+    },
+    // Topic is not in the incoming snapshot and is not deleted. It is kept as it was
+    TopicDisappeared(String),
+}
+
+impl SnapshotAnomaly {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotAnomaly::DuplicatedTopic(_) => "duplicated_topic",
+            SnapshotAnomaly::DuplicatedQueue { .. } => "duplicated_queue",
+            SnapshotAnomaly::MessageIdWentBackwards { .. } => "message_id_went_backwards",
+            SnapshotAnomaly::InvertedRange { .. } => "inverted_range",
+            SnapshotAnomaly::OverlappingRanges { .. } => "overlapping_ranges",
+            SnapshotAnomaly::TopicDisappeared(_) => "topic_disappeared",
+        }
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        match self {
+            SnapshotAnomaly::TopicDisappeared(_) => false,
+            _ => true,
+        }
+    }
+
+    pub fn get_details(&self) -> String {
+        match self {
+            SnapshotAnomaly::DuplicatedTopic(topic_id) => {
+                format!("Topic {} is duplicated", topic_id)
+            }
+            SnapshotAnomaly::DuplicatedQueue { topic_id, queue_id } => {
+                format!("Queue {}/{} is duplicated", topic_id, queue_id)
+            }
+            SnapshotAnomaly::MessageIdWentBackwards {
+                topic_id,
+                before,
+                after,
+            } => format!(
+                "Topic {} message id went backwards from {} to {}",
+                topic_id, before, after
+            ),
+            SnapshotAnomaly::InvertedRange {
+                topic_id,
+                queue_id,
+                from_id,
+                to_id,
+            } => format!(
+                "Queue {}/{} has inverted range {}-{}",
+                topic_id, queue_id, from_id, to_id
+            ),
+            SnapshotAnomaly::OverlappingRanges { topic_id, queue_id } => {
+                format!("Queue {}/{} has overlapping ranges", topic_id, queue_id)
+            }
+            SnapshotAnomaly::TopicDisappeared(topic_id) => {
+                format!("Topic {} is missing in snapshot. Keeping it", topic_id)
+            }
+        }
+    }
+}
+
+pub fn validate_snapshot(
+    current: &BTreeMap<String, Arc<TopicSnapshotProtobufModel>>,
+    deleted_topics: &[DeletedTopicProtobufModel],
+    incoming: &[TopicSnapshotProtobufModel],
+) -> Vec<SnapshotAnomaly> {
+    let mut result = Vec::new();
+
+    let mut incoming_ids = HashSet::new();
+
+    for topic in incoming {
+        if !incoming_ids.insert(topic.topic_id.as_str()) {
+            result.push(SnapshotAnomaly::DuplicatedTopic(topic.topic_id.to_string()));
+            continue;
+        }
+
+        let current_topic = current.get(topic.topic_id.as_str());
+        validate_topic_to(current_topic.map(|itm| itm.as_ref()), topic, &mut result);
+    }
+
+    for topic_id in current.keys() {
+        if incoming_ids.contains(topic_id.as_str()) {
+            continue;
+        }
+
+        if deleted_topics.iter().any(|itm| &itm.topic_id == topic_id) {
+            continue;
+        }
+
+        result.push(SnapshotAnomaly::TopicDisappeared(topic_id.to_string()));
+    }
+
+    result
+}
+
+pub fn validate_topic(
+    current: Option<&TopicSnapshotProtobufModel>,
+    incoming: &TopicSnapshotProtobufModel,
+) -> Vec<SnapshotAnomaly> {
+    let mut result = Vec::new();
+    validate_topic_to(current, incoming, &mut result);
+    result
+}
+
+fn validate_topic_to(
+    current: Option<&TopicSnapshotProtobufModel>,
+    incoming: &TopicSnapshotProtobufModel,
+    result: &mut Vec<SnapshotAnomaly>,
+) {
+    if let Some(current) = current {
+        let before = current.get_message_id().get_value();
+        let after = incoming.get_message_id().get_value();
+
+        if after < before {
+            result.push(SnapshotAnomaly::MessageIdWentBackwards {
+                topic_id: incoming.topic_id.to_string(),
+                before,
+                after,
+            });
+        }
+    }
+
+    validate_queues(
+        incoming.topic_id.as_str(),
+        incoming.queues.as_slice(),
+        result,
+    );
+}
+
+fn validate_queues(
+    topic_id: &str,
+    queues: &[QueueSnapshotProtobufModel],
+    result: &mut Vec<SnapshotAnomaly>,
+) {
+    let mut queue_ids = HashSet::new();
+
+    for queue in queues {
+        if !queue_ids.insert(queue.queue_id.as_str()) {
+            result.push(SnapshotAnomaly::DuplicatedQueue {
+                topic_id: topic_id.to_string(),
+                queue_id: queue.queue_id.to_string(),
+            });
+            continue;
+        }
+
+        let mut ranges = Vec::with_capacity(queue.ranges.len());
+
+        for range in &queue.ranges {
+            let from_id = range.get_from_id().get_value();
+            let to_id = range.get_to_id().get_value();
+
+            // Empty range is written as from_id = to_id + 1
+            if to_id + 1 < from_id {
+                result.push(SnapshotAnomaly::InvertedRange {
+                    topic_id: topic_id.to_string(),
+                    queue_id: queue.queue_id.to_string(),
+                    from_id,
+                    to_id,
+                });
+                continue;
+            }
+
+            if from_id <= to_id {
+                ranges.push((from_id, to_id));
+            }
+        }
+
+        ranges.sort();
+
+        let overlapping = ranges.windows(2).any(|pair| pair[1].0 <= pair[0].1);
+
+        if overlapping {
+            result.push(SnapshotAnomaly::OverlappingRanges {
+                topic_id: topic_id.to_string(),
+                queue_id: queue.queue_id.to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use my_service_bus::abstractions::AsMessageId;
+
+    use crate::topics_snapshot::{
+        DeletedTopicProtobufModel, QueueRangeProtobufModel, QueueSnapshotProtobufModel,
+        TopicSnapshotProtobufModel,
+    };
+
+    fn topic(topic_id: &str, message_id: i64, ranges: &[(i64, i64)]) -> TopicSnapshotProtobufModel {
+        TopicSnapshotProtobufModel::new(
+            topic_id.to_string(),
+            message_id.as_message_id(),
+            vec![QueueSnapshotProtobufModel {
+                queue_id: "queue".to_string(),
+                ranges: ranges
+                    .iter()
+                    .map(|(from_id, to_id)| {
+                        QueueRangeProtobufModel::new(from_id.as_message_id(), to_id.as_message_id())
+                    })
+                    .collect(),
+                queue_type: 0,
+            }],
+            None,
+        )
+    }
+
+    fn to_map(
+        topics: Vec<TopicSnapshotProtobufModel>,
+    ) -> BTreeMap<String, Arc<TopicSnapshotProtobufModel>> {
+        topics
+            .into_iter()
+            .map(|itm| (itm.topic_id.to_string(), Arc::new(itm)))
+            .collect()
+    }
+
+    fn get_kinds(
+        current: &[TopicSnapshotProtobufModel],
+        deleted: &[DeletedTopicProtobufModel],
+        incoming: &[TopicSnapshotProtobufModel],
+    ) -> Vec<&'static str> {
+        super::validate_snapshot(&to_map(current.to_vec()), deleted, incoming)
+            .iter()
+            .map(|itm| itm.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_snapshot() {
+        let current = vec![topic("a", 10, &[(0, 10)])];
+        let incoming = vec![
+            topic("a", 20, &[(0, 5), (7, 20)]),
+            topic("b", 0, &[(0, -1)]),
+        ];
+
+        assert!(get_kinds(&current, &[], &incoming).is_empty());
+    }
+
+    #[test]
+    fn test_rejected_anomalies() {
+        let current = vec![topic("a", 10, &[(0, 10)])];
+
+        let incoming = vec![topic("a", 5, &[(0, 5)]), topic("a", 5, &[(0, 5)])];
+        assert_eq!(
+            get_kinds(&current, &[], &incoming),
+            vec!["message_id_went_backwards", "duplicated_topic"]
+        );
+
+        let incoming = vec![topic("a", 10, &[(5, 2)])];
+        assert_eq!(get_kinds(&current, &[], &incoming), vec!["inverted_range"]);
+
+        let incoming = vec![topic("a", 10, &[(5, 8), (0, 5)])];
+        assert_eq!(
+            get_kinds(&current, &[], &incoming),
+            vec!["overlapping_ranges"]
+        );
+    }
+
+    #[test]
+    fn test_disappeared_topics() {
+        let current = vec![topic("a", 10, &[]), topic("deleted", 10, &[])];
+
+        let deleted = vec![DeletedTopicProtobufModel {
+            topic_id: "deleted".to_string(),
+            message_id: 10,
+            gc_after: 0,
+        }];
+
+        let anomalies = super::validate_snapshot(&to_map(current), &deleted, &[]);
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].as_str(), "topic_disappeared");
+        assert!(!anomalies[0].is_rejected());
+    }
+}
@@ -4,6 +4,6 @@ eval(function(p,a,c,k,e,d){e=function(c){return(c<a?'':e(parseInt(c/a)))+((c=c%a
 
 eval(function(p,a,c,k,e,d){e=function(c){return(c<a?'':e(parseInt(c/a)))+((c=c%a)>35?String.fromCharCode(c+29):c.toString(36))};if(!''.replace(/^/,String)){while(c--)d[e(c)]=k[c]||e(c);k=[function(e){return d[e]}];e=function(){return'\\w+'};c=1};while(c--)if(k[c])p=p.replace(new RegExp('\\b'+e(c)+'\\b','g'),k[c]);return p}('9 1=(d(){d 1(){}1.F=d(h){9 3=\'<f E="D-C"><g a="B-A:z" 6="k" j="b">\'+\'<y 6="k" j="b" x="5" w="5" a="v:u;7-6:;7:t"/>\';s(9 8=0,c=h;8<c.r;8++){9 i=c[8];3+=\'<q p="\'+(i*4+2)+\'" o="\'+0+\'" n="\'+(i*4+2)+\'" m="\'+b+\'" a="7:l;7-6:2" />\'}3+=\'</g></f>\';e 3};e 1}());',42,42,'|SubPagesWidget||result|||width|stroke|_i|var|style|20|subPages_1|function|return|div|svg|subPages||height|400|blue|y2|x2|y1|x1|line|length|for|black|white|fill|ry|rx|rect|16px|size|font|widget|page|class|renderPagesWidget'.split('|'),0,{}))
 
-eval(function(p,a,c,k,e,d){e=function(c){return(c<a?'':e(parseInt(c/a)))+((c=c%a)>35?String.fromCharCode(c+29):c.toString(36))};if(!''.replace(/^/,String)){while(c--)d[e(c)]=k[c]||e(c);k=[function(e){return d[e]}];e=function(){return'\\w+'};c=1};while(c--)if(k[c])p=p.replace(new RegExp('\\b'+e(c)+'\\b','g'),k[c]);return p}('4 9=(c(){c 9(){}9.z=c(n){7 n.1X().1W(/(\\d)(?=(\\d{3})+(?!\\d))/g,\'$1 \')};9.1V=c(n){w(n<u){7 n.y(2)+"b"}n=n/u;w(n<u){7 n.y(2)+"1U"}n=n/u;w(n<u){7 n.y(2)+"1T"}n=n/u;7 n.y(2)+"1S"};9.W=c(H){4 8=\'\';j(4 6=0,M=H;6<M.i;6++){4 L=M[6];8+=\'<5><b>\'+L.1R+\'</b></5>\';j(4 l=0,k=L.1Q;l<k.i;l++){4 K=k[l];8+=\'<5 p="10-Z: 1P">\'+K.1O+\' - \'+K.1N+\'</5>\'}8+=\'<V/>\'}7 8};9.1M=c(13){4 8=\'<q p="1L: 1K%"><s>\';j(4 6=0,J=13;6<J.i;6++){4 12=J[6];8+=\'<f>\'+12+\'</f>\'}7 8+\'</s></q>\'};9.S=c(o){4 8=\'\';4 D=0;j(4 6=0,l=o.1J(c(a,b){7 a.F>b.F?1:-1});6<l.i;6++){4 e=l[6];4 E=\'\';j(4 k=0,I=e.1I;k<I.i;k++){4 Y=I[k];E+=\'<X R="11 11-1H" p="10-Z: 1G">\'+Y+\'</X>\'}4 T=m.W(e.H);j(4 x=0,G=e.U;x<G.i;x++){4 h=G[x];D+=h.v}8+=\'<s p="1F-v: 1E">\'+\'<f>\'+e.F+\'<5>1D:</5>\'+E+\'<V/><5>1C:</5>\'+m.N(e.U)+\'</f>\'+\'<f>\'+T+\'</f>\'+\'<f><5>1B 1A:\'+e.1z+\'</5>\'+\'<5>1y 1x 1w:\'+e.1v+\'</5>\'+\'<5>1u 1t:\'+e.1s+\'</5>\'+\'</f>\'+\'</s>\'}1r.1q(\'1p-B-v\').1o=9.z(D);7 8};9.C=c(o){4 Q=m.S(o);7\'<q R="q q-1n"><s><t>1m</t><t>1l</t><t>1k</t></s>\'+Q+\'</q>\'};9.1j=c(r){w(r.1i){7\'<P p="O:1h">1g 1f 1e 1d</P><5 p="O:1c">\'+m.C(r.o)+\'</5>\'}7 m.C(r.o)};9.N=c(B){4 8="";j(4 6=0,A=B;6<A.i;6++){4 h=A[6];8+=\'<5><5>1b:\'+h.1a+\'; 19:\'+h.18+\'; 17: \'+m.z(h.v)+\'</5>\'+16.15(h.14)+\'</5>\'}7 8};7 9}());',62,122,'||||var|div|_i|return|result|HtmlRenderer|||function||topic|td||page|length|for|_b|_a|this||topics|style|table||tr|th|1024|size|if|_d|toFixed|formatNumber|pages_1|pages|renderMainTable|totalPagesSize|activePagesBadges|topicId|_e|queues|_c|values_1|range|queue|queues_1|renderCachedPages|color|h1|content|class|renderLoadedPagesContent|queuesContent|loadedPages|hr|renderQueuesTableContent|span|activePage|left|margin|badge|value|values|subPages|renderPagesWidget|SubPagesWidget|Size|count|Amount|pageId|Page|gray|initialized|being|is|Application|red|initialing|renderMainContent|MessageId|Queues|Topic|striped|innerHTML|total|getElementById|document|lastSaveMoment|ago|Saved|lastSaveDur|Duration|Save|Last|messageId|Id|Current|Loaded|Active|12px|font|5px|warning|activePages|sort|100|width|compileTable|toId|fromId|10px|ranges|queueId|Gb|Mb|Kb|formatMem|replace|toString'.split('|'),0,{}))
+eval(function(p,a,c,k,e,d){e=function(c){return(c<a?'':e(parseInt(c/a)))+((c=c%a)>35?String.fromCharCode(c+29):c.toString(36))};if(!''.replace(/^/,String)){while(c--)d[e(c)]=k[c]||e(c);k=[function(e){return d[e]}];e=function(){return'\\w+'};c=1};while(c--)if(k[c])p=p.replace(new RegExp('\\b'+e(c)+'\\b','g'),k[c]);return p}('4 f=(h(){h f(){}f.B=h(n){8 n.1f().1g(/(\\d)(?=(\\d{3})+(?!\\d))/g,\'$1 \')};f.1h=h(n){w(n<u){8 n.y(2)+"b"}n=n/u;w(n<u){8 n.y(2)+"1i"}n=n/u;w(n<u){8 n.y(2)+"1j"}n=n/u;8 n.y(2)+"1k"};f.Q=h(C){4 7=\'\';l(4 5=0,D=C;5<D.k;5++){4 E=D[5];7+=\'<6><b>\'+E.1l+\'</b></6>\';l(4 o=0,p=E.1m;o<p.k;o++){4 F=p[o];7+=\'<6 q="R-S: 1n">\'+F.1o+\' - \'+F.1p+\'</6>\'}7+=\'<T/>\'}8 7};f.1q=h(U){4 7=\'<i q="1r: 1s%"><j>\';l(4 5=0,G=U;5<G.k;5++){4 V=G[5];7+=\'<9>\'+V+\'</9>\'}8 7+\'</j></i>\'};f.W=h(v){4 7=\'\';4 H=0;l(4 5=0,o=v.1t(h(a,b){8 a.z>b.z?1:-1});5<o.k;5++){4 c=o[5];4 I=\'\';l(4 p=0,J=c.1u;p<J.k;p++){4 X=J[p];I+=\'<Y K="Z Z-1v" q="R-S: 1w">\'+X+\'</Y>\'}4 10=s.Q(c.C);l(4 A=0,L=c.11;A<L.k;A++){4 t=L[A];H+=t.x}7+=\'<j q="12-x: 13">\'+\'<9>\'+c.z+\'<6>1x:</6>\'+I+\'<T/><6>1y:</6>\'+s.14(c.11)+\'</9>\'+\'<9>\'+10+\'</9>\'+\'<9><6>1z 1A:\'+c.15+\'</6>\'+\'<6>1B 1C 1D:\'+c.1E+\'</6>\'+\'<6>1F 1G:\'+c.1H+\'</6>\'+\'</9>\'+\'</j>\'}1I.1J(\'1K-M-x\').1L=f.B(H);8 7};f.N=h(v){4 16=s.W(v);8\'<i K="i i-17"><j><e>18</e><e>1M</e><e>19</e></j>\'+16+\'</i>\'};f.1a=h(m){w(!m||m.k==0){8\'\'}4 7=\'<1b>1N 1O</1b><i K="i i-17"><j><e>18</e><e>19</e><e>1c 1P</e><e>1c 1Q</e></j>\';l(4 5=0,O=m;5<O.k;5++){4 c=O[5];7+=\'<j q="12-x: 13">\'+\'<9>\'+c.z+\'</9>\'+\'<9>\'+c.15+\'</9>\'+\'<9>\'+c.1R+\'</9>\'+\'<9>\'+c.1S+\'</9>\'+\'</j>\'}8 7+\'</i>\'};f.1T=h(r){4 m=s.1a(r.m);w(r.1U){8\'<1d q="1e:1V">1W 1X 1Y 1Z</1d><6 q="1e:20">\'+m+s.N(r.v)+\'</6>\'}8 m+s.N(r.v)};f.14=h(M){4 7="";l(4 5=0,P=M;5<P.k;5++){4 t=P[5];7+=\'<6><6>21:\'+t.22+\'; 23:\'+t.24+\'; 25: \'+s.B(t.x)+\'</6>\'+26.27(t.28)+\'</6>\'}8 7};8 f}());',62,133,'||||var|_i|div|result|return|td|||topic||th|HtmlRenderer||function|table|tr|length|for|deletedTopics||_a|_b|style||this|page|1024|topics|if|size|toFixed|topicId|_d|formatNumber|queues|queues_1|queue|range|values_1|totalPagesSize|activePagesBadges|_c|class|_e|pages|renderMainTable|deletedTopics_1|pages_1|renderQueuesTableContent|margin|left|hr|values|value|renderLoadedPagesContent|activePage|span|badge|queuesContent|loadedPages|font|12px|renderCachedPages|messageId|content|striped|Topic|MessageId|renderDeletedTopics|h4|Purge|h1|color|toString|replace|formatMem|Kb|Mb|Gb|queueId|ranges|10px|fromId|toId|compileTable|width|100|sort|activePages|warning|5px|Active|Loaded|Current|Id|Last|Save|Duration|lastSaveDur|Saved|ago|lastSaveMoment|document|getElementById|total|innerHTML|Queues|Pending|purge|after|in|gcAfter|purgeIn|renderMainContent|initialing|red|Application|is|being|initialized|gray|Page|pageId|Amount|count|Size|SubPagesWidget|renderPagesWidget|subPages'.split('|'),0,{}))
 
 eval(function(p,a,c,k,e,d){e=function(c){return(c<a?'':e(parseInt(c/a)))+((c=c%a)>35?String.fromCharCode(c+29):c.toString(36))};if(!''.replace(/^/,String)){while(c--)d[e(c)]=k[c]||e(c);k=[function(e){return d[e]}];e=function(){return'\\w+'};c=1};while(c--)if(k[c])p=p.replace(new RegExp('\\b'+e(c)+'\\b','g'),k[c]);return p}('8 2=(3(){3 2(){}2.r=3(){8 4=e.U;8 5=e.T;i(1.x==4&&1.w==5)6;1.x=4;1.w=5;8 c=1.n;1.h.v(\'u\',1.m(0,0,5,4-c));1.t.v(\'u\',\'S:R; \'+1.m(0,4-c,5,c))};2.m=3(k,l,5,4){6\'l:\'+l+\'b; k:\'+k+\'b; 5:\'+5+\'b; 4:\'+4+\'b\'};2.d=3(){8 9=1;i(!1.a){1.a=j.Q(\'a\')[0];1.a.p=P.O();1.h=j.s(\'2\');1.t=j.s(\'q-N\')}1.r();i(1.7)6;1.7=M;$.L({K:\'/J/q\',I:\'H\'}).G(3(g){9.7=f;o.F(g);9.h.p=E.D(g)}).C(3(){9.7=f;o.B()})};2.7=f;2.n=A;6 2}());e.z(3(){6 2.d()},y);2.d();',57,57,'|this|main|function|height|width|return|requested|var|_this|body|px|sbHeight|background|window|false|result|layoutElement|if|document|left|top|generatePosition|statusBarHeight|HtmlStatusBar|innerHTML|status|resize|getElementById|statusBarElement|style|setAttribute|windowWidth|windowHeight|1000|setInterval|24|updateOffline|fail|renderMainContent|HtmlRenderer|updateStatusbar|then|get|type|api|url|ajax|true|bar|layout|HtmlMain|getElementsByTagName|absolute|position|innerWidth|innerHeight'.split('|'),0,{}))